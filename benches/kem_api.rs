@@ -1,7 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use criterion_cycles_per_byte::CyclesPerByte;
 
-use classic_mceliece_rust::{decapsulate, encapsulate, keypair, CRYPTO_BYTES};
+use classic_mceliece_rust::{decapsulate, encapsulate, keypair, DecapsulationKey, CRYPTO_BYTES};
 use classic_mceliece_rust::{CRYPTO_PUBLICKEYBYTES, CRYPTO_SECRETKEYBYTES};
 
 pub fn bench_complete_kem(criterion: &mut Criterion<CyclesPerByte>) {
@@ -103,9 +103,35 @@ pub fn bench_kem_dec(criterion: &mut Criterion<CyclesPerByte>) {
     });
 }
 
+pub fn bench_kem_dec_key(criterion: &mut Criterion<CyclesPerByte>) {
+    let mut rng = rand::thread_rng();
+    let mut sk_buf = [0u8; CRYPTO_SECRETKEYBYTES];
+    let mut ss_buf = [0u8; CRYPTO_BYTES];
+    let mut pk_buf;
+    #[cfg(feature = "alloc")]
+    {
+        pk_buf = Box::new([0u8; CRYPTO_PUBLICKEYBYTES]);
+    }
+    #[cfg(not(feature = "alloc"))]
+    {
+        pk_buf = [0u8; CRYPTO_PUBLICKEYBYTES];
+    }
+
+    let (pk, sk) = keypair(&mut pk_buf, &mut sk_buf, &mut rng);
+    let (ct, _) = encapsulate(&pk, &mut ss_buf, &mut rng);
+    let decapsulation_key = DecapsulationKey::new(&sk);
+
+    criterion.bench_function("kem_dec_key", |b| {
+        #[allow(unused_must_use)]
+        b.iter(|| {
+            black_box(decapsulation_key.decapsulate(&ct, &mut ss_buf));
+        })
+    });
+}
+
 criterion_group!(
     name = benches;
     config = Criterion::default().with_measurement(CyclesPerByte);
-    targets = bench_complete_kem, bench_kem_keypair, bench_kem_enc, bench_kem_dec
+    targets = bench_complete_kem, bench_kem_keypair, bench_kem_enc, bench_kem_dec, bench_kem_dec_key
 );
 criterion_main!(benches);
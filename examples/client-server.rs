@@ -12,6 +12,8 @@ use std::sync::mpsc::{self, Sender};
 use std::thread;
 
 type Error = Box<dyn std::error::Error>;
+/// A public key sent to the server together with the channel for the ciphertext response
+type Request = (Box<[u8]>, Sender<Box<[u8]>>);
 
 fn main() -> Result<(), Error> {
     let mut server_sender = spawn_server();
@@ -31,7 +33,7 @@ fn main() -> Result<(), Error> {
     Ok(())
 }
 
-fn spawn_server() -> Sender<(Box<[u8]>, Sender<Box<[u8]>>)> {
+fn spawn_server() -> Sender<Request> {
     // Convert the bytes read from the client into a `PublicKey`
     fn parse_public_key(public_key_data: &mut [u8]) -> Result<PublicKey<'_>, Error> {
         let public_key_array = <&mut [u8; CRYPTO_PUBLICKEYBYTES]>::try_from(public_key_data)?;
@@ -52,7 +54,7 @@ fn spawn_server() -> Sender<(Box<[u8]>, Sender<Box<[u8]>>)> {
         }
     }
 
-    let (sender, receiver) = mpsc::channel::<(Box<[u8]>, Sender<Box<[u8]>>)>();
+    let (sender, receiver) = mpsc::channel::<Request>();
     thread::spawn(move || {
         for (mut public_key, response_sender) in receiver.iter() {
             handle_request(&mut public_key, response_sender);
@@ -62,9 +64,7 @@ fn spawn_server() -> Sender<(Box<[u8]>, Sender<Box<[u8]>>)> {
 }
 
 /// Negotiate with `server` and return the shared secret.
-fn run_client(
-    server: &mut Sender<(Box<[u8]>, Sender<Box<[u8]>>)>,
-) -> Result<SharedSecret<'static>, Error> {
+fn run_client(server: &mut Sender<Request>) -> Result<SharedSecret<'static>, Error> {
     // Convert the bytes read from the server into a `Ciphertext`
     fn parse_ciphertext(ciphertext_data: &[u8]) -> Result<Ciphertext, Error> {
         let ciphertext_array = <[u8; CRYPTO_CIPHERTEXTBYTES]>::try_from(ciphertext_data)?;
@@ -0,0 +1,144 @@
+//! Authenticated encryption with associated data used on top of the KEM
+//!
+//! The marker types of this module select the AEAD of the [`envelope`](crate::envelope) and
+//! the `hpke` modules. Each of them is only available with the feature of the same name.
+
+use alloc::vec::Vec;
+
+use ::aead::{KeyInit, Payload};
+
+use crate::Error;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// An AEAD with 96 bit nonces and 128 bit tags.
+///
+/// This trait is implemented by the marker types [`ChaCha20Poly1305`], [`Aes128Gcm`] and
+/// [`Aes256Gcm`]. The trait is sealed and cannot be implemented outside of this crate.
+pub trait Aead: private::Sealed + Copy + Clone + core::fmt::Debug + Default + 'static {
+    /// The identifier of the AEAD in the HPKE registry of RFC 9180
+    const ID: u16;
+    /// The length of the key in bytes
+    const KEY_BYTES: usize;
+    /// The length of the nonce in bytes
+    const NONCE_BYTES: usize = NONCE_BYTES;
+    /// The length of the authentication tag in bytes
+    const TAG_BYTES: usize = 16;
+
+    /// Encrypts `plaintext` and appends the tag. `key` must have [`Aead::KEY_BYTES`] bytes.
+    #[doc(hidden)]
+    fn seal(
+        key: &[u8],
+        nonce: &[u8; NONCE_BYTES],
+        aad: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, Error>;
+
+    /// Checks the tag at the end of `ciphertext` and decrypts the rest of it.
+    #[doc(hidden)]
+    fn open(
+        key: &[u8],
+        nonce: &[u8; NONCE_BYTES],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, Error>;
+}
+
+/// The nonce length shared by all AEADs of this module
+pub(crate) const NONCE_BYTES: usize = 12;
+
+fn seal<C: ::aead::Aead + KeyInit>(
+    key: &[u8],
+    nonce: &[u8; NONCE_BYTES],
+    aad: &[u8],
+    plaintext: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let cipher = C::new_from_slice(key).expect("key has the length of the AEAD");
+    let payload = Payload {
+        msg: plaintext,
+        aad,
+    };
+    cipher
+        .encrypt(::aead::Nonce::<C>::from_slice(nonce), payload)
+        .map_err(|_| Error::Aead)
+}
+
+fn open<C: ::aead::Aead + KeyInit>(
+    key: &[u8],
+    nonce: &[u8; NONCE_BYTES],
+    aad: &[u8],
+    ciphertext: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let cipher = C::new_from_slice(key).expect("key has the length of the AEAD");
+    let payload = Payload {
+        msg: ciphertext,
+        aad,
+    };
+    cipher
+        .decrypt(::aead::Nonce::<C>::from_slice(nonce), payload)
+        .map_err(|_| Error::Aead)
+}
+
+macro_rules! define_aead {
+    ($name:ident, $feature:literal, $cipher:ty, $id:literal, $key_bytes:literal, $doc:literal) => {
+        #[doc = $doc]
+        #[cfg(feature = $feature)]
+        #[cfg_attr(docsrs, doc(cfg(feature = $feature)))]
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+        pub struct $name;
+
+        #[cfg(feature = $feature)]
+        impl private::Sealed for $name {}
+
+        #[cfg(feature = $feature)]
+        impl Aead for $name {
+            const ID: u16 = $id;
+            const KEY_BYTES: usize = $key_bytes;
+
+            fn seal(
+                key: &[u8],
+                nonce: &[u8; NONCE_BYTES],
+                aad: &[u8],
+                plaintext: &[u8],
+            ) -> Result<Vec<u8>, Error> {
+                seal::<$cipher>(key, nonce, aad, plaintext)
+            }
+
+            fn open(
+                key: &[u8],
+                nonce: &[u8; NONCE_BYTES],
+                aad: &[u8],
+                ciphertext: &[u8],
+            ) -> Result<Vec<u8>, Error> {
+                open::<$cipher>(key, nonce, aad, ciphertext)
+            }
+        }
+    };
+}
+
+define_aead!(
+    Aes128Gcm,
+    "aes-gcm",
+    aes_gcm::Aes128Gcm,
+    0x0001,
+    16,
+    "AES-128-GCM"
+);
+define_aead!(
+    Aes256Gcm,
+    "aes-gcm",
+    aes_gcm::Aes256Gcm,
+    0x0002,
+    32,
+    "AES-256-GCM"
+);
+define_aead!(
+    ChaCha20Poly1305,
+    "chacha20poly1305",
+    chacha20poly1305::ChaCha20Poly1305,
+    0x0003,
+    32,
+    "ChaCha20-Poly1305 (RFC 8439)"
+);
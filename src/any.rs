@@ -0,0 +1,494 @@
+//! Keys and ciphertexts whose parameter set is only known at runtime
+//!
+//! The types in this module wrap the statically typed [`PublicKey`], [`SecretKey`] and
+//! [`Ciphertext`] of every parameter set and carry the [`Variant`] tag with them.
+
+use rand::{CryptoRng, RngCore};
+
+use crate::error::Error;
+use crate::params::*;
+use crate::variant::{with_parameter_set, Variant};
+use crate::{generic, Ciphertext, PublicKey, SecretKey, SecretSeed, SharedSecret, CRYPTO_BYTES};
+
+macro_rules! any_types {
+    ($($name:ident),* $(,)?) => {
+        /// A [`PublicKey`] of any parameter set.
+        #[derive(Debug)]
+        #[must_use]
+        pub enum AnyPublicKey<'a> {
+            $($name(PublicKey<'a, $name>),)*
+        }
+
+        /// A [`SecretKey`] of any parameter set.
+        #[derive(Debug)]
+        #[must_use]
+        pub enum AnySecretKey<'a> {
+            $($name(SecretKey<'a, $name>),)*
+        }
+
+        /// A [`Ciphertext`] of any parameter set.
+        #[derive(Debug)]
+        #[must_use]
+        pub enum AnyCiphertext {
+            $($name(Ciphertext<$name>),)*
+        }
+
+        $(
+            impl<'a> From<PublicKey<'a, $name>> for AnyPublicKey<'a> {
+                fn from(key: PublicKey<'a, $name>) -> Self {
+                    AnyPublicKey::$name(key)
+                }
+            }
+
+            impl<'a> From<SecretKey<'a, $name>> for AnySecretKey<'a> {
+                fn from(key: SecretKey<'a, $name>) -> Self {
+                    AnySecretKey::$name(key)
+                }
+            }
+
+            impl From<Ciphertext<$name>> for AnyCiphertext {
+                fn from(ciphertext: Ciphertext<$name>) -> Self {
+                    AnyCiphertext::$name(ciphertext)
+                }
+            }
+        )*
+
+        impl AnyPublicKey<'_> {
+            /// Returns the parameter set of this key.
+            pub fn variant(&self) -> Variant {
+                match self {
+                    $(AnyPublicKey::$name(_) => Variant::$name,)*
+                }
+            }
+
+            /// Copies the key to the heap and makes it `'static`.
+            #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+            #[cfg(feature = "alloc")]
+            pub fn to_owned(&self) -> AnyPublicKey<'static> {
+                match self {
+                    $(AnyPublicKey::$name(key) => AnyPublicKey::$name(key.to_owned()),)*
+                }
+            }
+
+            /// Checks the structure of the key, see [`PublicKey::validate`].
+            pub fn validate(&self) -> Result<(), Error> {
+                match self {
+                    $(AnyPublicKey::$name(key) => key.validate(),)*
+                }
+            }
+
+            /// KEM Encapsulation with the parameter set of this key.
+            ///
+            /// See [`generic::encapsulate`]. The returned ciphertext has the same parameter
+            /// set as this key.
+            pub fn encapsulate<'shared_secret, R: CryptoRng + RngCore>(
+                &self,
+                shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
+                rng: &mut R,
+            ) -> (AnyCiphertext, SharedSecret<'shared_secret>) {
+                match self {
+                    $(AnyPublicKey::$name(key) => {
+                        let (ciphertext, shared_secret) =
+                            generic::encapsulate(key, shared_secret_buf, rng);
+                        (AnyCiphertext::$name(ciphertext), shared_secret)
+                    })*
+                }
+            }
+
+            /// Convenient wrapper around [`AnyPublicKey::encapsulate`] that stores the shared
+            /// secret on the heap and returns it with the ``'static`` lifetime.
+            #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+            #[cfg(feature = "alloc")]
+            pub fn encapsulate_boxed<R: CryptoRng + RngCore>(
+                &self,
+                rng: &mut R,
+            ) -> (AnyCiphertext, SharedSecret<'static>) {
+                match self {
+                    $(AnyPublicKey::$name(key) => {
+                        let (ciphertext, shared_secret) = generic::encapsulate_boxed(key, rng);
+                        (AnyCiphertext::$name(ciphertext), shared_secret)
+                    })*
+                }
+            }
+
+            /// Fallible KEM Encapsulation with the parameter set of this key.
+            ///
+            /// See [`generic::try_encapsulate`].
+            pub fn try_encapsulate<'shared_secret, R: CryptoRng + RngCore>(
+                &self,
+                shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
+                rng: &mut R,
+            ) -> Result<(AnyCiphertext, SharedSecret<'shared_secret>), Error> {
+                match self {
+                    $(AnyPublicKey::$name(key) => {
+                        let (ciphertext, shared_secret) =
+                            generic::try_encapsulate(key, shared_secret_buf, rng)?;
+                        Ok((AnyCiphertext::$name(ciphertext), shared_secret))
+                    })*
+                }
+            }
+
+            /// Convenient wrapper around [`AnyPublicKey::try_encapsulate`] that stores the
+            /// shared secret on the heap and returns it with the ``'static`` lifetime.
+            #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+            #[cfg(feature = "alloc")]
+            pub fn try_encapsulate_boxed<R: CryptoRng + RngCore>(
+                &self,
+                rng: &mut R,
+            ) -> Result<(AnyCiphertext, SharedSecret<'static>), Error> {
+                match self {
+                    $(AnyPublicKey::$name(key) => {
+                        let (ciphertext, shared_secret) =
+                            generic::try_encapsulate_boxed(key, rng)?;
+                        Ok((AnyCiphertext::$name(ciphertext), shared_secret))
+                    })*
+                }
+            }
+
+            /// Derandomized KEM Encapsulation with the parameter set of this key.
+            ///
+            /// See [`generic::encapsulate_derand`].
+            pub fn encapsulate_derand<'shared_secret>(
+                &self,
+                coins: &[u8; 32],
+                shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
+            ) -> Result<(AnyCiphertext, SharedSecret<'shared_secret>), Error> {
+                match self {
+                    $(AnyPublicKey::$name(key) => {
+                        let (ciphertext, shared_secret) =
+                            generic::encapsulate_derand(key, coins, shared_secret_buf)?;
+                        Ok((AnyCiphertext::$name(ciphertext), shared_secret))
+                    })*
+                }
+            }
+        }
+
+        impl AnySecretKey<'_> {
+            /// Returns the parameter set of this key.
+            pub fn variant(&self) -> Variant {
+                match self {
+                    $(AnySecretKey::$name(_) => Variant::$name,)*
+                }
+            }
+
+            /// Copies the key to the heap and makes it `'static`.
+            #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+            #[cfg(feature = "alloc")]
+            pub fn to_owned(&self) -> AnySecretKey<'static> {
+                match self {
+                    $(AnySecretKey::$name(key) => AnySecretKey::$name(key.to_owned()),)*
+                }
+            }
+
+            /// Regenerates the public key belonging to this secret key, see
+            /// [`SecretKey::public_key`].
+            #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+            #[cfg(feature = "alloc")]
+            pub fn public_key(&self) -> Result<AnyPublicKey<'static>, Error> {
+                match self {
+                    $(AnySecretKey::$name(key) => key.public_key().map(AnyPublicKey::from),)*
+                }
+            }
+
+            /// Checks the structure of the secret key, see [`SecretKey::validate`].
+            pub fn validate(&self) -> Result<(), Error> {
+                match self {
+                    $(AnySecretKey::$name(key) => key.validate(),)*
+                }
+            }
+
+            /// Checks the structure of the secret key and that it belongs to `public_key`,
+            /// see [`SecretKey::validate_with_public_key`].
+            #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+            #[cfg(feature = "alloc")]
+            pub fn validate_with_public_key(&self, public_key: &AnyPublicKey<'_>) -> Result<(), Error> {
+                match (self, public_key) {
+                    $((AnySecretKey::$name(key), AnyPublicKey::$name(public_key)) => {
+                        key.validate_with_public_key(public_key)
+                    })*
+                    _ => Err(Error::VariantMismatch {
+                        expected: self.variant(),
+                        actual: public_key.variant(),
+                    }),
+                }
+            }
+
+            /// Returns the 32 byte seed the key was expanded from, see [`SecretKey::seed`].
+            pub fn seed(&self) -> SecretSeed {
+                match self {
+                    $(AnySecretKey::$name(key) => key.seed(),)*
+                }
+            }
+
+            /// KEM Decapsulation with the parameter set of this key.
+            ///
+            /// See [`decapsulate`](crate::decapsulate). Fails if `ciphertext` belongs to
+            /// another parameter set than this key.
+            pub fn decapsulate<'shared_secret>(
+                &self,
+                ciphertext: &AnyCiphertext,
+                shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
+            ) -> Result<SharedSecret<'shared_secret>, Error> {
+                match (self, ciphertext) {
+                    $((AnySecretKey::$name(key), AnyCiphertext::$name(ciphertext)) => {
+                        Ok(generic::decapsulate(ciphertext, key, shared_secret_buf))
+                    })*
+                    _ => Err(self.mismatch(ciphertext)),
+                }
+            }
+
+            /// Convenient wrapper around [`AnySecretKey::decapsulate`] that stores the shared
+            /// secret on the heap and returns it with the ``'static`` lifetime.
+            #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+            #[cfg(feature = "alloc")]
+            pub fn decapsulate_boxed(
+                &self,
+                ciphertext: &AnyCiphertext,
+            ) -> Result<SharedSecret<'static>, Error> {
+                match (self, ciphertext) {
+                    $((AnySecretKey::$name(key), AnyCiphertext::$name(ciphertext)) => {
+                        Ok(generic::decapsulate_boxed(ciphertext, key))
+                    })*
+                    _ => Err(self.mismatch(ciphertext)),
+                }
+            }
+
+            /// Fallible KEM Decapsulation with the parameter set of this key.
+            ///
+            /// See [`generic::try_decapsulate`]. Also fails if `ciphertext` belongs to another
+            /// parameter set than this key.
+            pub fn try_decapsulate<'shared_secret>(
+                &self,
+                ciphertext: &AnyCiphertext,
+                shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
+            ) -> Result<SharedSecret<'shared_secret>, Error> {
+                match (self, ciphertext) {
+                    $((AnySecretKey::$name(key), AnyCiphertext::$name(ciphertext)) => {
+                        generic::try_decapsulate(ciphertext, key, shared_secret_buf)
+                    })*
+                    _ => Err(self.mismatch(ciphertext)),
+                }
+            }
+
+            /// Convenient wrapper around [`AnySecretKey::try_decapsulate`] that stores the
+            /// shared secret on the heap and returns it with the ``'static`` lifetime.
+            #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+            #[cfg(feature = "alloc")]
+            pub fn try_decapsulate_boxed(
+                &self,
+                ciphertext: &AnyCiphertext,
+            ) -> Result<SharedSecret<'static>, Error> {
+                match (self, ciphertext) {
+                    $((AnySecretKey::$name(key), AnyCiphertext::$name(ciphertext)) => {
+                        generic::try_decapsulate_boxed(ciphertext, key)
+                    })*
+                    _ => Err(self.mismatch(ciphertext)),
+                }
+            }
+        }
+
+        impl AnyCiphertext {
+            /// Returns the parameter set of this ciphertext.
+            pub fn variant(&self) -> Variant {
+                match self {
+                    $(AnyCiphertext::$name(_) => Variant::$name,)*
+                }
+            }
+        }
+
+        impl AsRef<[u8]> for AnyPublicKey<'_> {
+            fn as_ref(&self) -> &[u8] {
+                match self {
+                    $(AnyPublicKey::$name(key) => key.as_ref(),)*
+                }
+            }
+        }
+
+        impl AsRef<[u8]> for AnySecretKey<'_> {
+            fn as_ref(&self) -> &[u8] {
+                match self {
+                    $(AnySecretKey::$name(key) => key.as_ref(),)*
+                }
+            }
+        }
+
+        impl AsRef<[u8]> for AnyCiphertext {
+            fn as_ref(&self) -> &[u8] {
+                match self {
+                    $(AnyCiphertext::$name(ciphertext) => ciphertext.as_ref(),)*
+                }
+            }
+        }
+    };
+}
+
+any_types!(
+    Mceliece348864,
+    Mceliece348864f,
+    Mceliece460896,
+    Mceliece460896f,
+    Mceliece6688128,
+    Mceliece6688128f,
+    Mceliece6960119,
+    Mceliece6960119f,
+    Mceliece8192128,
+    Mceliece8192128f,
+);
+
+fn check_length(variant: Variant, expected: usize, bytes: &[u8]) -> Result<(), Error> {
+    if bytes.len() == expected {
+        Ok(())
+    } else {
+        Err(Error::InvalidLength {
+            variant,
+            expected,
+            actual: bytes.len(),
+        })
+    }
+}
+
+impl AnyPublicKey<'_> {
+    /// Copies `bytes` into a public key of parameter set `variant`.
+    ///
+    /// Fails if `bytes` does not have [`Variant::public_key_bytes`] bytes or is not a valid
+    /// key, see [`PublicKey::try_from_bytes`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn from_bytes(variant: Variant, bytes: &[u8]) -> Result<AnyPublicKey<'static>, Error> {
+        Ok(with_parameter_set!(variant, P => {
+            AnyPublicKey::from(PublicKey::<P>::try_from_bytes(bytes)?)
+        }))
+    }
+
+    /// The length of the key in bytes.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.variant().public_key_bytes()
+    }
+}
+
+impl AnySecretKey<'_> {
+    /// Copies `bytes` into a secret key of parameter set `variant`.
+    ///
+    /// Fails if `bytes` does not have [`Variant::secret_key_bytes`] bytes.
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn from_bytes(variant: Variant, bytes: &[u8]) -> Result<AnySecretKey<'static>, Error> {
+        check_length(variant, variant.secret_key_bytes(), bytes)?;
+
+        Ok(with_parameter_set!(variant, P => {
+            let mut buf = <P as ParameterSet>::SecretKeyArray::zeroed_boxed();
+            buf.copy_from_slice(bytes);
+            AnySecretKey::from(SecretKey::<P>::from(buf))
+        }))
+    }
+
+    /// Regenerates the secret key of parameter set `variant` expanded from `seed`, see
+    /// [`SecretKey::from_seed`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn from_seed_boxed(variant: Variant, seed: &SecretSeed) -> AnySecretKey<'static> {
+        with_parameter_set!(variant, P => {
+            AnySecretKey::from(SecretKey::<P>::from_seed_boxed(seed))
+        })
+    }
+
+    /// The length of the key in bytes.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.variant().secret_key_bytes()
+    }
+
+    fn mismatch(&self, ciphertext: &AnyCiphertext) -> Error {
+        Error::VariantMismatch {
+            expected: self.variant(),
+            actual: ciphertext.variant(),
+        }
+    }
+}
+
+impl AnyCiphertext {
+    /// Copies `bytes` into a ciphertext of parameter set `variant`.
+    ///
+    /// Fails if `bytes` does not have [`Variant::ciphertext_bytes`] bytes.
+    pub fn from_bytes(variant: Variant, bytes: &[u8]) -> Result<AnyCiphertext, Error> {
+        check_length(variant, variant.ciphertext_bytes(), bytes)?;
+
+        Ok(with_parameter_set!(variant, P => {
+            let mut buf = <P as ParameterSet>::CiphertextArray::zeroed();
+            buf.copy_from_slice(bytes);
+            AnyCiphertext::from(Ciphertext::<P>::from(buf))
+        }))
+    }
+
+    /// The length of the ciphertext in bytes.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.variant().ciphertext_bytes()
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_from_bytes_checks_length() {
+        let ciphertext = [0u8; Mceliece460896::CIPHERTEXT_BYTES];
+        assert_eq!(
+            AnyCiphertext::from_bytes(Variant::Mceliece348864, &ciphertext).unwrap_err(),
+            Error::InvalidLength {
+                variant: Variant::Mceliece348864,
+                expected: Mceliece348864::CIPHERTEXT_BYTES,
+                actual: Mceliece460896::CIPHERTEXT_BYTES,
+            }
+        );
+
+        let ciphertext = AnyCiphertext::from_bytes(Variant::Mceliece460896, &ciphertext).unwrap();
+        assert_eq!(ciphertext.variant(), Variant::Mceliece460896);
+        assert_eq!(ciphertext.len(), Mceliece460896::CIPHERTEXT_BYTES);
+
+        let secret_key = std::vec![0u8; Mceliece6688128::SECRET_KEY_BYTES];
+        assert!(AnySecretKey::from_bytes(Variant::Mceliece6960119, &secret_key).is_err());
+        assert!(AnyPublicKey::from_bytes(Variant::Mceliece6688128, &secret_key).is_err());
+        let secret_key = AnySecretKey::from_bytes(Variant::Mceliece6688128, &secret_key).unwrap();
+        assert_eq!(secret_key.variant(), Variant::Mceliece6688128);
+        assert_eq!(secret_key.len(), Mceliece6688128::SECRET_KEY_BYTES);
+    }
+
+    #[test]
+    fn test_runtime_dispatch() {
+        fn run() {
+            let mut rng = rand::thread_rng();
+
+            let (public_key, secret_key) = Variant::Mceliece348864f.keypair_boxed(&mut rng);
+            assert_eq!(public_key.variant(), Variant::Mceliece348864f);
+            assert_eq!(public_key.len(), public_key.as_ref().len());
+
+            let public_key = AnyPublicKey::from_bytes(public_key.variant(), public_key.as_ref());
+            let (ciphertext, shared_secret_bob) = public_key.unwrap().encapsulate_boxed(&mut rng);
+            assert_eq!(ciphertext.variant(), Variant::Mceliece348864f);
+
+            let shared_secret_alice = secret_key.decapsulate_boxed(&ciphertext).unwrap();
+            assert_eq!(shared_secret_bob.as_array(), shared_secret_alice.as_array());
+
+            let other_ciphertext =
+                AnyCiphertext::from(Ciphertext::<Mceliece348864>::from([0u8; 96]));
+            assert_eq!(
+                secret_key.decapsulate_boxed(&other_ciphertext).unwrap_err(),
+                Error::VariantMismatch {
+                    expected: Variant::Mceliece348864f,
+                    actual: Variant::Mceliece348864,
+                }
+            );
+        }
+
+        thread::Builder::new()
+            .stack_size(4 * 1024 * 1024)
+            .spawn(run)
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
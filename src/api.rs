@@ -3,121 +3,124 @@
 #[cfg(feature = "kem")]
 use kem::generic_array::typenum;
 
-#[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-/// The number of bytes required to store the public key
-pub const CRYPTO_PUBLICKEYBYTES: usize = 261120;
-#[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-/// The number of bytes required to store the secret key
-pub const CRYPTO_SECRETKEYBYTES: usize = 6492;
-#[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-/// The number of bytes required to store the ciphertext resulting from the encryption
-pub const CRYPTO_CIPHERTEXTBYTES: usize = 96;
+use crate::params::ParameterSet;
 
-#[cfg(all(
-    feature = "kem",
-    any(feature = "mceliece348864", feature = "mceliece348864f")
-))]
-/// The number of bytes required to store the ciphertext resulting from the encryption, as a typenum
-pub type CryptoCiphertextBytesTypenum = typenum::U96;
+/// Defines [`DefaultParameterSet`] and its ciphertext size as a typenum for the first
+/// parameter set whose condition holds.
+macro_rules! default_parameter_set {
+    ($(#[cfg($condition:meta)] $name:ident, $ciphertext_bytes:ident;)*) => {
+        $(
+            /// The parameter set used by the non-generic API ([`keypair`](crate::keypair),
+            /// [`CRYPTO_PUBLICKEYBYTES`], …). It is selected through the `mceliece*` feature
+            /// flags. If several of them are enabled, the one listed first in `Cargo.toml`
+            /// (`mceliece348864` before `mceliece348864f`, …, before `mceliece8192128f`) is
+            /// used. Without any of them, it is [`Mceliece348864`](crate::Mceliece348864).
+            #[cfg($condition)]
+            pub type DefaultParameterSet = crate::params::$name;
 
-#[cfg(feature = "mceliece348864")]
-/// Name of the variant
-pub const CRYPTO_PRIMITIVE: &str = "mceliece348864";
-#[cfg(feature = "mceliece348864f")]
-/// Name of the variant
-pub const CRYPTO_PRIMITIVE: &str = "mceliece348864f";
-
-#[cfg(any(feature = "mceliece460896", feature = "mceliece460896f"))]
-/// The number of bytes required to store the public key
-pub const CRYPTO_PUBLICKEYBYTES: usize = 524160;
-#[cfg(any(feature = "mceliece460896", feature = "mceliece460896f"))]
-/// The number of bytes required to store the secret key
-pub const CRYPTO_SECRETKEYBYTES: usize = 13608;
-#[cfg(any(feature = "mceliece460896", feature = "mceliece460896f"))]
-/// The number of bytes required to store the ciphertext resulting from the encryption
-pub const CRYPTO_CIPHERTEXTBYTES: usize = 156;
-#[cfg(all(
-    feature = "kem",
-    any(feature = "mceliece460896", feature = "mceliece460896f")
-))]
-/// The number of bytes required to store the ciphertext resulting from the encryption, as a typenum
-pub type CryptoCiphertextBytesTypenum = typenum::U156;
+            /// The number of bytes required to store the ciphertext resulting from the
+            /// encryption, as a typenum
+            #[cfg(all(feature = "kem", $condition))]
+            pub type CryptoCiphertextBytesTypenum = typenum::$ciphertext_bytes;
+        )*
+    };
+}
 
-#[cfg(feature = "mceliece460896")]
-/// Name of the variant
-pub const CRYPTO_PRIMITIVE: &str = "mceliece460896";
-#[cfg(feature = "mceliece460896f")]
-/// Name of the variant
-pub const CRYPTO_PRIMITIVE: &str = "mceliece460896f";
+// Each condition excludes the parameter sets before it, so exactly one of them holds for
+// any combination of features.
+default_parameter_set! {
+    #[cfg(any(feature = "mceliece348864", not(any(
+        feature = "mceliece348864f",
+        feature = "mceliece460896",
+        feature = "mceliece460896f",
+        feature = "mceliece6688128",
+        feature = "mceliece6688128f",
+        feature = "mceliece6960119",
+        feature = "mceliece6960119f",
+        feature = "mceliece8192128",
+        feature = "mceliece8192128f"
+    ))))]
+    Mceliece348864, U96;
+    #[cfg(all(feature = "mceliece348864f", not(feature = "mceliece348864")))]
+    Mceliece348864f, U96;
+    #[cfg(all(feature = "mceliece460896", not(any(
+        feature = "mceliece348864",
+        feature = "mceliece348864f"
+    ))))]
+    Mceliece460896, U156;
+    #[cfg(all(feature = "mceliece460896f", not(any(
+        feature = "mceliece348864",
+        feature = "mceliece348864f",
+        feature = "mceliece460896"
+    ))))]
+    Mceliece460896f, U156;
+    #[cfg(all(feature = "mceliece6688128", not(any(
+        feature = "mceliece348864",
+        feature = "mceliece348864f",
+        feature = "mceliece460896",
+        feature = "mceliece460896f"
+    ))))]
+    Mceliece6688128, U208;
+    #[cfg(all(feature = "mceliece6688128f", not(any(
+        feature = "mceliece348864",
+        feature = "mceliece348864f",
+        feature = "mceliece460896",
+        feature = "mceliece460896f",
+        feature = "mceliece6688128"
+    ))))]
+    Mceliece6688128f, U208;
+    #[cfg(all(feature = "mceliece6960119", not(any(
+        feature = "mceliece348864",
+        feature = "mceliece348864f",
+        feature = "mceliece460896",
+        feature = "mceliece460896f",
+        feature = "mceliece6688128",
+        feature = "mceliece6688128f"
+    ))))]
+    Mceliece6960119, U194;
+    #[cfg(all(feature = "mceliece6960119f", not(any(
+        feature = "mceliece348864",
+        feature = "mceliece348864f",
+        feature = "mceliece460896",
+        feature = "mceliece460896f",
+        feature = "mceliece6688128",
+        feature = "mceliece6688128f",
+        feature = "mceliece6960119"
+    ))))]
+    Mceliece6960119f, U194;
+    #[cfg(all(feature = "mceliece8192128", not(any(
+        feature = "mceliece348864",
+        feature = "mceliece348864f",
+        feature = "mceliece460896",
+        feature = "mceliece460896f",
+        feature = "mceliece6688128",
+        feature = "mceliece6688128f",
+        feature = "mceliece6960119",
+        feature = "mceliece6960119f"
+    ))))]
+    Mceliece8192128, U208;
+    #[cfg(all(feature = "mceliece8192128f", not(any(
+        feature = "mceliece348864",
+        feature = "mceliece348864f",
+        feature = "mceliece460896",
+        feature = "mceliece460896f",
+        feature = "mceliece6688128",
+        feature = "mceliece6688128f",
+        feature = "mceliece6960119",
+        feature = "mceliece6960119f",
+        feature = "mceliece8192128"
+    ))))]
+    Mceliece8192128f, U208;
+}
 
-#[cfg(any(feature = "mceliece6688128", feature = "mceliece6688128f"))]
 /// The number of bytes required to store the public key
-pub const CRYPTO_PUBLICKEYBYTES: usize = 1044992;
-#[cfg(any(feature = "mceliece6688128", feature = "mceliece6688128f"))]
+pub const CRYPTO_PUBLICKEYBYTES: usize = DefaultParameterSet::PUBLIC_KEY_BYTES;
 /// The number of bytes required to store the secret key
-pub const CRYPTO_SECRETKEYBYTES: usize = 13932;
-#[cfg(any(feature = "mceliece6688128", feature = "mceliece6688128f"))]
+pub const CRYPTO_SECRETKEYBYTES: usize = DefaultParameterSet::SECRET_KEY_BYTES;
 /// The number of bytes required to store the ciphertext resulting from the encryption
-pub const CRYPTO_CIPHERTEXTBYTES: usize = 208;
-#[cfg(all(
-    feature = "kem",
-    any(feature = "mceliece6688128", feature = "mceliece6688128f")
-))]
-/// The number of bytes required to store the ciphertext resulting from the encryption, as a typenum
-pub type CryptoCiphertextBytesTypenum = typenum::U208;
-
-#[cfg(feature = "mceliece6688128")]
-/// Name of the variant
-pub const CRYPTO_PRIMITIVE: &str = "mceliece6688128";
-#[cfg(feature = "mceliece6688128f")]
-/// Name of the variant
-pub const CRYPTO_PRIMITIVE: &str = "mceliece6688128f";
-
-#[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-/// The number of bytes required to store the public key
-pub const CRYPTO_PUBLICKEYBYTES: usize = 1047319;
-#[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-/// The number of bytes required to store the secret key
-pub const CRYPTO_SECRETKEYBYTES: usize = 13948;
-#[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-/// The number of bytes required to store the ciphertext resulting from the encryption
-pub const CRYPTO_CIPHERTEXTBYTES: usize = 194;
-#[cfg(all(
-    feature = "kem",
-    any(feature = "mceliece6960119", feature = "mceliece6960119f")
-))]
-/// The number of bytes required to store the ciphertext resulting from the encryption, as a typenum
-pub type CryptoCiphertextBytesTypenum = typenum::U194;
-
-#[cfg(feature = "mceliece6960119")]
-/// Name of the variant
-pub const CRYPTO_PRIMITIVE: &str = "mceliece6960119";
-#[cfg(feature = "mceliece6960119f")]
-/// Name of the variant
-pub const CRYPTO_PRIMITIVE: &str = "mceliece6960119f";
-
-#[cfg(any(feature = "mceliece8192128", feature = "mceliece8192128f"))]
-/// The number of bytes required to store the public key
-pub const CRYPTO_PUBLICKEYBYTES: usize = 1357824;
-#[cfg(any(feature = "mceliece8192128", feature = "mceliece8192128f"))]
-/// The number of bytes required to store the secret key
-pub const CRYPTO_SECRETKEYBYTES: usize = 14120;
-#[cfg(any(feature = "mceliece8192128", feature = "mceliece8192128f"))]
-/// The number of bytes required to store the ciphertext resulting from the encryption
-pub const CRYPTO_CIPHERTEXTBYTES: usize = 208;
-#[cfg(all(
-    feature = "kem",
-    any(feature = "mceliece8192128", feature = "mceliece8192128f")
-))]
-/// The number of bytes required to store the ciphertext resulting from the encryption, as a typenum
-pub type CryptoCiphertextBytesTypenum = typenum::U208;
-
-#[cfg(feature = "mceliece8192128")]
-/// Name of the variant
-pub const CRYPTO_PRIMITIVE: &str = "mceliece8192128";
-#[cfg(feature = "mceliece8192128f")]
+pub const CRYPTO_CIPHERTEXTBYTES: usize = DefaultParameterSet::CIPHERTEXT_BYTES;
 /// Name of the variant
-pub const CRYPTO_PRIMITIVE: &str = "mceliece8192128f";
+pub const CRYPTO_PRIMITIVE: &str = DefaultParameterSet::NAME;
 
 /// The number of bytes required to store the shared secret negotiated between both parties
 // this value is uniform
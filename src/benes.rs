@@ -6,8 +6,7 @@
 
 use crate::gf::Gf;
 use crate::macros::sub;
-use crate::params::SYS_N;
-use crate::params::{COND_BYTES, GFBITS};
+use crate::params::{ParameterSet, MAX_GFBITS};
 use crate::transpose;
 use crate::util;
 
@@ -21,7 +20,6 @@ use crate::util;
 /// | 6 | 128 | 64 |
 /// | 5 | 64 | 32 |
 /// | 4 | 64 | 32 |
-#[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
 fn layer(data: &mut [u64], bits: &[u64], lgs: usize) {
     let mut index = 0;
 
@@ -43,7 +41,6 @@ fn layer(data: &mut [u64], bits: &[u64], lgs: usize) {
 
 /// Inner layers of the Beneš network. The required size of `data` and `bits` depends on the value `lgs`.
 /// `data[0]`, `data[1]` and `bits` must have the same length; namely `2^(lgs + 1)` with `lgs ≥ 5`.
-#[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
 fn layer_in<const L: usize>(data: &mut [[u64; L]; 2], bits: &[u64; L], lgs: usize) {
     let mut d: u64;
     let mut index = 0;
@@ -53,18 +50,18 @@ fn layer_in<const L: usize>(data: &mut [[u64; L]; 2], bits: &[u64; L], lgs: usiz
     let mut i = 0usize;
     while i < 64 {
         for j in i..(i + s) {
-            d = data[0][j + 0] ^ data[0][j + s];
+            d = data[0][j] ^ data[0][j + s];
             d &= bits[index];
             index += 1;
 
-            data[0][j + 0] ^= d;
+            data[0][j] ^= d;
             data[0][j + s] ^= d;
 
-            d = data[1][j + 0] ^ data[1][j + s];
+            d = data[1][j] ^ data[1][j + s];
             d &= bits[index];
             index += 1;
 
-            data[1][j + 0] ^= d;
+            data[1][j] ^= d;
             data[1][j + s] ^= d;
         }
         i += s * 2;
@@ -87,7 +84,7 @@ fn layer_in<const L: usize>(data: &mut [[u64; L]; 2], bits: &[u64; L], lgs: usiz
 /// the entire array `data`, this implementation always considers `data` as two-dimensional array.
 /// The C implementation uses 128 as upper bound (because the array contains 128 elements),
 /// but this implementation has 64 elements per subarray and needs case distinctions at different places.
-#[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
+#[allow(clippy::needless_range_loop)]
 fn layer_ex(data: &mut [[u64; 64]; 2], bits: &[u64], lgs: usize) {
     let mut data0_idx = 0;
     let mut data1_idx = 32;
@@ -97,11 +94,11 @@ fn layer_ex(data: &mut [[u64; 64]; 2], bits: &[u64], lgs: usize) {
         // in this case where `s` has the highest possible value,
         // we need to access both subarrays in one expression.
         for j in 0..64 {
-            let mut d = data[0][j + 0] ^ data[1][j];
+            let mut d = data[0][j] ^ data[1][j];
             d &= bits[data0_idx];
             data0_idx += 1;
 
-            data[0][j + 0] ^= d;
+            data[0][j] ^= d;
             data[1][j] ^= d;
         }
     } else {
@@ -111,19 +108,19 @@ fn layer_ex(data: &mut [[u64; 64]; 2], bits: &[u64], lgs: usize) {
         while i < 64 {
             for j in i..(i + s) {
                 // data[0] computations
-                let mut d = data[0][j + 0] ^ data[0][j + s];
+                let mut d = data[0][j] ^ data[0][j + s];
                 d &= bits[data0_idx];
                 data0_idx += 1;
 
-                data[0][j + 0] ^= d;
+                data[0][j] ^= d;
                 data[0][j + s] ^= d;
 
                 // data[1] computations
-                d = data[1][j + 0] ^ data[1][j + s];
+                d = data[1][j] ^ data[1][j + s];
                 d &= bits[data1_idx];
                 data1_idx += 1;
 
-                data[1][j + 0] ^= d;
+                data[1][j] ^= d;
                 data[1][j + s] ^= d;
             }
             i += s * 2;
@@ -135,8 +132,11 @@ fn layer_ex(data: &mut [[u64; 64]; 2], bits: &[u64], lgs: usize) {
 /// Here, `r` is a sequence of bits to be permuted.
 /// `bits` defines the condition bits configuring the Beneš network and
 /// `rev` toggles between normal application (0) or its inverse (!0).
-#[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-fn apply_benes(r: &mut [u8; 512], bits: &[u8; COND_BYTES], rev: usize) {
+///
+/// This is the variant for `GFBITS = 12`.
+fn apply_benes_gf12(r: &mut [u8; 512], bits: &[u8], rev: usize) {
+    const GFBITS: usize = 12;
+
     let mut bs = [0u64; 64];
     let mut cond = [0u64; 64];
 
@@ -222,8 +222,8 @@ fn apply_benes(r: &mut [u8; 512], bits: &[u8; COND_BYTES], rev: usize) {
                     (2 * GFBITS - 2 - 6 - 6) * 256 - (4 - low) * 256 + i * 8,
                     8
                 ));
-                layer(&mut bs, &cond, low);
             }
+            layer(&mut bs, &cond, low);
         }
 
         transpose::transpose_64x64_inplace(&mut bs);
@@ -252,8 +252,9 @@ fn apply_benes(r: &mut [u8; 512], bits: &[u8; COND_BYTES], rev: usize) {
 /// Here, `r` is a sequence of bits to be permuted.
 /// `bits` defines the condition bits configuring the Beneš network and
 /// `rev` toggles between normal application (0) or its inverse (!0).
-#[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
-fn apply_benes(r: &mut [u8; 1024], bits: &[u8; COND_BYTES], rev: usize) {
+///
+/// This is the variant for `GFBITS = 13`.
+fn apply_benes_gf13(r: &mut [u8; 1024], bits: &[u8], rev: usize) {
     let mut r_int_v = [[0u64; 64]; 2];
     let mut r_int_h = [[0u64; 64]; 2];
     let mut b_int_v = [0u64; 64];
@@ -282,7 +283,7 @@ fn apply_benes(r: &mut [u8; 1024], bits: &[u8; COND_BYTES], rev: usize) {
 
         transpose::transpose(&mut b_int_h, b_int_v);
 
-        layer_ex(&mut r_int_h, &mut b_int_h, iter);
+        layer_ex(&mut r_int_h, &b_int_h, iter);
     }
 
     transpose::transpose(&mut r_int_v[0], r_int_h[0]);
@@ -299,7 +300,7 @@ fn apply_benes(r: &mut [u8; 1024], bits: &[u8; COND_BYTES], rev: usize) {
             calc_index - 512
         };
 
-        layer_in(&mut r_int_v, &mut b_int_v, iter);
+        layer_in(&mut r_int_v, &b_int_v, iter);
     }
 
     for iter in (0..=4).rev() {
@@ -312,7 +313,7 @@ fn apply_benes(r: &mut [u8; 1024], bits: &[u8; COND_BYTES], rev: usize) {
             calc_index - 512
         };
 
-        layer_in(&mut r_int_v, &mut b_int_v, iter);
+        layer_in(&mut r_int_v, &b_int_v, iter);
     }
 
     transpose::transpose(&mut r_int_h[0], r_int_v[0]);
@@ -332,7 +333,7 @@ fn apply_benes(r: &mut [u8; 1024], bits: &[u8; COND_BYTES], rev: usize) {
 
         transpose::transpose(&mut b_int_h, b_int_v);
 
-        layer_ex(&mut r_int_h, &mut b_int_h, iter);
+        layer_ex(&mut r_int_h, &b_int_h, iter);
     }
 
     transpose::transpose(&mut r_int_v[0], r_int_h[0]);
@@ -344,12 +345,34 @@ fn apply_benes(r: &mut [u8; 1024], bits: &[u8; COND_BYTES], rev: usize) {
     }
 }
 
-pub(crate) fn support_gen(s: &mut [Gf; SYS_N], c: &[u8; COND_BYTES]) {
+/// Apply the Beneš network configured by `bits` to the `1 << GFBITS` bits of `r`,
+/// dispatching to [`apply_benes_gf12`] or [`apply_benes_gf13`].
+/// `rev` toggles between normal application (0) or its inverse (!0).
+pub(crate) fn apply_benes<P: ParameterSet>(r: &mut [u8], bits: &[u8], rev: usize) {
+    debug_assert_eq!(r.len(), (1 << P::GFBITS) / 8);
+
+    if P::GFBITS == 12 {
+        apply_benes_gf12(sub!(mut r, 0, 512), bits, rev);
+    } else {
+        apply_benes_gf13(sub!(mut r, 0, 1024), bits, rev);
+    }
+}
+
+/// Given the images `f[a]` of all `1 << GFBITS` field elements `a`, compute `out[i] = f[s[i]]`
+/// for the support `s` described by the control bits `c` (see [`support_gen`]).
+/// Only the public indices `bitrev(i)` are used to access `f`, the secret permutation is
+/// applied by the Beneš network to the bit planes of the images.
+pub(crate) fn support_map<P: ParameterSet>(out: &mut [Gf], f: &[Gf], c: &[u8]) {
+    debug_assert!(out.len() == P::SYS_N || out.len() == 1 << P::GFBITS);
+    debug_assert_eq!(f.len(), 1 << P::GFBITS);
+    debug_assert_eq!(c.len(), P::COND_BYTES);
+
     let mut a: Gf;
-    let mut l = [[0u8; (1 << GFBITS) / 8]; GFBITS];
+    let mut l_buf = [[0u8; (1 << MAX_GFBITS) / 8]; MAX_GFBITS];
+    let l = &mut l_buf[..P::GFBITS];
 
-    for i in 0..(1 << GFBITS) {
-        a = util::bitrev(i as Gf);
+    for i in 0..(1 << P::GFBITS) {
+        a = f[util::bitrev::<P>(i as Gf) as usize];
 
         for (j, itr_l) in l.iter_mut().enumerate() {
             itr_l[i / 8] |= (((a >> j) & 1) << (i % 8)) as u8;
@@ -357,44 +380,48 @@ pub(crate) fn support_gen(s: &mut [Gf; SYS_N], c: &[u8; COND_BYTES]) {
     }
 
     for itr_l in l.iter_mut() {
-        #[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-        {
-            apply_benes(itr_l, c, 0);
-        }
-        #[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
-        {
-            apply_benes(itr_l, c, 0);
-        }
+        apply_benes::<P>(&mut itr_l[..(1 << P::GFBITS) / 8], c, 0);
     }
 
-    for (i, itr_s) in s.iter_mut().enumerate() {
-        *itr_s = 0;
-        for j in (0..=(GFBITS - 1)).rev() {
-            *itr_s <<= 1;
-            *itr_s |= ((l[j][i / 8] >> (i % 8)) & 1) as u16;
+    for (i, itr_out) in out.iter_mut().enumerate() {
+        *itr_out = 0;
+        for j in (0..=(P::GFBITS - 1)).rev() {
+            *itr_out <<= 1;
+            *itr_out |= ((l[j][i / 8] >> (i % 8)) & 1) as u16;
         }
     }
 }
 
+/// Compute the support `s` from the control bits `c`. `s` usually has `SYS_N` elements,
+/// but may also hold the full permuted field of `1 << GFBITS` elements.
+pub(crate) fn support_gen<P: ParameterSet>(s: &mut [Gf], c: &[u8]) {
+    let mut field_buf = [0 as Gf; 1 << MAX_GFBITS];
+    let field = &mut field_buf[..1 << P::GFBITS];
+    for (a, itr_field) in field.iter_mut().enumerate() {
+        *itr_field = a as Gf;
+    }
+
+    support_map::<P>(s, field, c);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::api::CRYPTO_PRIMITIVE;
+    use crate::params::{Mceliece348864, Mceliece460896};
     use crate::test_utils::TestData;
 
     use std::convert::TryFrom;
     use std::vec::Vec;
 
-    fn get(name: &str) -> Vec<u64> {
-        let fullname = format!("{}_{}", CRYPTO_PRIMITIVE, name);
+    fn get<P: ParameterSet>(name: &str) -> Vec<u64> {
+        let fullname = format!("{}_{}", P::NAME, name);
         TestData::new().u64vec(&fullname)
     }
 
-    fn get64(name: &str) -> [u64; 64] {
-        <[u64; 64]>::try_from(get(name).as_slice()).unwrap()
+    fn get64<P: ParameterSet>(name: &str) -> [u64; 64] {
+        <[u64; 64]>::try_from(get::<P>(name).as_slice()).unwrap()
     }
 
-    #[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
     #[test]
     fn test_layer() {
         let mut data = [0u64; 64];
@@ -420,48 +447,51 @@ mod tests {
         );
     }
 
-    #[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
     #[test]
     fn test_layer_2() {
-        let mut data_arg = get64("benes_layer_data_before");
-        let bits_arg = get("benes_layer_bits");
+        type P = Mceliece348864;
+
+        let mut data_arg = get64::<P>("benes_layer_data_before");
+        let bits_arg = get::<P>("benes_layer_bits");
         layer(&mut data_arg, &bits_arg, 0);
         let actual_data = data_arg;
 
-        let expected_data = get64("benes_layer_data_after");
+        let expected_data = get64::<P>("benes_layer_data_after");
 
         assert_eq!(actual_data, expected_data);
     }
 
-    #[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
     #[test]
     fn test_layer_in() {
-        let data0_arg = get64("benes_layer_in_data0_before");
-        let data1_arg = get64("benes_layer_in_data1_before");
+        type P = Mceliece460896;
+
+        let data0_arg = get64::<P>("benes_layer_in_data0_before");
+        let data1_arg = get64::<P>("benes_layer_in_data1_before");
         let mut data_arg = [data0_arg, data1_arg];
-        let bits_arg = get64("benes_layer_in_bits");
+        let bits_arg = get64::<P>("benes_layer_in_bits");
         layer_in(&mut data_arg, &bits_arg, 0);
         let actual_data = data_arg;
 
-        let expected_data0 = get64("benes_layer_in_data0_after");
-        let expected_data1 = get64("benes_layer_in_data1_after");
+        let expected_data0 = get64::<P>("benes_layer_in_data0_after");
+        let expected_data1 = get64::<P>("benes_layer_in_data1_after");
         let expected_data = [expected_data0, expected_data1];
 
         assert_eq!(actual_data, expected_data);
     }
 
-    #[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
     #[test]
     fn test_layer_ex() {
         let mut data = [[0u64; 64]; 2];
         let mut bits = [0u64; 64];
 
-        for i in 0..64 {
-            data[0][i] = 0xFC81 ^ (i as u64 * 17);
-            data[1][i] = 0x9837 ^ (i as u64 * 3);
+        for (i, d) in data[0].iter_mut().enumerate() {
+            *d = 0xFC81 ^ (i as u64 * 17);
         }
-        for i in 0..64 {
-            bits[i] = (i as u64) << 3;
+        for (i, d) in data[1].iter_mut().enumerate() {
+            *d = 0x9837 ^ (i as u64 * 3);
+        }
+        for (i, b) in bits.iter_mut().enumerate() {
+            *b = (i as u64) << 3;
         }
         layer_ex(&mut data, &bits, 5);
 
@@ -490,24 +520,24 @@ mod tests {
         );
     }
 
-    #[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
     #[test]
-    fn test_apply_benes() {
+    fn test_apply_benes_gf12() {
         let t = TestData::new();
         let mut r_arg =
             <[u8; 512]>::try_from(t.u8vec("mceliece348864_benes_apply_benes_r_before")).unwrap();
         let bits_arg =
             <[u8; 5888]>::try_from(t.u8vec("mceliece348864_benes_apply_benes_bits")).unwrap();
-        apply_benes(&mut r_arg, &bits_arg, 0);
+        apply_benes_gf12(&mut r_arg, &bits_arg, 0);
         let actual_r = r_arg;
         let expected_r =
             <[u8; 512]>::try_from(t.u8vec("mceliece348864_benes_apply_benes_r_after")).unwrap();
         assert_eq!(actual_r, expected_r);
     }
 
-    #[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
     #[test]
-    fn test_apply_benes() {
+    fn test_apply_benes_gf13() {
+        const COND_BYTES: usize = Mceliece460896::COND_BYTES;
+
         let t = TestData::new();
         let mut r_arg =
             <[u8; 1024]>::try_from(t.u8vec("mceliece460896orlarger_benes_apply_benes_r_before"))
@@ -515,7 +545,7 @@ mod tests {
         let bits_arg =
             <[u8; COND_BYTES]>::try_from(t.u8vec("mceliece460896orlarger_benes_apply_benes_bits"))
                 .unwrap(); // TODO actual array has wrong size of 12_800
-        apply_benes(&mut r_arg, &bits_arg, 0);
+        apply_benes_gf13(&mut r_arg, &bits_arg, 0);
         let actual_r = r_arg;
         let expected_r =
             <[u8; 1024]>::try_from(t.u8vec("mceliece460896orlarger_benes_apply_benes_r_after"))
@@ -0,0 +1,245 @@
+//! Runs the known answer tests of all Classic McEliece variants.
+//!
+//! ```text
+//! classic-mceliece-kat PATH...
+//! classic-mceliece-kat --generate DIR
+//! ```
+//!
+//! Every `PATH` is a response file or a directory containing response files (`*.rsp`). The
+//! variant of each file is taken from its header line (`# kem/mceliece348864`), files of
+//! unknown variants are skipped. Every test case is regenerated from its seed and reported as
+//! passed or failed. Finally, the SHA3-256 digest of every regenerated response file and a
+//! summary digest over all of them are printed.
+//!
+//! With `--generate`, the request and response files of all ten variants are written to `DIR`
+//! and their digests are compared with the known digests of the reference implementation.
+//!
+//! The exit code is 0 if all test cases passed, 1 if any failed and 2 for usage or I/O errors.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::{env, fs, thread};
+
+use classic_mceliece_rust::kat::{self, Testcase};
+use classic_mceliece_rust::Variant;
+use sha3::{Digest, Sha3_256};
+
+/// SHA3-256 digests of the response files `PQCgenKAT_kem` creates with the reference
+/// implementation
+const EXPECTED_DIGESTS: [(Variant, &str); 10] = [
+    (
+        Variant::Mceliece348864,
+        "752cc8333456a529aaba662dd5c06c4d21a9c961a74daeb9e557b5f0bd98ecf5",
+    ),
+    (
+        Variant::Mceliece348864f,
+        "4f956ffcc495ead07efa2e3146f4c08ad88754b8342480780309e6371c2a3959",
+    ),
+    (
+        Variant::Mceliece460896,
+        "9dc2c85d45de52d670f555f6dfa36e9859c2a6d496a8470a7288958befde58ce",
+    ),
+    (
+        Variant::Mceliece460896f,
+        "55096c535862635b5feff5315e2b8e239a6784744faf9caf68b83ab33626b91e",
+    ),
+    (
+        Variant::Mceliece6688128,
+        "27da2d52f32f0a4376302e3257097cc27879386277b9e4c7ebb782e62f56f78f",
+    ),
+    (
+        Variant::Mceliece6688128f,
+        "49255962dfb9809707b009c48291ec0448636c7295da5497db84a3b7decde362",
+    ),
+    (
+        Variant::Mceliece6960119,
+        "8fc012f398c6a84f948d9fb92424bd101ad9df7742139f756cb11a68188e7879",
+    ),
+    (
+        Variant::Mceliece6960119f,
+        "303ba3147bf607323dfd711b5d576c8ae661468674c91c65e061f3d7bda65038",
+    ),
+    (
+        Variant::Mceliece8192128,
+        "559438a09bb9f8ff0964d9a00ca6fa19573876825307e157366d969bc941f3a3",
+    ),
+    (
+        Variant::Mceliece8192128f,
+        "c89dfcb1aadee10f341d77114f97a50bd18013925d8115f39335d6b921f77578",
+    ),
+];
+
+/// The KEM keeps its buffers on the stack, the largest variants need several megabytes
+const STACK_SIZE: usize = 64 * 1024 * 1024;
+
+const USAGE: &str =
+    "usage: classic-mceliece-kat PATH...\n       classic-mceliece-kat --generate DIR";
+
+/// The digests of all regenerated response files, one per variant
+struct Summary {
+    digests: Vec<(Variant, String)>,
+    passed: usize,
+    failed: usize,
+}
+
+impl Summary {
+    fn new() -> Summary {
+        Summary {
+            digests: Vec::new(),
+            passed: 0,
+            failed: 0,
+        }
+    }
+
+    fn print(mut self) {
+        self.digests.sort_by_key(|&(variant, _)| variant as usize);
+        let mut hasher = Sha3_256::new();
+        for (variant, digest) in &self.digests {
+            hasher.update(format!("{} {digest}\n", variant.name()));
+        }
+
+        println!(
+            "{} files, {} passed, {} failed",
+            self.digests.len(),
+            self.passed,
+            self.failed
+        );
+        println!("summary digest {}", hex::encode(hasher.finalize()));
+    }
+}
+
+fn digest(rsp: &str) -> String {
+    hex::encode(Sha3_256::digest(rsp.as_bytes()))
+}
+
+/// The response files given on the command line, directories are searched for `*.rsp` files
+fn response_files(paths: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for path in paths.iter().map(Path::new) {
+        if !path.is_dir() {
+            files.push(path.to_path_buf());
+            continue;
+        }
+
+        let entries =
+            fs::read_dir(path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+        let mut rsp_files = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+            let file = entry.path();
+            if file.extension().is_some_and(|ext| ext == "rsp") {
+                rsp_files.push(file);
+            }
+        }
+        rsp_files.sort();
+        files.extend(rsp_files);
+    }
+    Ok(files)
+}
+
+fn verify(paths: &[String]) -> Result<bool, String> {
+    let mut summary = Summary::new();
+
+    for path in response_files(paths)? {
+        let input = fs::read_to_string(&path)
+            .map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+        let file = kat::parse_rsp(&input).map_err(|e| format!("{}: {e}", path.display()))?;
+        let Some(variant) = file.variant else {
+            println!("{}: skipped, unknown variant", path.display());
+            continue;
+        };
+
+        let mut responses = Vec::with_capacity(file.testcases.len());
+        let mut failed = 0;
+        for expected in &file.testcases {
+            let actual = Testcase::generate(variant, expected.count, expected.seed);
+            if kat::compare(expected, &actual).is_empty() {
+                println!("{} count = {}: pass", variant.name(), expected.count);
+            } else {
+                println!("{} count = {}: FAIL", variant.name(), expected.count);
+                failed += 1;
+            }
+            responses.push(actual);
+        }
+
+        let digest = digest(&kat::write_rsp(variant, &responses));
+        println!(
+            "{}: {} {}, {} of {} test cases failed, sha3-256 {digest}",
+            path.display(),
+            variant.name(),
+            if failed == 0 { "passed" } else { "FAILED" },
+            failed,
+            responses.len(),
+        );
+        summary.passed += responses.len() - failed;
+        summary.failed += failed;
+        summary.digests.push((variant, digest));
+    }
+
+    let ok = summary.failed == 0 && !summary.digests.is_empty();
+    summary.print();
+    Ok(ok)
+}
+
+fn generate(dir: &Path) -> Result<bool, String> {
+    fs::create_dir_all(dir).map_err(|e| format!("cannot create {}: {e}", dir.display()))?;
+
+    let requests = kat::request(kat::KATNUM);
+    let mut summary = Summary::new();
+
+    for (variant, expected_digest) in EXPECTED_DIGESTS {
+        let responses = kat::respond(variant, &requests);
+        let rsp = kat::write_rsp(variant, &responses);
+
+        for (extension, content) in [("req", kat::write_req(&requests)), ("rsp", rsp.clone())] {
+            let path = dir.join(format!("{}.{extension}", variant.name()));
+            fs::write(&path, content)
+                .map_err(|e| format!("cannot write {}: {e}", path.display()))?;
+        }
+
+        let digest = digest(&rsp);
+        if digest == expected_digest {
+            println!("{}: passed, sha3-256 {digest}", variant.name());
+            summary.passed += responses.len();
+        } else {
+            println!(
+                "{}: FAILED, sha3-256 {digest}, expected {expected_digest}",
+                variant.name()
+            );
+            summary.failed += responses.len();
+        }
+        summary.digests.push((variant, digest));
+    }
+
+    let ok = summary.failed == 0;
+    summary.print();
+    Ok(ok)
+}
+
+fn run(args: Vec<String>) -> Result<bool, String> {
+    match args.as_slice() {
+        [] => Err(USAGE.to_string()),
+        [flag, dir] if flag == "--generate" => generate(Path::new(dir)),
+        [flag, ..] if flag.starts_with('-') => Err(USAGE.to_string()),
+        paths => verify(paths),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = env::args().skip(1).collect();
+    let result = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || run(args))
+        .expect("failed to spawn thread")
+        .join()
+        .expect("KAT thread panicked");
+
+    match result {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(message) => {
+            eprintln!("{message}");
+            ExitCode::from(2)
+        }
+    }
+}
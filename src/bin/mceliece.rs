@@ -0,0 +1,583 @@
+//! Command-line tool for key generation, encapsulation and decapsulation.
+//!
+//! ```text
+//! mceliece keygen --variant mceliece460896f --pk alice.pub --sk alice.sec
+//! mceliece pubkey alice.sec
+//! mceliece encaps alice.pub --ct message.ct --ss bob.ss
+//! mceliece decaps alice.sec message.ct --ss alice.ss
+//! mceliece inspect alice.pub
+//! ```
+//!
+//! Keys and ciphertexts are written as raw bytes, hex, base64 or PEM (`--format`). Keys in PEM
+//! are the standard `PUBLIC KEY` (SubjectPublicKeyInfo) and `PRIVATE KEY` (PKCS#8) documents,
+//! whose algorithm identifier names the variant. Ciphertexts have no standard encoding, their
+//! PEM label names the variant instead, e.g. `MCELIECE460896F CIPHERTEXT`. Shared secrets are
+//! written as hex instead of PEM. Input files are read in the format given
+//! with `--input-format`. By default it is detected: PEM is recognised by its armor, hex and
+//! base64 by their alphabet, anything else is taken as raw bytes. Input which does not decode
+//! in its format is an error. Only PEM records the variant, for the other formats it must be
+//! passed with `--variant`.
+//!
+//! A path of `-` stands for standard input or output. On unix, files with secret keys and
+//! shared secrets are only readable and writable by their owner.
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use std::thread;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use clap::{Parser, Subcommand, ValueEnum};
+use classic_mceliece_rust::pkcs8::spki::{DecodePublicKey, EncodePublicKey};
+use classic_mceliece_rust::pkcs8::{DecodePrivateKey, EncodePrivateKey, LineEnding};
+use classic_mceliece_rust::{
+    AnyCiphertext, AnyPublicKey, AnySecretKey, DefaultParameterSet, ParameterSet, Variant,
+};
+use rand::rngs::OsRng;
+
+/// The KEM keeps its buffers on the stack, the largest variants need several megabytes
+const STACK_SIZE: usize = 64 * 1024 * 1024;
+
+#[derive(Parser)]
+#[command(
+    name = "mceliece",
+    version,
+    about = "Classic McEliece key encapsulation"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Format of the input files, detected if not given
+    #[arg(long, global = true, value_enum)]
+    input_format: Option<Format>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generate a key pair
+    Keygen {
+        #[arg(short, long, value_parser = parse_variant, default_value_t = DefaultParameterSet::VARIANT)]
+        variant: Variant,
+        #[arg(short, long, value_enum, default_value_t = Format::Pem)]
+        format: Format,
+        /// Output file of the public key
+        #[arg(long)]
+        pk: String,
+        /// Output file of the secret key
+        #[arg(long)]
+        sk: String,
+    },
+    /// Derive the public key from a secret key
+    Pubkey {
+        /// The secret key
+        sk: String,
+        #[arg(short, long, value_parser = parse_variant)]
+        variant: Option<Variant>,
+        #[arg(short, long, value_enum, default_value_t = Format::Pem)]
+        format: Format,
+        /// Output file of the public key
+        #[arg(short, long, default_value = "-")]
+        out: String,
+    },
+    /// Encapsulate a fresh shared secret to a public key
+    Encaps {
+        /// The public key
+        pk: String,
+        #[arg(short, long, value_parser = parse_variant)]
+        variant: Option<Variant>,
+        #[arg(short, long, value_enum, default_value_t = Format::Pem)]
+        format: Format,
+        /// Output file of the ciphertext
+        #[arg(long)]
+        ct: String,
+        /// Output file of the shared secret
+        #[arg(long, default_value = "-")]
+        ss: String,
+    },
+    /// Decapsulate the shared secret of a ciphertext with a secret key
+    Decaps {
+        /// The secret key
+        sk: String,
+        /// The ciphertext
+        ct: String,
+        #[arg(short, long, value_parser = parse_variant)]
+        variant: Option<Variant>,
+        #[arg(short, long, value_enum, default_value_t = Format::Hex)]
+        format: Format,
+        /// Output file of the shared secret
+        #[arg(long, default_value = "-")]
+        ss: String,
+    },
+    /// Print format, kind, variant and validity of a key or ciphertext
+    Inspect {
+        /// The file to inspect
+        file: String,
+        #[arg(short, long, value_parser = parse_variant)]
+        variant: Option<Variant>,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Raw,
+    Hex,
+    Base64,
+    Pem,
+}
+
+impl Format {
+    /// Shared secrets are not armored, they are written as hex instead of PEM
+    fn for_shared_secret(self) -> Format {
+        match self {
+            Format::Pem => Format::Hex,
+            format => format,
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Format::Raw => "raw",
+            Format::Hex => "hex",
+            Format::Base64 => "base64",
+            Format::Pem => "pem",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    PublicKey,
+    SecretKey,
+    Ciphertext,
+    SharedSecret,
+}
+
+impl Kind {
+    const ALL: [Kind; 4] = [
+        Kind::PublicKey,
+        Kind::SecretKey,
+        Kind::Ciphertext,
+        Kind::SharedSecret,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            Kind::PublicKey => "PUBLIC KEY",
+            Kind::SecretKey => "SECRET KEY",
+            Kind::Ciphertext => "CIPHERTEXT",
+            Kind::SharedSecret => "SHARED SECRET",
+        }
+    }
+
+    fn len(self, variant: Variant) -> usize {
+        match self {
+            Kind::PublicKey => variant.public_key_bytes(),
+            Kind::SecretKey => variant.secret_key_bytes(),
+            Kind::Ciphertext => variant.ciphertext_bytes(),
+            Kind::SharedSecret => classic_mceliece_rust::CRYPTO_BYTES,
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.label().to_lowercase())
+    }
+}
+
+/// The decoded content of an input file
+struct Object {
+    format: Format,
+    /// The variant and kind given by the PEM document
+    label: Option<(Variant, Kind)>,
+    bytes: Vec<u8>,
+}
+
+impl Object {
+    /// Returns the variant of this object of the given `kind`, taken from the PEM label or
+    /// from `--variant`
+    fn variant(&self, kind: Kind, variant: Option<Variant>) -> Result<Variant, String> {
+        match (self.label, variant) {
+            (Some((_, label_kind)), _) if label_kind != kind => {
+                Err(format!("expected a {kind}, found a {label_kind}"))
+            }
+            (Some((label_variant, _)), Some(variant)) if label_variant != variant => Err(format!(
+                "the {kind} belongs to {label_variant}, not to {variant}"
+            )),
+            (Some((label_variant, _)), _) => Ok(label_variant),
+            (None, Some(variant)) => Ok(variant),
+            (None, None) => Err(format!(
+                "the variant of a {} encoded {kind} must be given with --variant",
+                self.format
+            )),
+        }
+    }
+}
+
+fn parse_variant(name: &str) -> Result<Variant, String> {
+    Variant::from_name(name).ok_or_else(|| {
+        let names: Vec<&str> = Variant::ALL.iter().map(|v| v.name()).collect();
+        format!("expected one of {}", names.join(", "))
+    })
+}
+
+fn read_input(path: &str) -> Result<Vec<u8>, String> {
+    if path == "-" {
+        let mut input = Vec::new();
+        io::stdin()
+            .read_to_end(&mut input)
+            .map_err(|e| format!("cannot read standard input: {e}"))?;
+        Ok(input)
+    } else {
+        fs::read(path).map_err(|e| format!("cannot read {path}: {e}"))
+    }
+}
+
+fn write_output(path: &str, content: &[u8]) -> Result<(), String> {
+    if path == "-" {
+        io::stdout()
+            .write_all(content)
+            .map_err(|e| format!("cannot write standard output: {e}"))
+    } else {
+        fs::write(path, content).map_err(|e| format!("cannot write {path}: {e}"))
+    }
+}
+
+/// Like [`write_output`], but on unix a file is only readable and writable by its owner
+/// (mode `0o600`). This is used for secret keys and shared secrets.
+fn write_secret_output(path: &str, content: &[u8]) -> Result<(), String> {
+    if path == "-" {
+        return write_output(path, content);
+    }
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let write = || -> io::Result<()> {
+        let mut file = options.open(path)?;
+        // the mode only applies to new files, an existing one is restricted as well
+        #[cfg(unix)]
+        if file.metadata()?.is_file() {
+            use std::os::unix::fs::PermissionsExt;
+            file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        }
+        file.write_all(content)
+    };
+    write().map_err(|e| format!("cannot write {path}: {e}"))
+}
+
+fn decode_pem(text: &str) -> Result<Object, String> {
+    let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+    let label = lines
+        .next()
+        .and_then(|line| line.strip_prefix("-----BEGIN "))
+        .and_then(|line| line.strip_suffix("-----"))
+        .ok_or("invalid PEM header")?;
+    let object = |variant, kind, bytes| Object {
+        format: Format::Pem,
+        label: Some((variant, kind)),
+        bytes,
+    };
+
+    match label {
+        "PUBLIC KEY" => {
+            let key = AnyPublicKey::from_public_key_pem(text)
+                .map_err(|e| format!("invalid SubjectPublicKeyInfo: {e}"))?;
+            Ok(object(
+                key.variant(),
+                Kind::PublicKey,
+                key.as_ref().to_vec(),
+            ))
+        }
+        "PRIVATE KEY" => {
+            let key = AnySecretKey::from_pkcs8_pem(text)
+                .map_err(|e| format!("invalid PKCS#8 private key: {e}"))?;
+            Ok(object(
+                key.variant(),
+                Kind::SecretKey,
+                key.as_ref().to_vec(),
+            ))
+        }
+        _ => {
+            let variant = label
+                .strip_suffix(" CIPHERTEXT")
+                .and_then(|name| Variant::from_name(&name.to_lowercase()))
+                .ok_or_else(|| format!("unknown PEM label '{label}'"))?;
+
+            let mut body = String::new();
+            for line in lines.by_ref() {
+                if line == format!("-----END {label}-----") {
+                    let bytes = BASE64
+                        .decode(body)
+                        .map_err(|e| format!("invalid PEM body: {e}"))?;
+                    return Ok(object(variant, Kind::Ciphertext, bytes));
+                }
+                body.push_str(line);
+            }
+            Err(format!("missing PEM footer '-----END {label}-----'"))
+        }
+    }
+}
+
+/// Guesses the format of an input file
+fn detect_format(input: &[u8]) -> Format {
+    match std::str::from_utf8(input).ok().map(str::trim) {
+        Some(text) if text.starts_with("-----BEGIN ") => Format::Pem,
+        Some(text) if !text.is_empty() && text.bytes().all(|b| b.is_ascii_hexdigit()) => {
+            Format::Hex
+        }
+        Some(text)
+            if !text.is_empty()
+                && text
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"+/=\r\n".contains(&b)) =>
+        {
+            Format::Base64
+        }
+        _ => Format::Raw,
+    }
+}
+
+/// Decodes an input file in `format`, or in its detected format if `format` is `None`
+fn decode(input: Vec<u8>, format: Option<Format>) -> Result<Object, String> {
+    let format = format.unwrap_or_else(|| detect_format(&input));
+    let object = |bytes| Object {
+        format,
+        label: None,
+        bytes,
+    };
+    let text = || {
+        std::str::from_utf8(&input)
+            .map(str::trim)
+            .map_err(|_| format!("invalid {format}: not a text file"))
+    };
+
+    match format {
+        Format::Raw => Ok(object(input)),
+        Format::Hex => {
+            let bytes = hex::decode(text()?).map_err(|e| format!("invalid hex: {e}"))?;
+            Ok(object(bytes))
+        }
+        Format::Base64 => {
+            let compact: String = text()?.split_whitespace().collect();
+            let bytes = BASE64
+                .decode(compact)
+                .map_err(|e| format!("invalid base64: {e}"))?;
+            Ok(object(bytes))
+        }
+        Format::Pem => decode_pem(text()?),
+    }
+}
+
+/// Encodes a ciphertext or shared secret. Keys are encoded by [`encode_public_key`] and
+/// [`encode_secret_key`], which write the standard PEM documents.
+fn encode(format: Format, variant: Variant, kind: Kind, bytes: &[u8]) -> Vec<u8> {
+    match format {
+        Format::Raw => bytes.to_vec(),
+        Format::Hex => format!("{}\n", hex::encode(bytes)).into_bytes(),
+        Format::Base64 => format!("{}\n", BASE64.encode(bytes)).into_bytes(),
+        Format::Pem => {
+            let label = format!("{} {}", variant.name().to_uppercase(), kind.label());
+            let body = BASE64.encode(bytes);
+            let mut pem = format!("-----BEGIN {label}-----\n");
+            for line in body.as_bytes().chunks(64) {
+                pem.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+                pem.push('\n');
+            }
+            pem.push_str(&format!("-----END {label}-----\n"));
+            pem.into_bytes()
+        }
+    }
+}
+
+/// Reads and decodes an input file in `format`, or in its detected format if it is `None`
+fn read_object(path: &str, format: Option<Format>) -> Result<Object, String> {
+    decode(read_input(path)?, format).map_err(|e| format!("{path}: {e}"))
+}
+
+fn encode_public_key(format: Format, key: &AnyPublicKey<'_>) -> Result<Vec<u8>, String> {
+    match format {
+        Format::Pem => key
+            .to_public_key_pem(LineEnding::LF)
+            .map(String::into_bytes)
+            .map_err(|e| format!("cannot encode the public key: {e}")),
+        format => Ok(encode(format, key.variant(), Kind::PublicKey, key.as_ref())),
+    }
+}
+
+fn encode_secret_key(format: Format, key: &AnySecretKey<'_>) -> Result<Vec<u8>, String> {
+    match format {
+        Format::Pem => key
+            .to_pkcs8_pem(LineEnding::LF)
+            .map(|pem| pem.as_bytes().to_vec())
+            .map_err(|e| format!("cannot encode the secret key: {e}")),
+        format => Ok(encode(format, key.variant(), Kind::SecretKey, key.as_ref())),
+    }
+}
+
+fn read_public_key(
+    path: &str,
+    format: Option<Format>,
+    variant: Option<Variant>,
+) -> Result<AnyPublicKey<'static>, String> {
+    let object = read_object(path, format)?;
+    let variant = object.variant(Kind::PublicKey, variant)?;
+    AnyPublicKey::from_bytes(variant, &object.bytes).map_err(|e| format!("{path}: {e}"))
+}
+
+fn read_secret_key(
+    path: &str,
+    format: Option<Format>,
+    variant: Option<Variant>,
+) -> Result<AnySecretKey<'static>, String> {
+    let object = read_object(path, format)?;
+    let variant = object.variant(Kind::SecretKey, variant)?;
+    AnySecretKey::from_bytes(variant, &object.bytes).map_err(|e| format!("{path}: {e}"))
+}
+
+fn read_ciphertext(
+    path: &str,
+    format: Option<Format>,
+    variant: Option<Variant>,
+) -> Result<AnyCiphertext, String> {
+    let object = read_object(path, format)?;
+    let variant = object.variant(Kind::Ciphertext, variant)?;
+    AnyCiphertext::from_bytes(variant, &object.bytes).map_err(|e| format!("{path}: {e}"))
+}
+
+fn inspect(path: &str, format: Option<Format>, variant: Option<Variant>) -> Result<(), String> {
+    let object = read_object(path, format)?;
+    println!("format: {}", object.format);
+    println!("length: {} bytes", object.bytes.len());
+
+    // without a PEM label, every kind and variant of matching length is a candidate
+    let candidates: Vec<(Variant, Kind)> = match object.label {
+        Some(label) => vec![label],
+        None => Variant::ALL
+            .into_iter()
+            .filter(|v| variant.is_none_or(|variant| variant == *v))
+            .flat_map(|v| Kind::ALL.into_iter().map(move |kind| (v, kind)))
+            .filter(|&(v, kind)| kind.len(v) == object.bytes.len())
+            .collect(),
+    };
+    if candidates.is_empty() {
+        return Err(format!("{path}: no variant has objects of this length"));
+    }
+
+    for (variant, kind) in candidates {
+        let validity = match kind {
+            Kind::PublicKey => AnyPublicKey::from_bytes(variant, &object.bytes).map(|_| ()),
+            Kind::SecretKey => {
+                AnySecretKey::from_bytes(variant, &object.bytes).and_then(|sk| sk.validate())
+            }
+            Kind::Ciphertext => AnyCiphertext::from_bytes(variant, &object.bytes).map(|_| ()),
+            Kind::SharedSecret => Ok(()),
+        };
+        match validity {
+            Ok(()) => println!("{variant} {kind}: valid"),
+            Err(e) => println!("{variant} {kind}: invalid, {e}"),
+        }
+    }
+    Ok(())
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let input_format = cli.input_format;
+    match cli.command {
+        Command::Keygen {
+            variant,
+            format,
+            pk,
+            sk,
+        } => {
+            let (public_key, secret_key) = variant
+                .try_keypair_boxed(&mut OsRng)
+                .map_err(|e| e.to_string())?;
+            write_output(&pk, &encode_public_key(format, &public_key)?)?;
+            write_secret_output(&sk, &encode_secret_key(format, &secret_key)?)
+        }
+        Command::Pubkey {
+            sk,
+            variant,
+            format,
+            out,
+        } => {
+            let secret_key = read_secret_key(&sk, input_format, variant)?;
+            let public_key = secret_key.public_key().map_err(|e| format!("{sk}: {e}"))?;
+            write_output(&out, &encode_public_key(format, &public_key)?)
+        }
+        Command::Encaps {
+            pk,
+            variant,
+            format,
+            ct,
+            ss,
+        } => {
+            let public_key = read_public_key(&pk, input_format, variant)?;
+            let variant = public_key.variant();
+            let (ciphertext, shared_secret) = public_key
+                .try_encapsulate_boxed(&mut OsRng)
+                .map_err(|e| e.to_string())?;
+            write_output(
+                &ct,
+                &encode(format, variant, Kind::Ciphertext, ciphertext.as_ref()),
+            )?;
+            let bytes = encode(
+                format.for_shared_secret(),
+                variant,
+                Kind::SharedSecret,
+                shared_secret.as_ref(),
+            );
+            write_secret_output(&ss, &bytes)
+        }
+        Command::Decaps {
+            sk,
+            ct,
+            variant,
+            format,
+            ss,
+        } => {
+            let secret_key = read_secret_key(&sk, input_format, variant)?;
+            let ciphertext = read_ciphertext(
+                &ct,
+                input_format,
+                Some(variant.unwrap_or(secret_key.variant())),
+            )?;
+            let shared_secret = secret_key
+                .try_decapsulate_boxed(&ciphertext)
+                .map_err(|e| e.to_string())?;
+            let bytes = encode(
+                format.for_shared_secret(),
+                secret_key.variant(),
+                Kind::SharedSecret,
+                shared_secret.as_ref(),
+            );
+            write_secret_output(&ss, &bytes)
+        }
+        Command::Inspect { file, variant } => inspect(&file, input_format, variant),
+    }
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let result = thread::Builder::new()
+        .stack_size(STACK_SIZE)
+        .spawn(move || run(cli))
+        .expect("failed to spawn thread")
+        .join()
+        .expect("mceliece thread panicked");
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("mceliece: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
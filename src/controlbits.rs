@@ -5,7 +5,7 @@
 //!        <https://cr.yp.to/papers/controlbits-20200923.pdf>
 
 use crate::int32_sort::int32_sort;
-use crate::params::GFBITS;
+use crate::params::MAX_GFBITS;
 
 // controlbits returns ((2*m - 1) * 2^(m - 1)) control bits for a permutation pi over 2^m indices
 // equals ((2*m - 1) * 2^(m - 1))/8 control bytes, which is what this macro computes.
@@ -264,6 +264,11 @@ fn cbrecursion(
         }
     }
 
+    #[cfg(feature = "parallel")]
+    if w >= PARALLEL_MIN_W && cbrecursion_join(out, pos, step, w, n, temp) {
+        return;
+    }
+
     cbrecursion(out, pos, step * 2, n + n / 4, w - 1, n / 2, temp, aux);
     cbrecursion(
         out,
@@ -277,6 +282,82 @@ fn cbrecursion(
     );
 }
 
+/// Smallest `w` for which [`cbrecursion`] runs its two recursive calls in parallel
+#[cfg(feature = "parallel")]
+const PARALLEL_MIN_W: usize = 10;
+
+/// Run the two recursive calls at the end of [`cbrecursion`] in parallel.
+///
+/// The calls only read disjoint parts of `temp` and set disjoint bits of `out`. So each one
+/// is passed its part of `temp` as `aux` and gets its own `temp` and zeroed output, and the
+/// outputs are merged into `out` afterwards. The result is the same as the sequential one.
+///
+/// Returns `false` without touching `out` if the buffers can not be allocated, the calls
+/// then have to be run sequentially.
+#[cfg(feature = "parallel")]
+fn cbrecursion_join(
+    out: &mut [u8],
+    pos: usize,
+    step: usize,
+    w: usize,
+    n: usize,
+    temp: &[i32],
+) -> bool {
+    use crate::util::try_alloc_vec;
+
+    let (input0, input1) = (&temp[n + n / 4..n + n / 2], &temp[n + n / 2..n + 3 * n / 4]);
+    let (Some(mut out0), Some(mut out1), Some(mut temp0), Some(mut temp1)) = (
+        try_alloc_vec::<u8>(out.len()),
+        try_alloc_vec::<u8>(out.len()),
+        try_alloc_vec::<i32>(n),
+        try_alloc_vec::<i32>(n),
+    ) else {
+        return false;
+    };
+
+    rayon::join(
+        || {
+            cbrecursion(
+                &mut out0,
+                pos,
+                step * 2,
+                0,
+                w - 1,
+                n / 2,
+                &mut temp0,
+                input0,
+            )
+        },
+        || {
+            cbrecursion(
+                &mut out1,
+                pos + step,
+                step * 2,
+                0,
+                w - 1,
+                n / 2,
+                &mut temp1,
+                input1,
+            )
+        },
+    );
+
+    for ((itr_out, b0), b1) in out.iter_mut().zip(&out0).zip(&out1) {
+        *itr_out ^= b0 ^ b1;
+    }
+
+    #[cfg(feature = "zeroize")]
+    {
+        use zeroize::Zeroize;
+        out0[..].zeroize();
+        out1[..].zeroize();
+        temp0[..].zeroize();
+        temp1[..].zeroize();
+    }
+
+    true
+}
+
 /// controlbitsfrompermutation computes control bits.
 ///
 /// Pick `w` ∈ {1, 2, …, 14}. Let `n = 2^w`.
@@ -290,13 +371,16 @@ pub(crate) fn controlbitsfrompermutation(out: &mut [u8], pi: &[i16], w: usize, n
     debug_assert_eq!(pi.len(), n);
     debug_assert_eq!(out.len(), ((2 * w - 1) * n / 2).div_ceil(8));
 
-    let mut temp = [0i32; 2 * (1 << GFBITS)];
+    debug_assert!(w <= MAX_GFBITS);
+
+    let mut temp_buf = [0i32; 2 * (1 << MAX_GFBITS)];
+    let temp = &mut temp_buf[..2 * n];
     let mut diff: i16 = 0;
 
     // reinterpret pi as i32 array
-    debug_assert_eq!(pi.len(), 1 << GFBITS);
-    let mut pi_as_i32 = [0i32; 1 << (GFBITS - 1)];
-    for i in 0..(1 << (GFBITS - 1)) {
+    let mut pi_as_i32_buf = [0i32; 1 << (MAX_GFBITS - 1)];
+    let pi_as_i32 = &mut pi_as_i32_buf[..n / 2];
+    for i in 0..(n / 2) {
         pi_as_i32[i] = pi[2 * i] as i32 | ((pi[2 * i + 1] as i32) << 16);
     }
 
@@ -304,21 +388,22 @@ pub(crate) fn controlbitsfrompermutation(out: &mut [u8], pi: &[i16], w: usize, n
 
     loop {
         sub.fill(0);
-        cbrecursion(sub, 0, 1, 0, w, n, &mut temp, &pi_as_i32);
+        cbrecursion(sub, 0, 1, 0, w, n, temp, pi_as_i32);
 
-        let mut pi_test = [0i16; 1 << GFBITS];
+        let mut pi_test_buf = [0i16; 1 << MAX_GFBITS];
+        let pi_test = &mut pi_test_buf[..n];
 
         for (i, itr_pi_test) in pi_test.iter_mut().enumerate() {
             *itr_pi_test = i as i16;
         }
 
         for i in 0..w {
-            layer(&mut pi_test, sub, i as i32, n as i32);
+            layer(pi_test, sub, i as i32, n as i32);
             sub = &mut sub[(n >> 4)..];
         }
 
         for i in (0..w - 1).rev() {
-            layer(&mut pi_test, sub, i as i32, n as i32);
+            layer(pi_test, sub, i as i32, n as i32);
             sub = &mut sub[(n >> 4)..];
         }
 
@@ -335,7 +420,6 @@ pub(crate) fn controlbitsfrompermutation(out: &mut [u8], pi: &[i16], w: usize, n
 #[cfg(test)]
 mod tests {
     use super::*;
-    #[cfg(any(feature = "mceliece348864", feature = "mceliece6960119"))]
     use crate::test_utils::TestData;
 
     // A simple testcase for layer().
@@ -425,7 +509,6 @@ mod tests {
     // This testcase corresponds to the call of controlbitsfrompermutation
     // in the 3rd KAT testcase of the mceliece348864 reference implementation
     #[test]
-    #[cfg(feature = "mceliece348864")]
     fn test_controlbitsfrompermutation_kat3_mceliece348864() {
         let pi = TestData::new().i16vec("controlbits_kat3_mceliece348864_pi");
         let mut out = [0u8; 5888];
@@ -439,7 +522,6 @@ mod tests {
     // This testcase corresponds to the call of controlbitsfrompermutation
     // in the 8th KAT testcase of the mceliece348864 reference implementation
     #[test]
-    #[cfg(feature = "mceliece348864")]
     fn test_controlbitsfrompermutation_kat8_mceliece348864() {
         let mut out = [0u8; 5888];
         let pi = TestData::new().i16vec("controlbits_kat8_mceliece348864_pi");
@@ -453,7 +535,6 @@ mod tests {
     // This testcase corresponds to the call of controlbitsfrompermutation
     // in the 9th KAT testcase of the mceliece348864 reference implementation
     #[test]
-    #[cfg(feature = "mceliece348864")]
     fn test_controlbitsfrompermutation_kat9_mceliece348864() {
         let mut out = [0u8; 5888];
         let pi = TestData::new().i16vec("controlbits_kat9_mceliece348864_pi");
@@ -467,10 +548,7 @@ mod tests {
     // This testcase corresponds to the call of controlbitsfrompermutation
     // in the 3rd KAT testcase of the mceliece6960119 reference implementation
     #[test]
-    #[cfg(feature = "mceliece6960119")]
     fn test_controlbitsfrompermutation_kat3_mceliece6960119() {
-        assert_eq!(GFBITS, 13);
-
         let mut out = [0u8; 12800];
 
         let pi = TestData::new().i16vec("controlbits_kat3_mceliece6960119_pi");
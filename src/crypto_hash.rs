@@ -1,6 +1,7 @@
 //! Hash function implementations (only SHAKE)
 
-use sha3::digest::ExtendableOutput;
+use rand::{CryptoRng, RngCore};
+use sha3::digest::{ExtendableOutput, Update, XofReader};
 use sha3::Shake256;
 
 /// Utilizes the SHAKE256 hash function. Input and output is of arbitrary length.
@@ -9,6 +10,44 @@ pub(crate) fn shake256(output: &mut [u8], input: &[u8]) {
     Shake256::digest_xof(input, output);
 }
 
+/// A deterministic random number generator reading the output of SHAKE256 applied to
+/// a prefix byte and a seed.
+pub(crate) struct ShakeRng(<Shake256 as ExtendableOutput>::Reader);
+
+impl ShakeRng {
+    pub(crate) fn new(prefix: u8, seed: &[u8]) -> Self {
+        let mut hasher = Shake256::default();
+        hasher.update(&[prefix]);
+        hasher.update(seed);
+        Self(hasher.finalize_xof())
+    }
+}
+
+impl RngCore for ShakeRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.read(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        Ok(())
+    }
+}
+
+impl CryptoRng for ShakeRng {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -25,4 +64,20 @@ mod tests {
         shake256(&mut c[208..=239], &two_e[0..1025]);
         assert_eq!(&c, compare_array.as_slice());
     }
+
+    #[test]
+    fn test_shake_rng_matches_shake256() {
+        let mut input = [0u8; 33];
+        input[0] = 3;
+        input[1..].copy_from_slice(&[7u8; 32]);
+        let mut expected = [0u8; 100];
+        shake256(&mut expected, &input);
+
+        // reading in several chunks continues the same output stream
+        let mut rng = ShakeRng::new(3, &[7u8; 32]);
+        let mut output = [0u8; 100];
+        rng.fill_bytes(&mut output[..10]);
+        rng.fill_bytes(&mut output[10..]);
+        assert_eq!(output, expected);
+    }
 }
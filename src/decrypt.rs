@@ -1,13 +1,12 @@
 //! Decryption function to turn ciphertext into a ciphertext using the secret key
 
 use crate::{
-    benes::support_gen,
     bm::bm,
     gf::gf_iszero,
     macros::sub,
-    params::{COND_BYTES, IRR_BYTES, SYND_BYTES, SYS_N, SYS_T},
-    root::root,
-    synd::synd,
+    params::{ParameterSet, MAX_GFBITS, MAX_SYS_N, MAX_SYS_T},
+    root::root_support,
+    synd::{synd, synd_weights},
     util::load_gf,
 };
 
@@ -15,56 +14,88 @@ use crate::{
 ///
 /// It takes as input the secret key `sk` and a ciphertext `c`.
 /// It returns an error vector in `e` and the return value indicates success (0) or failure (1)
-pub(crate) fn decrypt(
-    e: &mut [u8; SYS_N / 8],
-    sk: &[u8; IRR_BYTES + COND_BYTES],
-    c: &[u8; SYND_BYTES],
-) -> u8 {
-    let mut t: u16;
-    let mut w: i32 = 0;
+pub(crate) fn decrypt<P: ParameterSet>(e: &mut [u8], sk: &[u8], c: &[u8]) -> u8 {
+    debug_assert_eq!(e.len(), P::SYS_N / 8);
+    debug_assert_eq!(sk.len(), P::IRR_BYTES + P::COND_BYTES);
+    debug_assert_eq!(c.len(), P::SYND_BYTES);
 
-    let mut r = [0u8; SYS_N / 8];
+    let mut w_buf = [0u16; 1 << MAX_GFBITS];
+    let weights = &mut w_buf[..1 << P::GFBITS];
 
-    let mut g = [0u16; SYS_T + 1];
-    let mut l = [0u16; SYS_N];
+    goppa_weights::<P>(weights, &sk[..P::IRR_BYTES]);
 
-    let mut s = [0u16; SYS_T * 2];
-    let mut s_cmp = [0u16; SYS_T * 2];
-    let mut locator = [0u16; SYS_T + 1];
-    let mut images = [0u16; SYS_N];
+    let cond = &sk[P::IRR_BYTES..P::IRR_BYTES + P::COND_BYTES];
+
+    decode::<P>(e, weights, cond, c)
+}
 
-    r[..SYND_BYTES].copy_from_slice(&c[..SYND_BYTES]);
+/// Compute the weights `1 / g(a)^2` of the Goppa polynomial `g` stored in the secret key
+/// section `irr` for all field elements `a`, see [`synd_weights`]
+pub(crate) fn goppa_weights<P: ParameterSet>(weights: &mut [u16], irr: &[u8]) {
+    debug_assert_eq!(weights.len(), 1 << P::GFBITS);
+    debug_assert_eq!(irr.len(), P::IRR_BYTES);
 
-    r[SYND_BYTES..SYS_N / 8].fill(0);
+    let mut g_buf = [0u16; MAX_SYS_T + 1];
+    let g = &mut g_buf[..=P::SYS_T];
 
-    for (i, chunk) in sk.chunks(2).take(SYS_T).enumerate() {
-        g[i] = load_gf(sub!(chunk, 0, 2));
+    for (i, chunk) in irr.chunks(2).take(P::SYS_T).enumerate() {
+        g[i] = load_gf::<P>(sub!(chunk, 0, 2));
     }
-    g[SYS_T] = 1;
+    g[P::SYS_T] = 1;
 
-    support_gen(&mut l, sub!(sk, IRR_BYTES, COND_BYTES));
+    synd_weights::<P>(weights, g);
+}
 
-    synd(&mut s, &g, &l, &r);
+/// Niederreiter decryption with the weights `1 / g(a)^2` of the Goppa polynomial `g`
+/// (see [`synd_weights`]) and the control bits `cond` of the support already computed.
+///
+/// Returns the error vector in `e` and success (0) or failure (1) like [`decrypt`].
+pub(crate) fn decode<P: ParameterSet>(e: &mut [u8], weights: &[u16], cond: &[u8], c: &[u8]) -> u8 {
+    debug_assert_eq!(e.len(), P::SYS_N / 8);
+    debug_assert_eq!(weights.len(), 1 << P::GFBITS);
+    debug_assert_eq!(cond.len(), P::COND_BYTES);
+    debug_assert_eq!(c.len(), P::SYND_BYTES);
 
-    bm(&mut locator, &mut s);
+    let mut t: u16;
+    let mut w: i32 = 0;
 
-    root(&mut images, &locator, &l);
+    let mut r_buf = [0u8; MAX_SYS_N / 8];
+    let r = &mut r_buf[..P::SYS_N / 8];
 
-    e[0..SYS_N / 8].fill(0);
+    let mut s_buf = [0u16; MAX_SYS_T * 2];
+    let s = &mut s_buf[..P::SYS_T * 2];
+    let mut s_cmp_buf = [0u16; MAX_SYS_T * 2];
+    let s_cmp = &mut s_cmp_buf[..P::SYS_T * 2];
+    let mut locator_buf = [0u16; MAX_SYS_T + 1];
+    let locator = &mut locator_buf[..=P::SYS_T];
+    let mut images_buf = [0u16; MAX_SYS_N];
+    let images = &mut images_buf[..P::SYS_N];
 
-    for i in 0..SYS_N {
+    r[..P::SYND_BYTES].copy_from_slice(&c[..P::SYND_BYTES]);
+
+    r[P::SYND_BYTES..].fill(0);
+
+    synd::<P>(s, weights, cond, r);
+
+    bm::<P>(locator, s);
+
+    root_support::<P>(images, locator, cond);
+
+    e.fill(0);
+
+    for i in 0..P::SYS_N {
         t = gf_iszero(images[i]) & 1;
 
         e[i / 8] |= (t << (i % 8)) as u8;
         w += t as i32;
     }
 
-    synd(&mut s_cmp, &g, &l, e);
+    synd::<P>(s_cmp, weights, cond, e);
 
     let mut check = w as u16;
-    check ^= SYS_T as u16;
+    check ^= P::SYS_T as u16;
 
-    for i in 0..SYS_T * 2 {
+    for i in 0..P::SYS_T * 2 {
         check |= s[i] ^ s_cmp[i];
     }
 
@@ -75,24 +106,30 @@ pub(crate) fn decrypt(
 }
 
 #[cfg(test)]
-#[cfg(any(feature = "mceliece8192128", feature = "mceliece8192128f"))]
 mod tests {
     use super::*;
+    use crate::params::Mceliece8192128f;
     use crate::test_utils::TestData;
 
     #[test]
     fn test_decrypt() {
+        type P = Mceliece8192128f;
+        const SYS_N: usize = P::SYS_N;
+        const IRR_BYTES: usize = P::IRR_BYTES;
+        const COND_BYTES: usize = P::COND_BYTES;
+        const SYND_BYTES: usize = P::SYND_BYTES;
+
         let sk = TestData::new().u8vec("mceliece8192128f_sk1"); // TODO: sk has wrong size … IRR_BYTES + COND_BYTES required
-        let mut c = TestData::new().u8vec("mceliece8192128f_ct1");
+        let c = TestData::new().u8vec("mceliece8192128f_ct1");
         let expected_error_vector = TestData::new().u8vec("mceliece8192128f_decrypt_errvec");
 
         let mut actual_error_vector = [0u8; 1 + SYS_N / 8];
         actual_error_vector[0] = 2;
 
-        decrypt(
-            sub!(mut actual_error_vector, 1, SYS_N / 8),
-            sub!(sk, 40, IRR_BYTES + COND_BYTES),
-            sub!(mut c, 0, SYND_BYTES),
+        decrypt::<P>(
+            &mut actual_error_vector[1..1 + SYS_N / 8],
+            &sk[40..40 + IRR_BYTES + COND_BYTES],
+            &c[..SYND_BYTES],
         );
 
         assert_eq!(
@@ -1,9 +1,8 @@
 //! Encryption function to compute error vector and syndrome to get ciphertext
 
 use crate::{
-    api::CRYPTO_CIPHERTEXTBYTES,
     macros::sub,
-    params::{PK_NROWS, PK_ROW_BYTES, SYND_BYTES, SYS_N, SYS_T},
+    params::{ParameterSet, MAX_SYS_N, MAX_SYS_T},
     util::load_gf,
 };
 use rand::{CryptoRng, RngCore};
@@ -18,191 +17,172 @@ fn same_mask_u8(x: u16, y: u16) -> u8 {
     (mask & 0xFF) as u8 // ∈ {0, u8::MAX}
 }
 
-/// Generation of `e`, an error vector of weight `t`.
-/// Does not take any input arguments.
+/// Sampling of the `t` distinct positions `ind` of the error vector.
+/// `bytes` and `nums` are scratch space for the random bytes and the candidate indices.
 /// If generation of pseudo-random numbers fails, an error is returned.
-#[cfg(not(any(feature = "mceliece8192128", feature = "mceliece8192128f")))]
-fn gen_e<R: CryptoRng + RngCore>(e: &mut [u8; SYS_N / 8], rng: &mut R) {
-    let mut ind = [0u16; SYS_T];
-    let mut val = [0u8; SYS_T];
-
-    loop {
-        let mut bytes = [0u8; SYS_T * 4];
-        rng.fill_bytes(&mut bytes);
-
-        let mut nums = [0u16; SYS_T * 2];
-        for (i, chunk) in bytes.chunks(2).enumerate() {
-            nums[i] = load_gf(sub!(chunk, 0, 2));
-        }
+fn sample_indices<P: ParameterSet, R: CryptoRng + RngCore>(
+    ind: &mut [u16],
+    bytes: &mut [u8],
+    nums: &mut [u16],
+    rng: &mut R,
+) -> Result<(), rand::Error> {
+    debug_assert_eq!(ind.len(), P::SYS_T);
 
-        // moving and counting indices in the correct range
+    if P::SYS_N == 1 << P::GFBITS {
+        // every field element is a valid index, hence no rejection sampling is required
+        let bytes = &mut bytes[..P::SYS_T * 2];
 
-        let mut count = 0;
-        for itr_num in nums.iter() {
-            if count >= SYS_T {
-                break;
+        loop {
+            rng.try_fill_bytes(bytes)?;
+
+            for (i, chunk) in bytes.chunks(2).enumerate() {
+                ind[i] = load_gf::<P>(sub!(chunk, 0, 2));
             }
-            if *itr_num < SYS_N as u16 {
-                ind[count] = *itr_num;
-                count += 1;
+
+            // check for repetition
+
+            let mut eq = 0;
+
+            for i in 1..P::SYS_T {
+                for j in 0..i {
+                    if ind[i] == ind[j] {
+                        eq = 1;
+                    }
+                }
             }
-        }
 
-        if count < SYS_T {
-            continue;
+            if eq == 0 {
+                return Ok(());
+            }
         }
+    } else {
+        let bytes = &mut bytes[..P::SYS_T * 4];
+        let nums = &mut nums[..P::SYS_T * 2];
 
-        // check for repetition
+        loop {
+            rng.try_fill_bytes(bytes)?;
 
-        let mut eq = 0;
+            for (i, chunk) in bytes.chunks(2).enumerate() {
+                nums[i] = load_gf::<P>(sub!(chunk, 0, 2));
+            }
 
-        for i in 1..SYS_T {
-            for j in 0..i {
-                if ind[i] == ind[j] {
-                    eq = 1;
+            // moving and counting indices in the correct range
+
+            let mut count = 0;
+            for itr_num in nums.iter() {
+                if count >= P::SYS_T {
+                    break;
+                }
+                if *itr_num < P::SYS_N as u16 {
+                    ind[count] = *itr_num;
+                    count += 1;
                 }
             }
-        }
 
-        if eq == 0 {
-            break;
-        }
-    }
+            if count < P::SYS_T {
+                continue;
+            }
 
-    for j in 0..SYS_T {
-        val[j] = 1 << (ind[j] & 7);
-    }
+            // check for repetition
 
-    for (i, itr_e) in e.iter_mut().enumerate() {
-        *itr_e = 0;
+            let mut eq = 0;
 
-        for j in 0..SYS_T {
-            let mask: u8 = same_mask_u8(i as u16, ind[j] >> 3);
+            for i in 1..P::SYS_T {
+                for j in 0..i {
+                    if ind[i] == ind[j] {
+                        eq = 1;
+                    }
+                }
+            }
 
-            *itr_e |= val[j] & mask;
+            if eq == 0 {
+                return Ok(());
+            }
         }
     }
 }
 
 /// Generation of `e`, an error vector of weight `t`.
 /// Does not take any input arguments.
-/// If generation of pseudo-random numbers fails, an error is returned.
-#[cfg(any(feature = "mceliece8192128", feature = "mceliece8192128f"))]
-fn gen_e<R: CryptoRng + RngCore>(e: &mut [u8], rng: &mut R) {
-    let mut ind = [0u16; SYS_T];
-    let mut bytes = [0u8; SYS_T * 2];
-    let mut val = [0u8; SYS_T];
+/// If generation of pseudo-random numbers fails, an error is returned and `e` is
+/// left unchanged.
+fn gen_e<P: ParameterSet, R: CryptoRng + RngCore>(
+    e: &mut [u8],
+    rng: &mut R,
+) -> Result<(), rand::Error> {
+    debug_assert_eq!(e.len(), P::SYS_N / 8);
+
+    let mut ind_buf = [0u16; MAX_SYS_T];
+    let ind = &mut ind_buf[..P::SYS_T];
+    let mut val = [0u8; MAX_SYS_T];
+    let mut bytes_buf = [0u8; MAX_SYS_T * 4];
+    let mut nums_buf = [0u16; MAX_SYS_T * 2];
 
-    loop {
-        rng.fill_bytes(&mut bytes);
+    let result = sample_indices::<P, R>(ind, &mut bytes_buf, &mut nums_buf, rng);
 
-        for (i, chunk) in bytes.chunks(2).enumerate() {
-            ind[i] = load_gf(sub!(chunk, 0, 2));
+    if result.is_ok() {
+        for j in 0..P::SYS_T {
+            val[j] = 1 << (ind[j] & 7);
         }
 
-        // check for repetition
+        for (i, itr_e) in e.iter_mut().enumerate() {
+            *itr_e = 0;
 
-        let mut eq = 0;
+            for j in 0..P::SYS_T {
+                let mask: u8 = same_mask_u8(i as u16, ind[j] >> 3);
 
-        for i in 1..SYS_T {
-            for j in 0..i {
-                if ind[i] == ind[j] {
-                    eq = 1;
-                }
+                *itr_e |= val[j] & mask;
             }
         }
-
-        if eq == 0 {
-            break;
-        }
     }
 
-    for j in 0..SYS_T {
-        val[j] = 1 << (ind[j] & 7);
+    // the positions of the error vector are secret, also when sampling failed midway
+    #[cfg(feature = "zeroize")]
+    {
+        use zeroize::Zeroize;
+        ind_buf.zeroize();
+        val.zeroize();
+        bytes_buf.zeroize();
+        nums_buf.zeroize();
     }
 
-    for i in 0..SYS_N / 8 {
-        e[i] = 0;
-
-        for j in 0..SYS_T {
-            let mask: u8 = same_mask_u8(i as u16, ind[j] >> 3);
-
-            e[i] |= val[j] & mask;
-        }
-    }
+    result
 }
 
 /// Syndrome computation.
 ///
 /// Computes syndrome `s` based on public key `pk` and error vector `e`.
-#[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-fn syndrome(
-    s: &mut [u8; (PK_NROWS + 7) / 8],
-    pk: &[u8; PK_NROWS * PK_ROW_BYTES],
-    e: &[u8; SYS_N / 8],
-) {
-    let mut row = [0u8; SYS_N / 8];
+fn syndrome<P: ParameterSet>(s: &mut [u8], pk: &[u8], e: &[u8]) {
+    debug_assert_eq!(s.len(), P::SYND_BYTES);
+    debug_assert_eq!(pk.len(), P::PUBLIC_KEY_BYTES);
+    debug_assert_eq!(e.len(), P::SYS_N / 8);
 
-    let mut pk_segment = &pk[..];
-    let tail = PK_NROWS % 8;
+    let mut row_buf = [0u8; MAX_SYS_N / 8];
+    let row = &mut row_buf[..P::SYS_N / 8];
 
-    s[0..SYND_BYTES].fill(0);
+    let mut pk_segment = pk;
+    // only mceliece6960119 has a number of rows not divisible by 8
+    let tail = P::PK_NROWS % 8;
 
-    for i in 0..PK_NROWS {
-        row[0..SYS_N / 8].fill(0);
+    s.fill(0);
 
-        for j in 0..PK_ROW_BYTES {
-            row[SYS_N / 8 - PK_ROW_BYTES + j] = pk_segment[j];
-        }
+    for i in 0..P::PK_NROWS {
+        row.fill(0);
 
-        for j in ((SYS_N / 8 - PK_ROW_BYTES)..SYS_N / 8).rev() {
-            row[j] = (row[j] << tail) | (row[j - 1] >> (8 - tail));
+        for j in 0..P::PK_ROW_BYTES {
+            row[P::SYS_N / 8 - P::PK_ROW_BYTES + j] = pk_segment[j];
         }
 
-        row[i / 8] |= 1 << (i % 8);
-
-        let mut b = 0u8;
-        for j in 0..SYS_N / 8 {
-            b ^= row[j] & e[j];
-        }
-
-        b ^= b >> 4;
-        b ^= b >> 2;
-        b ^= b >> 1;
-        b &= 1;
-
-        s[i / 8] |= b << (i % 8);
-
-        pk_segment = &pk_segment[PK_ROW_BYTES..];
-    }
-}
-
-/// Syndrome computation.
-///
-/// Computes syndrome `s` based on public key `pk` and error vector `e`.
-#[cfg(not(any(feature = "mceliece6960119", feature = "mceliece6960119f")))]
-fn syndrome(
-    s: &mut [u8; PK_NROWS.div_ceil(8)],
-    pk: &[u8; PK_NROWS * PK_ROW_BYTES],
-    e: &[u8; SYS_N / 8],
-) {
-    let mut row = [0u8; SYS_N / 8];
-
-    let mut pk_segment = &pk[..];
-
-    s[0..SYND_BYTES].fill(0);
-
-    for i in 0..PK_NROWS {
-        row[0..SYS_N / 8].fill(0);
-
-        for j in 0..PK_ROW_BYTES {
-            row[SYS_N / 8 - PK_ROW_BYTES + j] = pk_segment[j];
+        if tail != 0 {
+            for j in ((P::SYS_N / 8 - P::PK_ROW_BYTES)..P::SYS_N / 8).rev() {
+                row[j] = (row[j] << tail) | (row[j - 1] >> (8 - tail));
+            }
         }
 
         row[i / 8] |= 1 << (i % 8);
 
         let mut b = 0u8;
-        for j in 0..SYS_N / 8 {
+        for j in 0..P::SYS_N / 8 {
             b ^= row[j] & e[j];
         }
 
@@ -213,46 +193,46 @@ fn syndrome(
 
         s[i / 8] |= b << (i % 8);
 
-        pk_segment = &pk_segment[PK_ROW_BYTES..];
+        pk_segment = &pk_segment[P::PK_ROW_BYTES..];
     }
 }
 
 /// Encryption routine.
 /// Takes a public key `pk` to compute error vector `e` and syndrome `s`.
-pub(crate) fn encrypt<R: CryptoRng + RngCore>(
-    s: &mut [u8; CRYPTO_CIPHERTEXTBYTES],
-    pk: &[u8; PK_NROWS * PK_ROW_BYTES],
-    e: &mut [u8; SYS_N / 8],
+/// If generation of pseudo-random numbers fails, an error is returned.
+pub(crate) fn encrypt<P: ParameterSet, R: CryptoRng + RngCore>(
+    s: &mut [u8],
+    pk: &[u8],
+    e: &mut [u8],
     rng: &mut R,
-) {
-    gen_e(e, rng);
-    syndrome(sub!(mut s, 0, PK_NROWS.div_ceil(8)), pk, e);
+) -> Result<(), rand::Error> {
+    debug_assert_eq!(s.len(), P::CIPHERTEXT_BYTES);
+
+    gen_e::<P, R>(e, rng)?;
+    syndrome::<P>(&mut s[..P::SYND_BYTES], pk, e);
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    #[cfg(feature = "mceliece8192128f")]
     use super::*;
-    #[cfg(feature = "mceliece8192128f")]
-    use crate::api::CRYPTO_CIPHERTEXTBYTES;
-    #[cfg(feature = "mceliece8192128f")]
-    use crate::api::CRYPTO_PUBLICKEYBYTES;
-    #[cfg(feature = "mceliece8192128f")]
-    use crate::nist_aes_rng::AesState;
-    #[cfg(feature = "mceliece8192128f")]
+    use crate::nist_aes_rng::NistDrbg;
+    use crate::params::Mceliece8192128f;
     use crate::test_utils::TestData;
 
     #[test]
-    #[cfg(feature = "mceliece8192128f")]
     fn test_encrypt() {
+        type P = Mceliece8192128f;
+        const SYS_N: usize = P::SYS_N;
+        const CRYPTO_CIPHERTEXTBYTES: usize = P::CIPHERTEXT_BYTES;
+
         let entropy_input = [
             6, 21, 80, 35, 77, 21, 140, 94, 201, 85, 149, 254, 4, 239, 122, 37, 118, 127, 46, 36,
             204, 43, 196, 121, 208, 157, 134, 220, 154, 188, 253, 231, 5, 106, 140, 38, 111, 158,
             249, 126, 208, 133, 65, 219, 210, 225, 255, 161,
         ];
 
-        let mut rng_state = AesState::new();
-        rng_state.randombytes_init(entropy_input);
+        let mut rng_state = NistDrbg::new(entropy_input);
 
         let mut second_seed = [0u8; 33];
         second_seed[0] = 64;
@@ -262,17 +242,12 @@ mod tests {
         let mut e = [0u8; SYS_N / 8];
 
         let mut c = [0u8; CRYPTO_CIPHERTEXTBYTES];
-        let mut pk = TestData::new().u8vec("mceliece8192128f_pk1");
+        let pk = TestData::new().u8vec("mceliece8192128f_pk1");
 
         let compare_ct = TestData::new().u8vec("mceliece8192128f_encrypt_ct");
         assert_eq!(compare_ct.len(), CRYPTO_CIPHERTEXTBYTES);
 
-        encrypt(
-            &mut c,
-            sub!(mut pk, 0, CRYPTO_PUBLICKEYBYTES),
-            sub!(mut e, 0, SYS_N / 8),
-            &mut rng_state,
-        );
+        encrypt::<P, _>(&mut c, &pk[..P::PUBLIC_KEY_BYTES], &mut e, &mut rng_state).unwrap();
 
         assert_eq!(compare_ct, c);
     }
@@ -0,0 +1,263 @@
+//! Public key encryption of arbitrary messages (KEM-DEM)
+//!
+//! [`seal`] encapsulates a fresh shared secret to a public key, derives a key and a nonce for
+//! the AEAD `A` of the [`aead`](crate::aead) module from it and encrypts the message. [`open`]
+//! decapsulates the shared secret and decrypts the message. As every message gets a key of its
+//! own, the nonce may be derived as well.
+//!
+//! [`seal_envelope`] and [`open_envelope`] additionally pack the McEliece ciphertext and the
+//! encrypted message into one byte string with a header naming the parameter set and the AEAD:
+//!
+//! | offset | length | content |
+//! |---|---|---|
+//! | 0 | 3 | the magic bytes `MCE` |
+//! | 3 | 1 | the format version, `0x01` |
+//! | 4 | 1 | the parameter set, `0x01` to `0x0a` in the order of [`Variant::ALL`] |
+//! | 5 | 2 | the HPKE identifier of the AEAD ([`Aead::ID`]), big-endian |
+//! | 7 | `P::CIPHERTEXT_BYTES` | the McEliece ciphertext |
+//! | 7 + `P::CIPHERTEXT_BYTES` | message length + 16 | the encrypted message and its tag |
+//!
+//! [`seal`] and [`open`] use the same header internally, so they are compatible with the
+//! envelope: the AEAD key and nonce are `SHAKE256(header || shared secret)` truncated to
+//! [`Aead::KEY_BYTES`] + 12 bytes, and the header is prepended to the associated data.
+//!
+//! ```
+//! # #[cfg(feature = "chacha20poly1305")] {
+//! use classic_mceliece_rust::aead::ChaCha20Poly1305;
+//! use classic_mceliece_rust::envelope::{open_envelope, seal_envelope};
+//! use classic_mceliece_rust::{generic, Mceliece348864};
+//!
+//! # std::thread::Builder::new().stack_size(4 * 1024 * 1024).spawn(|| {
+//! let mut rng = rand::thread_rng();
+//! let (public_key, secret_key) = generic::keypair_boxed::<Mceliece348864, _>(&mut rng);
+//!
+//! let envelope =
+//!     seal_envelope::<_, ChaCha20Poly1305, _>(&public_key, b"message", b"aad", &mut rng).unwrap();
+//! let message = open_envelope::<_, ChaCha20Poly1305>(&secret_key, &envelope, b"aad").unwrap();
+//! assert_eq!(message, b"message");
+//! # }).unwrap().join().unwrap();
+//! # }
+//! ```
+
+use alloc::vec::Vec;
+
+use rand::{CryptoRng, RngCore};
+
+use crate::aead::{Aead, NONCE_BYTES};
+use crate::crypto_hash::shake256;
+use crate::{
+    generic, ByteArray, Ciphertext, Error, ParameterSet, PublicKey, SecretKey, SharedSecret,
+    Variant,
+};
+
+/// The length of the envelope header
+pub const HEADER_BYTES: usize = 7;
+
+const MAGIC: [u8; 3] = *b"MCE";
+
+const VERSION: u8 = 0x01;
+
+/// The longest key and nonce of the AEADs in [`crate::aead`]
+const MAX_KEY_NONCE_BYTES: usize = 32 + NONCE_BYTES;
+
+/// Encrypts `plaintext` to `public_key`. Returns the McEliece ciphertext and the encrypted
+/// message, both of which the recipient needs. `aad` is authenticated but not encrypted.
+///
+/// Fails if the public key is malformed (see [`PublicKey::validate`]) or if `rng` fails.
+pub fn seal<P: ParameterSet, A: Aead, R: CryptoRng + RngCore>(
+    public_key: &PublicKey<'_, P>,
+    plaintext: &[u8],
+    aad: &[u8],
+    rng: &mut R,
+) -> Result<(Ciphertext<P>, Vec<u8>), Error> {
+    let (ciphertext, shared_secret) = generic::try_encapsulate_boxed(public_key, rng)?;
+    let sealed = dem::<P, A>(&shared_secret, aad, |key, nonce, aad| {
+        A::seal(key, nonce, aad, plaintext)
+    })?;
+    Ok((ciphertext, sealed))
+}
+
+/// Decrypts a message encrypted with [`seal`]. Fails with [`Error::Aead`] if the message, the
+/// ciphertext or `aad` were modified, or if the message was sealed to another key.
+pub fn open<P: ParameterSet, A: Aead>(
+    secret_key: &SecretKey<'_, P>,
+    ciphertext: &Ciphertext<P>,
+    sealed: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let shared_secret = generic::try_decapsulate_boxed(ciphertext, secret_key)?;
+    dem::<P, A>(&shared_secret, aad, |key, nonce, aad| {
+        A::open(key, nonce, aad, sealed)
+    })
+}
+
+/// Encrypts `plaintext` to `public_key` like [`seal`] and returns the envelope described in
+/// the [module documentation](self)
+pub fn seal_envelope<P: ParameterSet, A: Aead, R: CryptoRng + RngCore>(
+    public_key: &PublicKey<'_, P>,
+    plaintext: &[u8],
+    aad: &[u8],
+    rng: &mut R,
+) -> Result<Vec<u8>, Error> {
+    let (ciphertext, sealed) = seal::<P, A, R>(public_key, plaintext, aad, rng)?;
+    Ok([&header::<P, A>()[..], ciphertext.as_ref(), &sealed].concat())
+}
+
+/// Decrypts an envelope created by [`seal_envelope`].
+///
+/// Fails with [`Error::VariantMismatch`] if the envelope was sealed for another parameter
+/// set, with [`Error::InvalidEnvelope`] if the header is malformed or names another AEAD and
+/// with [`Error::Aead`] if the envelope or `aad` were modified.
+pub fn open_envelope<P: ParameterSet, A: Aead>(
+    secret_key: &SecretKey<'_, P>,
+    envelope: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let variant = envelope_variant(envelope)?;
+    if variant != P::VARIANT {
+        return Err(Error::VariantMismatch {
+            expected: P::VARIANT,
+            actual: variant,
+        });
+    }
+    if envelope[..HEADER_BYTES] != header::<P, A>()
+        || envelope.len() < HEADER_BYTES + P::CIPHERTEXT_BYTES
+    {
+        return Err(Error::InvalidEnvelope);
+    }
+
+    let (ciphertext, sealed) = envelope[HEADER_BYTES..].split_at(P::CIPHERTEXT_BYTES);
+    let mut array = P::CiphertextArray::zeroed();
+    array.as_mut().copy_from_slice(ciphertext);
+    open::<P, A>(secret_key, &Ciphertext(array), sealed, aad)
+}
+
+/// Returns the parameter set named in the header of `envelope`, e.g. to pick the matching
+/// secret key. Fails with [`Error::InvalidEnvelope`] if the header is malformed.
+pub fn envelope_variant(envelope: &[u8]) -> Result<Variant, Error> {
+    match envelope {
+        [m0, m1, m2, VERSION, tag, ..]
+            if [*m0, *m1, *m2] == MAGIC && envelope.len() >= HEADER_BYTES =>
+        {
+            Variant::ALL
+                .get(usize::from(*tag).wrapping_sub(1))
+                .copied()
+                .ok_or(Error::InvalidEnvelope)
+        }
+        _ => Err(Error::InvalidEnvelope),
+    }
+}
+
+fn header<P: ParameterSet, A: Aead>() -> [u8; HEADER_BYTES] {
+    let index = Variant::ALL.iter().position(|&v| v == P::VARIANT);
+    let tag = index.expect("ALL contains every variant") as u8 + 1;
+    let [id_high, id_low] = A::ID.to_be_bytes();
+    [MAGIC[0], MAGIC[1], MAGIC[2], VERSION, tag, id_high, id_low]
+}
+
+/// Derives the key and nonce of the AEAD from `shared_secret` and runs `operation` with them
+/// and the header prepended to `aad`
+fn dem<P: ParameterSet, A: Aead>(
+    shared_secret: &SharedSecret<'_>,
+    aad: &[u8],
+    operation: impl FnOnce(&[u8], &[u8; NONCE_BYTES], &[u8]) -> Result<Vec<u8>, Error>,
+) -> Result<Vec<u8>, Error> {
+    let header = header::<P, A>();
+    let mut input = [0u8; HEADER_BYTES + crate::CRYPTO_BYTES];
+    input[..HEADER_BYTES].copy_from_slice(&header);
+    input[HEADER_BYTES..].copy_from_slice(shared_secret.as_array());
+    let mut key_nonce = [0u8; MAX_KEY_NONCE_BYTES];
+    let key_nonce_bytes = A::KEY_BYTES + NONCE_BYTES;
+    shake256(&mut key_nonce[..key_nonce_bytes], &input);
+
+    let (key, nonce) = key_nonce[..key_nonce_bytes].split_at(A::KEY_BYTES);
+    let nonce = nonce.try_into().expect("nonce has NONCE_BYTES bytes");
+    let result = operation(key, nonce, &[&header[..], aad].concat());
+
+    #[cfg(feature = "zeroize")]
+    {
+        use zeroize::Zeroize;
+        input.zeroize();
+        key_nonce.zeroize();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Mceliece348864, Mceliece348864f};
+
+    #[cfg(feature = "chacha20poly1305")]
+    type A = crate::aead::ChaCha20Poly1305;
+    #[cfg(not(feature = "chacha20poly1305"))]
+    type A = crate::aead::Aes256Gcm;
+
+    #[test]
+    fn test_envelope() {
+        std::thread::Builder::new()
+            .stack_size(4 * 1024 * 1024)
+            .spawn(|| {
+                let mut rng = crate::nist_aes_rng::NistDrbg::new([0u8; 48]);
+                let (public_key, secret_key) =
+                    generic::keypair_from_seed_boxed::<Mceliece348864>(&[1u8; 32]);
+
+                let (ciphertext, sealed) =
+                    seal::<_, A, _>(&public_key, b"message", b"aad", &mut rng).unwrap();
+                let plaintext = open::<_, A>(&secret_key, &ciphertext, &sealed, b"aad");
+                assert_eq!(plaintext.unwrap(), b"message");
+                let plaintext = open::<_, A>(&secret_key, &ciphertext, &sealed, b"other");
+                assert_eq!(plaintext, Err(Error::Aead));
+
+                let envelope =
+                    seal_envelope::<_, A, _>(&public_key, b"message", b"", &mut rng).unwrap();
+                assert_eq!(&envelope[..5], b"MCE\x01\x01");
+                assert_eq!(envelope[5..HEADER_BYTES], A::ID.to_be_bytes());
+                assert_eq!(
+                    envelope.len(),
+                    HEADER_BYTES + Mceliece348864::CIPHERTEXT_BYTES + 7 + 16
+                );
+                assert_eq!(envelope_variant(&envelope), Ok(Variant::Mceliece348864));
+                let plaintext = open_envelope::<_, A>(&secret_key, &envelope, b"");
+                assert_eq!(plaintext.unwrap(), b"message");
+
+                // the envelope is just the header, the ciphertext and the sealed message
+                let (ciphertext, sealed) =
+                    envelope[HEADER_BYTES..].split_at(Mceliece348864::CIPHERTEXT_BYTES);
+                let ciphertext = Ciphertext(ciphertext.try_into().unwrap());
+                let plaintext = open::<_, A>(&secret_key, &ciphertext, sealed, b"");
+                assert_eq!(plaintext.unwrap(), b"message");
+
+                let mut modified = envelope.clone();
+                *modified.last_mut().unwrap() ^= 1;
+                let plaintext = open_envelope::<_, A>(&secret_key, &modified, b"");
+                assert_eq!(plaintext, Err(Error::Aead));
+
+                let mut modified = envelope.clone();
+                modified[4] = 0x02;
+                let plaintext = open_envelope::<_, A>(&secret_key, &modified, b"");
+                assert_eq!(
+                    plaintext,
+                    Err(Error::VariantMismatch {
+                        expected: Variant::Mceliece348864,
+                        actual: Variant::Mceliece348864f,
+                    })
+                );
+                let (_, secret_key_f) =
+                    generic::keypair_from_seed_boxed::<Mceliece348864f>(&[1u8; 32]);
+                let plaintext = open_envelope::<_, A>(&secret_key_f, &envelope, b"");
+                assert!(matches!(plaintext, Err(Error::VariantMismatch { .. })));
+
+                for length in [0, 4, HEADER_BYTES, HEADER_BYTES + 95] {
+                    let plaintext = open_envelope::<_, A>(&secret_key, &envelope[..length], b"");
+                    assert_eq!(plaintext, Err(Error::InvalidEnvelope));
+                }
+                let mut modified = envelope.clone();
+                modified[4] = 0x0b;
+                assert_eq!(envelope_variant(&modified), Err(Error::InvalidEnvelope));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
@@ -0,0 +1,143 @@
+//! The error type of the fallible API
+
+use core::fmt;
+
+use crate::variant::Variant;
+
+/// Errors returned by the fallible functions of this crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Error {
+    /// A byte string does not have the length required by the parameter set.
+    InvalidLength {
+        variant: Variant,
+        expected: usize,
+        actual: usize,
+    },
+    /// Keys or ciphertexts of different parameter sets were combined.
+    VariantMismatch { expected: Variant, actual: Variant },
+    /// The public key is malformed.
+    InvalidPublicKey(PublicKeyError),
+    /// The padding bits of the ciphertext are not all zero. Only the mceliece6960119
+    /// variants have padding bits. Ciphertexts which merely fail to decode are not
+    /// reported, decapsulation then returns a pseudo-random shared secret instead.
+    InvalidCiphertext,
+    /// A section of the secret key is malformed.
+    InvalidSecretKey(SecretKeyError),
+    /// The random number generator failed to provide random bytes.
+    Rng,
+    /// Memory for a key could not be allocated.
+    Allocation,
+    /// The AEAD failed to authenticate a ciphertext, or the message is too long for it.
+    Aead,
+    /// The pre-shared key and its identifier of an HPKE mode are inconsistent: both must be
+    /// given in the PSK mode, and neither in the base mode.
+    InvalidPsk,
+    /// The sequence number of an HPKE context is exhausted.
+    MessageLimitReached,
+    /// More bytes were requested from an HPKE exporter than it can provide.
+    ExportTooLong,
+    /// The header of an envelope is malformed or names another AEAD, or the envelope is
+    /// truncated.
+    InvalidEnvelope,
+}
+
+/// The defect of a public key found by [`PublicKey::validate`](crate::PublicKey::validate).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PublicKeyError {
+    /// The padding bits at the end of each row of the matrix are not all zero. Only the
+    /// mceliece6960119 variants have padding bits.
+    Padding,
+}
+
+/// The section of a secret key found to be malformed by
+/// [`SecretKey::validate`](crate::SecretKey::validate).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SecretKeyError {
+    /// The key is not the expansion of the seed stored in its first 32 bytes.
+    Seed,
+    /// The pivots field is not well-formed for the parameter set.
+    Pivots,
+    /// The Goppa polynomial has invalid coefficients or is not irreducible.
+    GoppaPolynomial,
+    /// The Beneš network control bits are not the canonical ones of the permutation they
+    /// describe, or the support contains a root of the Goppa polynomial.
+    ControlBits,
+    /// The secret key does not belong to the given public key.
+    PublicKeyMismatch,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidLength {
+                variant,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "invalid length for {variant}: expected {expected} bytes, got {actual}"
+            ),
+            Error::VariantMismatch { expected, actual } => {
+                write!(
+                    f,
+                    "parameter set mismatch: expected {expected}, got {actual}"
+                )
+            }
+            Error::InvalidPublicKey(error) => write!(f, "invalid public key: {error}"),
+            Error::InvalidCiphertext => {
+                f.write_str("invalid ciphertext: padding bits are not zero")
+            }
+            Error::InvalidSecretKey(error) => write!(f, "invalid secret key: {error}"),
+            Error::Rng => f.write_str("random number generator failure"),
+            Error::Allocation => f.write_str("memory allocation failure"),
+            Error::Aead => f.write_str("authenticated encryption failure"),
+            Error::InvalidPsk => f.write_str("inconsistent pre-shared key and identifier"),
+            Error::MessageLimitReached => f.write_str("message limit of the context reached"),
+            Error::ExportTooLong => f.write_str("exported secret too long"),
+            Error::InvalidEnvelope => f.write_str("malformed envelope"),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
+
+impl fmt::Display for PublicKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            PublicKeyError::Padding => "padding bits are not zero",
+        })
+    }
+}
+
+impl fmt::Display for SecretKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SecretKeyError::Seed => "key does not match its seed",
+            SecretKeyError::Pivots => "malformed pivots",
+            SecretKeyError::GoppaPolynomial => "Goppa polynomial is not irreducible",
+            SecretKeyError::ControlBits => "malformed control bits",
+            SecretKeyError::PublicKeyMismatch => "key does not belong to the public key",
+        })
+    }
+}
+
+impl From<rand::Error> for Error {
+    fn from(_: rand::Error) -> Self {
+        Error::Rng
+    }
+}
+
+impl From<PublicKeyError> for Error {
+    fn from(error: PublicKeyError) -> Self {
+        Error::InvalidPublicKey(error)
+    }
+}
+
+impl From<SecretKeyError> for Error {
+    fn from(error: SecretKeyError) -> Self {
+        Error::InvalidSecretKey(error)
+    }
+}
@@ -0,0 +1,365 @@
+//! Additive FFT over `GF(2^m)` and its transpose
+//!
+//! [`fft`] evaluates a polynomial at all `2^m` field elements and [`fft_tr`] computes the power
+//! sums `Σ v[a] · a^j` of a vector indexed by the field elements. Both follow Gao and Mateer,
+//! “Additive Fast Fourier Transforms over Finite Fields” (2010), as adapted to Classic McEliece
+//! in “McBits: fast constant-time code-based cryptography” by Bernstein, Chou and Schwabe
+//! <https://binary.cr.yp.to/mcbits-20130616.pdf>.
+//!
+//! Field elements are in their natural order, so `out[a] = f(a)`. The transforms take
+//! `O(2^m · m)` field operations instead of the `O(2^m · t)` of Horner's rule, and their
+//! control flow and memory accesses only depend on the parameter set.
+
+use core::marker::PhantomData;
+
+use crate::gf::{gf_inv, gf_mul, Gf};
+use crate::params::{ParameterSet, MAX_GFBITS, MAX_SYS_T};
+use crate::vec;
+
+/// The subspace bases of all recursion levels
+///
+/// Level `d` evaluates at the span of a basis `β` of `m - d` elements, starting with
+/// `1, 2, 4, …` at level 0. Its points are `β_last · (a + c)` with `c` in `{0, 1}` and `a` in
+/// the span of `δ_i = β_i / β_last`, so the next level uses the basis `δ_i^2 + δ_i`.
+///
+/// The bases only depend on the field, [`Bases::of`] is computed at compile time.
+struct Bases {
+    /// `β_last` of each level
+    twist: [Gf; MAX_GFBITS],
+    /// `δ_0, …, δ_{m-d-2}` of each level `d`
+    delta: [[Gf; MAX_GFBITS]; MAX_GFBITS],
+}
+
+/// Holds the [`Bases`] of the field of `P` as an associated constant
+struct BasesOf<P>(PhantomData<P>);
+
+impl<P: ParameterSet> BasesOf<P> {
+    const BASES: Bases = Bases::new::<P>();
+}
+
+impl Bases {
+    /// The bases of the field of `P`
+    fn of<P: ParameterSet>() -> &'static Self {
+        &BasesOf::<P>::BASES
+    }
+
+    const fn new<P: ParameterSet>() -> Self {
+        let mut bases = Bases {
+            twist: [0; MAX_GFBITS],
+            delta: [[0; MAX_GFBITS]; MAX_GFBITS],
+        };
+
+        let mut beta = [0 as Gf; MAX_GFBITS];
+        let mut i = 0;
+        while i < P::GFBITS {
+            beta[i] = 1 << i;
+            i += 1;
+        }
+
+        let mut d = 0;
+        while d < P::GFBITS {
+            let k = P::GFBITS - d;
+            let last = beta[k - 1];
+            let last_inv = gf_inv::<P>(last);
+            bases.twist[d] = last;
+
+            let mut i = 0;
+            while i < k - 1 {
+                let delta = gf_mul::<P>(beta[i], last_inv);
+                bases.delta[d][i] = delta;
+                beta[i] = gf_mul::<P>(delta, delta) ^ delta;
+                i += 1;
+            }
+            d += 1;
+        }
+
+        bases
+    }
+
+    /// Computes `out[k] = Σ k_i δ_i` for the basis `δ` of level `d`, where `k_i` are the bits of `k`
+    fn span(&self, d: usize, out: &mut [Gf]) {
+        out[0] = 0;
+        for k in 1..out.len() {
+            out[k] = out[k & (k - 1)] ^ self.delta[d][k.trailing_zeros() as usize];
+        }
+    }
+}
+
+/// The number of levels after which all polynomials of `len` coefficients are constants
+fn depth(len: usize) -> usize {
+    let mut d = 0;
+    while (1 << d) < len {
+        d += 1;
+    }
+    d
+}
+
+/// Rewrites `c` in place from coefficients of powers of `x` to the coefficients of
+/// `(x^2 + x)^i`, `x · (x^2 + x)^i` in the order `1, x, (x^2 + x), x · (x^2 + x), …`
+fn taylor(c: &mut [Gf]) {
+    let len = c.len();
+    if len <= 2 {
+        return;
+    }
+
+    // the largest power of two with `2 · tau < len`, so that `(x^2 + x)^tau = x^(2 tau) + x^tau`
+    let mut tau = 1;
+    while 4 * tau < len {
+        tau *= 2;
+    }
+
+    for i in (2 * tau..len).rev() {
+        c[i - tau] ^= c[i];
+    }
+
+    let (lo, hi) = c.split_at_mut(2 * tau);
+    taylor(lo);
+    taylor(hi);
+}
+
+/// The transpose of [`taylor`]
+fn taylor_tr(c: &mut [Gf]) {
+    let len = c.len();
+    if len <= 2 {
+        return;
+    }
+
+    let mut tau = 1;
+    while 4 * tau < len {
+        tau *= 2;
+    }
+
+    let (lo, hi) = c.split_at_mut(2 * tau);
+    taylor_tr(lo);
+    taylor_tr(hi);
+
+    for i in 2 * tau..len {
+        c[i] ^= c[i - tau];
+    }
+}
+
+/// Splits `f` of level `d` into the polynomials of the next level down to level `depth`,
+/// where each is a constant which is written to its block of `out`
+fn expand<P: ParameterSet>(out: &mut [Gf], f: &[Gf], bases: &Bases, d: usize, depth: usize) {
+    if d == depth {
+        debug_assert!(f.len() <= 1);
+        out.fill(f.first().copied().unwrap_or(0));
+        return;
+    }
+
+    let mut c_buf = [0 as Gf; 2 * MAX_SYS_T];
+    let c = &mut c_buf[..f.len()];
+
+    // g(x) = f(β_last · x)
+    let mut power: Gf = 1;
+    for (itr_c, &itr_f) in c.iter_mut().zip(f) {
+        *itr_c = gf_mul::<P>(itr_f, power);
+        power = gf_mul::<P>(power, bases.twist[d]);
+    }
+
+    // g(x) = g0(x^2 + x) + x · g1(x^2 + x)
+    taylor(c);
+
+    let mut g0_buf = [0 as Gf; MAX_SYS_T];
+    let mut g1_buf = [0 as Gf; MAX_SYS_T];
+    let g0 = &mut g0_buf[..c.len().div_ceil(2)];
+    let g1 = &mut g1_buf[..c.len() / 2];
+    for (i, &itr_c) in c.iter().enumerate() {
+        if i % 2 == 0 {
+            g0[i / 2] = itr_c;
+        } else {
+            g1[i / 2] = itr_c;
+        }
+    }
+
+    let (lo, hi) = out.split_at_mut(out.len() / 2);
+    expand::<P>(lo, g0, bases, d + 1, depth);
+    expand::<P>(hi, g1, bases, d + 1, depth);
+}
+
+/// The transpose of [`expand`]: collects the sums of the blocks of `values` into the
+/// coefficients `out` of level `d`
+fn expand_tr<P: ParameterSet>(
+    out: &mut [Gf],
+    values: &[Gf],
+    bases: &Bases,
+    d: usize,
+    depth: usize,
+) {
+    if d == depth {
+        debug_assert!(out.len() <= 1);
+        if let Some(itr_out) = out.first_mut() {
+            *itr_out = values.iter().fold(0, |acc, &v| acc ^ v);
+        }
+        return;
+    }
+
+    let mut g0_buf = [0 as Gf; MAX_SYS_T];
+    let mut g1_buf = [0 as Gf; MAX_SYS_T];
+    let g0 = &mut g0_buf[..out.len().div_ceil(2)];
+    let g1 = &mut g1_buf[..out.len() / 2];
+
+    let (lo, hi) = values.split_at(values.len() / 2);
+    expand_tr::<P>(g0, lo, bases, d + 1, depth);
+    expand_tr::<P>(g1, hi, bases, d + 1, depth);
+
+    let mut c_buf = [0 as Gf; 2 * MAX_SYS_T];
+    let c = &mut c_buf[..out.len()];
+    for (i, itr_c) in c.iter_mut().enumerate() {
+        *itr_c = if i % 2 == 0 { g0[i / 2] } else { g1[i / 2] };
+    }
+
+    taylor_tr(c);
+
+    let mut power: Gf = 1;
+    for (itr_out, &itr_c) in out.iter_mut().zip(c.iter()) {
+        *itr_out = gf_mul::<P>(itr_c, power);
+        power = gf_mul::<P>(power, bases.twist[d]);
+    }
+}
+
+/// Combines the evaluations of `g0` and `g1` of levels `depth - 1` down to 0:
+/// `f(a) = g0(a^2 + a) + a · g1(a^2 + a)` and `f(a + 1) = f(a) + g1(a^2 + a)`
+///
+/// With the feature `vec`, the bitsliced [`crate::vec::butterflies`] is used instead.
+fn butterflies<P: ParameterSet>(values: &mut [Gf], bases: &Bases, depth: usize) {
+    if cfg!(feature = "vec") {
+        return vec::butterflies::<P>(values, depth, |d, a| bases.span(d, a));
+    }
+
+    let mut a_buf = [0 as Gf; 1 << (MAX_GFBITS - 1)];
+
+    for d in (0..depth).rev() {
+        let h = 1 << (P::GFBITS - d - 1);
+        let a = &mut a_buf[..h];
+        bases.span(d, a);
+
+        for block in values.chunks_mut(2 * h) {
+            let (v0, v1) = block.split_at_mut(h);
+            for ((x0, x1), &itr_a) in v0.iter_mut().zip(v1.iter_mut()).zip(a.iter()) {
+                *x0 ^= gf_mul::<P>(itr_a, *x1);
+                *x1 ^= *x0;
+            }
+        }
+    }
+}
+
+/// The transpose of [`butterflies`], for levels 0 up to `depth - 1`
+///
+/// With the feature `vec`, the bitsliced [`crate::vec::butterflies_tr`] is used instead.
+fn butterflies_tr<P: ParameterSet>(values: &mut [Gf], bases: &Bases, depth: usize) {
+    if cfg!(feature = "vec") {
+        return vec::butterflies_tr::<P>(values, depth, |d, a| bases.span(d, a));
+    }
+
+    let mut a_buf = [0 as Gf; 1 << (MAX_GFBITS - 1)];
+
+    for d in 0..depth {
+        let h = 1 << (P::GFBITS - d - 1);
+        let a = &mut a_buf[..h];
+        bases.span(d, a);
+
+        for block in values.chunks_mut(2 * h) {
+            let (v0, v1) = block.split_at_mut(h);
+            for ((x0, x1), &itr_a) in v0.iter_mut().zip(v1.iter_mut()).zip(a.iter()) {
+                *x0 ^= *x1;
+                *x1 ^= gf_mul::<P>(itr_a, *x0);
+            }
+        }
+    }
+}
+
+/// Evaluates the polynomial `f` with at most `2 t` coefficients at all field elements:
+/// `out[a] = f(a)` for `a` in `0 .. 2^m`
+pub(crate) fn fft<P: ParameterSet>(out: &mut [Gf], f: &[Gf]) {
+    debug_assert_eq!(out.len(), 1 << P::GFBITS);
+    debug_assert!(f.len() <= 2 * P::SYS_T);
+
+    let bases = Bases::of::<P>();
+    let depth = depth(f.len());
+
+    expand::<P>(out, f, bases, 0, depth);
+    butterflies::<P>(out, bases, depth);
+}
+
+/// The transpose of [`fft`]: given `v` indexed by all field elements, computes
+/// `out[j] = Σ v[a] · a^j` for `j` in `0 .. out.len()`. `v` is overwritten.
+pub(crate) fn fft_tr<P: ParameterSet>(out: &mut [Gf], v: &mut [Gf]) {
+    debug_assert_eq!(v.len(), 1 << P::GFBITS);
+    debug_assert!(out.len() <= 2 * P::SYS_T);
+
+    let bases = Bases::of::<P>();
+    let depth = depth(out.len());
+
+    butterflies_tr::<P>(v, bases, depth);
+    expand_tr::<P>(out, v, bases, 0, depth);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gf::gf_add;
+    use crate::params::{Mceliece348864, Mceliece6960119, MAX_SYS_N};
+
+    /// Horner's rule for a polynomial of any degree
+    fn eval<P: ParameterSet>(f: &[Gf], a: Gf) -> Gf {
+        f.iter().rev().fold(0, |r, &c| gf_add(gf_mul::<P>(r, a), c))
+    }
+
+    fn check_fft<P: ParameterSet>() {
+        let mut f = [0 as Gf; 2 * MAX_SYS_T];
+        let mut v = [0 as Gf; MAX_SYS_N];
+        let mut state: u64 = 1;
+        for itr in f.iter_mut().chain(v.iter_mut()) {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            *itr = state as Gf & P::GFMASK as Gf;
+        }
+        let v = &mut v[..1 << P::GFBITS];
+
+        for len in [1, 2, 3, P::SYS_T + 1, 2 * P::SYS_T] {
+            let mut out = [0 as Gf; MAX_SYS_N];
+            fft::<P>(&mut out[..1 << P::GFBITS], &f[..len]);
+            for (a, &itr_out) in out[..1 << P::GFBITS].iter().enumerate() {
+                assert_eq!(itr_out, eval::<P>(&f[..len], a as Gf));
+            }
+
+            let mut sums = [0 as Gf; 2 * MAX_SYS_T];
+            let mut expected = [0 as Gf; 2 * MAX_SYS_T];
+            for (a, &itr_v) in v.iter().enumerate() {
+                let mut power = itr_v;
+                for itr_expected in expected[..len].iter_mut() {
+                    *itr_expected ^= power;
+                    power = gf_mul::<P>(power, a as Gf);
+                }
+            }
+            let mut w = [0 as Gf; MAX_SYS_N];
+            w[..v.len()].copy_from_slice(v);
+            fft_tr::<P>(&mut sums[..len], &mut w[..1 << P::GFBITS]);
+            assert_eq!(sums, expected);
+
+            // the bitsliced butterflies, whether or not the feature `vec` is enabled
+            let bases = Bases::new::<P>();
+            let depth = depth(len);
+            let values = &mut out[..1 << P::GFBITS];
+            expand::<P>(values, &f[..len], &bases, 0, depth);
+            vec::butterflies::<P>(values, depth, |d, a| bases.span(d, a));
+            for (a, &itr_out) in values.iter().enumerate() {
+                assert_eq!(itr_out, eval::<P>(&f[..len], a as Gf));
+            }
+
+            let mut sums = [0 as Gf; 2 * MAX_SYS_T];
+            w[..v.len()].copy_from_slice(v);
+            vec::butterflies_tr::<P>(&mut w[..1 << P::GFBITS], depth, |d, a| bases.span(d, a));
+            expand_tr::<P>(&mut sums[..len], &w[..1 << P::GFBITS], &bases, 0, depth);
+            assert_eq!(sums, expected);
+        }
+    }
+
+    #[test]
+    fn test_fft() {
+        check_fft::<Mceliece348864>();
+        check_fft::<Mceliece6960119>();
+    }
+}
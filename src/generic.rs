@@ -0,0 +1,395 @@
+//! KEM functions generic over the [`ParameterSet`].
+//!
+//! These are the counterparts of the functions at the crate root, which are fixed to the
+//! [`DefaultParameterSet`](crate::DefaultParameterSet) selected by feature flag. Keys and
+//! ciphertexts carry their parameter set in the type, so several variants can be used in
+//! the same program and mixing them up is a compile error:
+//!
+//! ```compile_fail
+//! use classic_mceliece_rust::generic::{decapsulate_boxed, encapsulate_boxed, keypair_boxed};
+//! use classic_mceliece_rust::{Mceliece460896, Mceliece6688128};
+//!
+//! let mut rng = rand::thread_rng();
+//! let (public_key, _) = keypair_boxed::<Mceliece460896, _>(&mut rng);
+//! let (_, secret_key) = keypair_boxed::<Mceliece6688128, _>(&mut rng);
+//! let (ciphertext, _) = encapsulate_boxed(&public_key, &mut rng);
+//! // A mceliece460896 ciphertext can not be decapsulated with a mceliece6688128 key
+//! let _ = decapsulate_boxed(&ciphertext, &secret_key);
+//! ```
+
+use rand::{CryptoRng, RngCore};
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+use crate::params::{ByteArray, ParameterSet};
+use crate::pk_gen::PkMatrix;
+use crate::{operations, Ciphertext, KeyBufferMut, PublicKey, SecretKey, SharedSecret};
+use crate::{Error, CRYPTO_BYTES};
+
+/// KEM Keypair generation for the parameter set `P`.
+///
+/// Generate a public and secret key.
+/// The public key is meant to be shared with any party,
+/// but access to the secret key must be limited to the generating party.
+///
+/// # Panics
+///
+/// Panics if `rng` fails to provide random bytes, see [`try_keypair`].
+pub fn keypair<'public, 'secret, P: ParameterSet, R: CryptoRng + RngCore>(
+    public_key_buf: &'public mut P::PublicKeyArray,
+    secret_key_buf: &'secret mut P::SecretKeyArray,
+    rng: &mut R,
+) -> (PublicKey<'public, P>, SecretKey<'secret, P>) {
+    let result = operations::crypto_kem_keypair::<P, _>(
+        public_key_buf.as_mut(),
+        secret_key_buf.as_mut(),
+        rng,
+        &mut PkMatrix::new(),
+    );
+    expect_rng(result);
+
+    (
+        PublicKey(KeyBufferMut::Borrowed(public_key_buf)),
+        SecretKey(KeyBufferMut::Borrowed(secret_key_buf)),
+    )
+}
+
+/// Convenient wrapper around [`keypair`] that stores the public and private keys on the heap
+/// and returns them with the ``'static`` lifetime.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn keypair_boxed<P: ParameterSet, R: CryptoRng + RngCore>(
+    rng: &mut R,
+) -> (PublicKey<'static, P>, SecretKey<'static, P>) {
+    let mut public_key_buf = P::PublicKeyArray::zeroed_boxed();
+    let mut secret_key_buf = P::SecretKeyArray::zeroed_boxed();
+
+    let result = operations::crypto_kem_keypair::<P, _>(
+        (*public_key_buf).as_mut(),
+        (*secret_key_buf).as_mut(),
+        rng,
+        &mut PkMatrix::new(),
+    );
+    expect_rng(result);
+
+    (
+        PublicKey(KeyBufferMut::Owned(public_key_buf)),
+        SecretKey(KeyBufferMut::Owned(secret_key_buf)),
+    )
+}
+
+/// Fallible KEM Keypair generation for the parameter set `P`.
+///
+/// Like [`keypair`], but returns [`Error::Rng`] instead of panicking if `rng` fails. Both
+/// buffers are then set to all 0's, so no partially generated key material is left behind.
+/// Returns [`Error::Allocation`] without touching the buffers if the scratch space of key
+/// generation can not be allocated, which is on the heap with the `alloc` feature.
+pub fn try_keypair<'public, 'secret, P: ParameterSet, R: CryptoRng + RngCore>(
+    public_key_buf: &'public mut P::PublicKeyArray,
+    secret_key_buf: &'secret mut P::SecretKeyArray,
+    rng: &mut R,
+) -> Result<(PublicKey<'public, P>, SecretKey<'secret, P>), Error> {
+    operations::crypto_kem_keypair::<P, _>(
+        public_key_buf.as_mut(),
+        secret_key_buf.as_mut(),
+        rng,
+        &mut PkMatrix::try_new()?,
+    )?;
+
+    Ok((
+        PublicKey(KeyBufferMut::Borrowed(public_key_buf)),
+        SecretKey(KeyBufferMut::Borrowed(secret_key_buf)),
+    ))
+}
+
+/// Convenient wrapper around [`try_keypair`] that stores the public and private keys on the
+/// heap and returns them with the ``'static`` lifetime. Returns [`Error::Allocation`] if the
+/// keys or the scratch space of key generation can not be allocated.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn try_keypair_boxed<P: ParameterSet, R: CryptoRng + RngCore>(
+    rng: &mut R,
+) -> Result<(PublicKey<'static, P>, SecretKey<'static, P>), Error> {
+    let mut public_key_buf = P::PublicKeyArray::try_zeroed_boxed().ok_or(Error::Allocation)?;
+    let mut secret_key_buf = P::SecretKeyArray::try_zeroed_boxed().ok_or(Error::Allocation)?;
+
+    operations::crypto_kem_keypair::<P, _>(
+        (*public_key_buf).as_mut(),
+        (*secret_key_buf).as_mut(),
+        rng,
+        &mut PkMatrix::try_new()?,
+    )?;
+
+    Ok((
+        PublicKey(KeyBufferMut::Owned(public_key_buf)),
+        SecretKey(KeyBufferMut::Owned(secret_key_buf)),
+    ))
+}
+
+/// Deterministic KEM Keypair generation for the parameter set `P`.
+///
+/// Like [`keypair`], but derives the keys from the 32 bytes `seed` instead of drawing
+/// randomness. The same seed always results in the same keys, so it must be kept as secret
+/// as the secret key itself.
+pub fn keypair_from_seed<'public, 'secret, P: ParameterSet>(
+    public_key_buf: &'public mut P::PublicKeyArray,
+    secret_key_buf: &'secret mut P::SecretKeyArray,
+    seed: &[u8; 32],
+) -> (PublicKey<'public, P>, SecretKey<'secret, P>) {
+    operations::crypto_kem_keypair_from_seed::<P>(
+        Some(public_key_buf.as_mut()),
+        secret_key_buf.as_mut(),
+        seed,
+        &mut PkMatrix::new(),
+    );
+
+    (
+        PublicKey(KeyBufferMut::Borrowed(public_key_buf)),
+        SecretKey(KeyBufferMut::Borrowed(secret_key_buf)),
+    )
+}
+
+/// Convenient wrapper around [`keypair_from_seed`] that stores the public and private keys
+/// on the heap and returns them with the ``'static`` lifetime.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn keypair_from_seed_boxed<P: ParameterSet>(
+    seed: &[u8; 32],
+) -> (PublicKey<'static, P>, SecretKey<'static, P>) {
+    let mut public_key_buf = P::PublicKeyArray::zeroed_boxed();
+    let mut secret_key_buf = P::SecretKeyArray::zeroed_boxed();
+
+    operations::crypto_kem_keypair_from_seed::<P>(
+        Some((*public_key_buf).as_mut()),
+        (*secret_key_buf).as_mut(),
+        seed,
+        &mut PkMatrix::new(),
+    );
+
+    (
+        PublicKey(KeyBufferMut::Owned(public_key_buf)),
+        SecretKey(KeyBufferMut::Owned(secret_key_buf)),
+    )
+}
+
+/// KEM Encapsulation for the parameter set of `public_key`.
+///
+/// Given a public key `public_key`, compute a shared key.
+/// The returned ciphertext should be sent back to the entity holding
+/// the secret key corresponding to public key given here, so they can compute
+/// the same shared key.
+///
+/// If `public_key` is malformed (see [`PublicKey::validate`]), the syndrome part of the
+/// ciphertext and the shared secret are all zero. Check untrusted keys with
+/// [`PublicKey::validate`] first, or use [`try_encapsulate`], which reports the error.
+///
+/// # Panics
+///
+/// Panics if `rng` fails to provide random bytes.
+pub fn encapsulate<'shared_secret, P: ParameterSet, R: CryptoRng + RngCore>(
+    public_key: &PublicKey<'_, P>,
+    shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
+    rng: &mut R,
+) -> (Ciphertext<P>, SharedSecret<'shared_secret>) {
+    expect_rng(encapsulate_into(
+        public_key,
+        KeyBufferMut::Borrowed(shared_secret_buf),
+        rng,
+    ))
+}
+
+/// Convenient wrapper around [`encapsulate`] that stores the shared secret on the heap
+/// and returns it with the ``'static`` lifetime.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn encapsulate_boxed<P: ParameterSet, R: CryptoRng + RngCore>(
+    public_key: &PublicKey<'_, P>,
+    rng: &mut R,
+) -> (Ciphertext<P>, SharedSecret<'static>) {
+    let shared_secret_buf = KeyBufferMut::Owned(Box::new([0u8; CRYPTO_BYTES]));
+    expect_rng(encapsulate_into(public_key, shared_secret_buf, rng))
+}
+
+/// Fallible KEM Encapsulation for the parameter set of `public_key`.
+///
+/// Like [`encapsulate`], but fails without drawing randomness if `public_key` is
+/// malformed, see [`PublicKey::validate`], and returns [`Error::Rng`] instead of panicking
+/// if `rng` fails. In the latter case `shared_secret_buf` is set to all 0's.
+pub fn try_encapsulate<'shared_secret, P: ParameterSet, R: CryptoRng + RngCore>(
+    public_key: &PublicKey<'_, P>,
+    shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
+    rng: &mut R,
+) -> Result<(Ciphertext<P>, SharedSecret<'shared_secret>), Error> {
+    public_key.validate()?;
+    encapsulate_into(public_key, KeyBufferMut::Borrowed(shared_secret_buf), rng)
+}
+
+/// Convenient wrapper around [`try_encapsulate`] that stores the shared secret on the heap
+/// and returns it with the ``'static`` lifetime. Returns [`Error::Allocation`] if the
+/// shared secret can not be allocated.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn try_encapsulate_boxed<P: ParameterSet, R: CryptoRng + RngCore>(
+    public_key: &PublicKey<'_, P>,
+    rng: &mut R,
+) -> Result<(Ciphertext<P>, SharedSecret<'static>), Error> {
+    public_key.validate()?;
+    let shared_secret_buf = KeyBufferMut::Owned(try_box_shared_secret()?);
+    encapsulate_into(public_key, shared_secret_buf, rng)
+}
+
+/// Derandomized KEM Encapsulation for the parameter set of `public_key`.
+///
+/// Like [`try_encapsulate`], but the error vector is derived from the 32 bytes `coins`
+/// instead of drawing randomness: it is sampled exactly as [`encapsulate`] does, reading the
+/// output of SHAKE256(3 || `coins`) instead of the random number generator. The same coins
+/// and public key always result in the same ciphertext and shared secret, so the coins
+/// must be as secret as the shared secret and must never be reused.
+///
+/// Fails if `public_key` is malformed, see [`PublicKey::validate`].
+pub fn encapsulate_derand<'shared_secret, P: ParameterSet>(
+    public_key: &PublicKey<'_, P>,
+    coins: &[u8; 32],
+    shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
+) -> Result<(Ciphertext<P>, SharedSecret<'shared_secret>), Error> {
+    try_encapsulate(
+        public_key,
+        shared_secret_buf,
+        &mut operations::coins_rng(coins),
+    )
+}
+
+/// Convenient wrapper around [`encapsulate_derand`] that stores the shared secret on the
+/// heap and returns it with the ``'static`` lifetime.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn encapsulate_derand_boxed<P: ParameterSet>(
+    public_key: &PublicKey<'_, P>,
+    coins: &[u8; 32],
+) -> Result<(Ciphertext<P>, SharedSecret<'static>), Error> {
+    try_encapsulate_boxed(public_key, &mut operations::coins_rng(coins))
+}
+
+/// Encapsulation without checking `public_key`. A malformed key results in an all-zero
+/// syndrome and shared secret.
+fn encapsulate_into<'shared_secret, P: ParameterSet, R: CryptoRng + RngCore>(
+    public_key: &PublicKey<'_, P>,
+    mut shared_secret_buf: KeyBufferMut<'shared_secret, [u8; CRYPTO_BYTES]>,
+    rng: &mut R,
+) -> Result<(Ciphertext<P>, SharedSecret<'shared_secret>), Error> {
+    let mut ciphertext_buf = P::CiphertextArray::zeroed();
+
+    operations::crypto_kem_enc::<P, _>(
+        ciphertext_buf.as_mut(),
+        shared_secret_buf.as_mut(),
+        public_key.as_ref(),
+        rng,
+    )?;
+
+    Ok((Ciphertext(ciphertext_buf), SharedSecret(shared_secret_buf)))
+}
+
+/// KEM Decapsulation for the parameter set of `ciphertext` and `secret_key`.
+///
+/// Given a secret key `secret_key` and a ciphertext `ciphertext`,
+/// determine the shared key negotiated by both parties.
+pub fn decapsulate<'shared_secret, P: ParameterSet>(
+    ciphertext: &Ciphertext<P>,
+    secret_key: &SecretKey<'_, P>,
+    shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
+) -> SharedSecret<'shared_secret> {
+    decapsulate_into(
+        ciphertext,
+        secret_key,
+        KeyBufferMut::Borrowed(shared_secret_buf),
+    )
+}
+
+/// Convenient wrapper around [`decapsulate`] that stores the shared secret on the heap
+/// and returns it with the ``'static`` lifetime.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn decapsulate_boxed<P: ParameterSet>(
+    ciphertext: &Ciphertext<P>,
+    secret_key: &SecretKey<'_, P>,
+) -> SharedSecret<'static> {
+    let shared_secret_buf = KeyBufferMut::Owned(Box::new([0u8; CRYPTO_BYTES]));
+    decapsulate_into(ciphertext, secret_key, shared_secret_buf)
+}
+
+/// Fallible KEM Decapsulation for the parameter set of `ciphertext` and `secret_key`.
+///
+/// Like [`decapsulate`], but returns [`Error::InvalidCiphertext`] if the padding bits of
+/// `ciphertext` are not zero (mceliece6960119 only) instead of an all-ones shared secret.
+/// Implicit rejection is preserved: a ciphertext that does not decode still results in a
+/// pseudo-random shared secret and no error, so this reveals nothing about the secret key.
+pub fn try_decapsulate<'shared_secret, P: ParameterSet>(
+    ciphertext: &Ciphertext<P>,
+    secret_key: &SecretKey<'_, P>,
+    shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
+) -> Result<SharedSecret<'shared_secret>, Error> {
+    try_decapsulate_into(
+        ciphertext,
+        secret_key,
+        KeyBufferMut::Borrowed(shared_secret_buf),
+    )
+}
+
+/// Convenient wrapper around [`try_decapsulate`] that stores the shared secret on the heap
+/// and returns it with the ``'static`` lifetime.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn try_decapsulate_boxed<P: ParameterSet>(
+    ciphertext: &Ciphertext<P>,
+    secret_key: &SecretKey<'_, P>,
+) -> Result<SharedSecret<'static>, Error> {
+    let shared_secret_buf = KeyBufferMut::Owned(try_box_shared_secret()?);
+    try_decapsulate_into(ciphertext, secret_key, shared_secret_buf)
+}
+
+fn decapsulate_into<'shared_secret, P: ParameterSet>(
+    ciphertext: &Ciphertext<P>,
+    secret_key: &SecretKey<'_, P>,
+    mut shared_secret_buf: KeyBufferMut<'shared_secret, [u8; CRYPTO_BYTES]>,
+) -> SharedSecret<'shared_secret> {
+    operations::crypto_kem_dec::<P>(
+        shared_secret_buf.as_mut(),
+        ciphertext.as_ref(),
+        secret_key.as_ref(),
+    );
+
+    SharedSecret(shared_secret_buf)
+}
+
+fn try_decapsulate_into<'shared_secret, P: ParameterSet>(
+    ciphertext: &Ciphertext<P>,
+    secret_key: &SecretKey<'_, P>,
+    mut shared_secret_buf: KeyBufferMut<'shared_secret, [u8; CRYPTO_BYTES]>,
+) -> Result<SharedSecret<'shared_secret>, Error> {
+    let padding_ok = operations::crypto_kem_dec::<P>(
+        shared_secret_buf.as_mut(),
+        ciphertext.as_ref(),
+        secret_key.as_ref(),
+    );
+
+    // the padding bits are public, so branching on them leaks nothing
+    if padding_ok != 0 {
+        return Err(Error::InvalidCiphertext);
+    }
+
+    Ok(SharedSecret(shared_secret_buf))
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn try_box_shared_secret() -> Result<Box<[u8; CRYPTO_BYTES]>, Error> {
+    <[u8; CRYPTO_BYTES]>::try_zeroed_boxed().ok_or(Error::Allocation)
+}
+
+/// Unwraps the result of an operation drawing randomness for the infallible API.
+pub(crate) fn expect_rng<T>(result: Result<T, impl core::fmt::Display>) -> T {
+    match result {
+        Ok(value) => value,
+        Err(error) => panic!("failed to draw random bytes: {error}"),
+    }
+}
@@ -1,8 +1,13 @@
 //! Module to implement Galois field operations
 
-use crate::params::{GFBITS, GFMASK, SYS_T};
+use crate::params::{ParameterSet, MAX_SYS_T};
 pub(crate) type Gf = u16;
 
+const GF12_BITS: usize = 12;
+const GF12_MASK: usize = (1 << GF12_BITS) - 1;
+const GF13_BITS: usize = 13;
+const GF13_MASK: usize = (1 << GF13_BITS) - 1;
+
 /// Does Gf element `a` have value 0? Returns yes (8191 = `u16::MAX/8`) or no (0) as Gf element.
 pub(crate) fn gf_iszero(a: Gf) -> Gf {
     let mut t = (a as u32).wrapping_sub(1u32);
@@ -18,8 +23,16 @@ pub(crate) fn gf_add(in0: Gf, in1: Gf) -> Gf {
 }
 
 /// Multiplication of two Gf elements.
-#[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-pub(crate) fn gf_mul(in0: Gf, in1: Gf) -> Gf {
+pub(crate) const fn gf_mul<P: ParameterSet>(in0: Gf, in1: Gf) -> Gf {
+    if P::GFBITS == GF12_BITS {
+        gf12_mul(in0, in1)
+    } else {
+        gf13_mul(in0, in1)
+    }
+}
+
+/// Multiplication of two elements of GF(2^12).
+const fn gf12_mul(in0: Gf, in1: Gf) -> Gf {
     let (mut tmp, t0, t1, mut t): (u64, u64, u64, u64);
 
     t0 = in0 as u64;
@@ -28,8 +41,10 @@ pub(crate) fn gf_mul(in0: Gf, in1: Gf) -> Gf {
     tmp = t0 * (t1 & 1); // if LSB 0, tmp will be 0, otherwise value of t0
 
     // (t1 & (1 << i)) ⇒ is either t1 to the power of i or zero
-    for i in 1..GFBITS {
+    let mut i = 1;
+    while i < GF12_BITS {
         tmp ^= t0 * (t1 & (1 << i));
+        i += 1;
     }
 
     // polynomial reduction
@@ -41,20 +56,21 @@ pub(crate) fn gf_mul(in0: Gf, in1: Gf) -> Gf {
     tmp ^= t >> 9;
     tmp ^= t >> 12;
 
-    tmp as u16 & GFMASK as u16
+    tmp as u16 & GF12_MASK as u16
 }
 
-/// Multiplication of two Gf elements.
-#[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
-pub(crate) fn gf_mul(in0: Gf, in1: Gf) -> Gf {
+/// Multiplication of two elements of GF(2^13).
+const fn gf13_mul(in0: Gf, in1: Gf) -> Gf {
     let t0: u64 = in0 as u64;
     let t1: u64 = in1 as u64;
     let mut tmp: u64 = t0 * (t1 & 1); // if LSB 0, tmp will be 0, otherwise value of t0
 
     // (t1 & (1 << i)) ∈ {0, t1 ^ i}
-    for i in 1..GFBITS {
+    let mut i = 1;
+    while i < GF13_BITS {
         // implements the convolution, thus the actual multiplication
         tmp ^= t0 * (t1 & (1 << i));
+        i += 1;
     }
 
     // polynomial reduction according to the field polynomial
@@ -64,12 +80,11 @@ pub(crate) fn gf_mul(in0: Gf, in1: Gf) -> Gf {
     t = tmp & 0x000E000;
     tmp ^= (t >> 9) ^ (t >> 10) ^ (t >> 12) ^ (t >> 13);
 
-    tmp as u16 & GFMASK as u16
+    tmp as u16 & GF13_MASK as u16
 }
 
-/// Computes the square `in0^2` for Gf element `in0`
-#[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-fn gf_sq(in0: Gf) -> Gf {
+/// Computes the square `in0^2` for element `in0` of GF(2^12)
+const fn gf12_sq(in0: Gf) -> Gf {
     let b = [0x55555555u32, 0x33333333, 0x0F0F0F0F, 0x00FF00FF];
 
     let mut x: u32 = in0 as u32;
@@ -86,13 +101,12 @@ fn gf_sq(in0: Gf) -> Gf {
     x ^= t >> 9;
     x ^= t >> 12;
 
-    x as u16 & GFMASK as u16
+    x as u16 & GF12_MASK as u16
 }
 
-/// Computes the double-square `(in0^2)^2` for Gf element `in0`
-#[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
+/// Computes the double-square `(in0^2)^2` for element `in0` of GF(2^13)
 #[inline]
-fn gf_sq2(in0: Gf) -> Gf {
+const fn gf13_sq2(in0: Gf) -> Gf {
     const B: [u64; 4] = [
         0x1111111111111111,
         0x0303030303030303,
@@ -115,27 +129,27 @@ fn gf_sq2(in0: Gf) -> Gf {
     x = (x | (x << 6)) & B[1];
     x = (x | (x << 3)) & B[0];
 
-    for i in 0..4 {
-        t = x & M[i];
+    let mut k = 0;
+    while k < M.len() {
+        t = x & M[k];
         x ^= (t >> 9) ^ (t >> 10) ^ (t >> 12) ^ (t >> 13);
+        k += 1;
     }
 
-    (x & GFMASK as u64) as u16
+    (x & GF13_MASK as u64) as u16
 }
 
-/// Computes the square `in0^2` multiplied by `m` for Gf elements `in0` and `m`. Thus `(in0^2)*m`.
-#[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
+/// Computes the square `in0^2` multiplied by `m` for elements `in0` and `m` of GF(2^13). Thus `(in0^2)*m`.
 #[inline]
-fn gf_sqmul(in0: Gf, m: Gf) -> Gf {
+const fn gf13_sqmul(in0: Gf, m: Gf) -> Gf {
     let mut x: u64;
     let mut t0: u64;
-    let t1: u64;
     let mut t: u64;
 
     const M: [u64; 3] = [0x0000001FF0000000, 0x000000000FF80000, 0x000000000007E000];
 
     t0 = in0 as u64;
-    t1 = m as u64;
+    let t1: u64 = m as u64;
 
     x = (t1 << 6) * (t0 & (1 << 6));
 
@@ -148,22 +162,22 @@ fn gf_sqmul(in0: Gf, m: Gf) -> Gf {
     x ^= (t1 * (t0 & (0x40010))) << 4;
     x ^= (t1 * (t0 & (0x80020))) << 5;
 
-    for i in 0..3 {
-        t = x & M[i];
+    let mut k = 0;
+    while k < M.len() {
+        t = x & M[k];
         x ^= (t >> 9) ^ (t >> 10) ^ (t >> 12) ^ (t >> 13);
+        k += 1;
     }
 
-    (x & GFMASK as u64) as u16
+    (x & GF13_MASK as u64) as u16
 }
 
 /// Computes the double-square `(in0^2)^2` multiplied by `m`
-/// for Gf elements `in0` and `m`. Thus `((in0^2)^2)*m`.
-#[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
+/// for elements `in0` and `m` of GF(2^13). Thus `((in0^2)^2)*m`.
 #[inline]
-fn gf_sq2mul(in0: Gf, m: Gf) -> Gf {
+const fn gf13_sq2mul(in0: Gf, m: Gf) -> Gf {
     let mut x: u64;
     let mut t0: u64;
-    let t1: u64;
     let mut t: u64;
 
     const M: [u64; 6] = [
@@ -176,7 +190,7 @@ fn gf_sq2mul(in0: Gf, m: Gf) -> Gf {
     ];
 
     t0 = in0 as u64;
-    t1 = m as u64;
+    let t1: u64 = m as u64;
 
     x = (t1 << 18) * (t0 & (1 << 6));
 
@@ -189,119 +203,120 @@ fn gf_sq2mul(in0: Gf, m: Gf) -> Gf {
     x ^= (t1 * (t0 & (0x100000010))) << 12;
     x ^= (t1 * (t0 & (0x200000020))) << 15;
 
-    for i in 0..6 {
-        t = x & M[i];
+    let mut k = 0;
+    while k < M.len() {
+        t = x & M[k];
         x ^= (t >> 9) ^ (t >> 10) ^ (t >> 12) ^ (t >> 13);
+        k += 1;
     }
 
-    (x & GFMASK as u64) as u16
+    (x & GF13_MASK as u64) as u16
 }
 
 /// Computes the division `num/den` for Gf elements `den` and `num`
-#[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-pub(crate) fn gf_frac(den: Gf, num: Gf) -> Gf {
-    gf_mul(gf_inv(den), num)
+pub(crate) fn gf_frac<P: ParameterSet>(den: Gf, num: Gf) -> Gf {
+    if P::GFBITS == GF12_BITS {
+        gf12_mul(gf12_inv(den), num)
+    } else {
+        gf13_frac(den, num)
+    }
 }
 
-/// Computes the division `num/den` for Gf elements `den` and `num`
-#[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
-pub(crate) fn gf_frac(den: Gf, num: Gf) -> Gf {
-    let tmp_11: Gf;
-    let tmp_1111: Gf;
-    let mut out: Gf;
-
-    tmp_11 = gf_sqmul(den, den); // ^11
-    tmp_1111 = gf_sq2mul(tmp_11, tmp_11); // ^1111
-    out = gf_sq2(tmp_1111);
-    out = gf_sq2mul(out, tmp_1111); // ^11111111
-    out = gf_sq2(out);
-    out = gf_sq2mul(out, tmp_1111); // ^111111111111
-
-    gf_sqmul(out, num) // ^1111111111110 = ^-1
+/// Computes the division `num/den` for elements `den` and `num` of GF(2^13)
+const fn gf13_frac(den: Gf, num: Gf) -> Gf {
+    let tmp_11: Gf = gf13_sqmul(den, den); // ^11
+    let tmp_1111: Gf = gf13_sq2mul(tmp_11, tmp_11); // ^1111
+    let mut out: Gf = gf13_sq2(tmp_1111);
+    out = gf13_sq2mul(out, tmp_1111); // ^11111111
+    out = gf13_sq2(out);
+    out = gf13_sq2mul(out, tmp_1111); // ^111111111111
+
+    gf13_sqmul(out, num) // ^1111111111110 = ^-1
 }
 
 /// Computes the inverse element of `den` in the Galois field.
-#[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-pub(crate) fn gf_inv(in0: Gf) -> Gf {
-    let mut out = gf_sq(in0);
-    let tmp_11 = gf_mul(out, in0); // 11
+pub(crate) const fn gf_inv<P: ParameterSet>(den: Gf) -> Gf {
+    if P::GFBITS == GF12_BITS {
+        gf12_inv(den)
+    } else {
+        gf13_frac(den, 1 as Gf)
+    }
+}
 
-    out = gf_sq(tmp_11);
-    out = gf_sq(out);
-    let tmp_1111 = gf_mul(out, tmp_11); // 1111
+/// Computes the inverse element of `in0` in GF(2^12).
+const fn gf12_inv(in0: Gf) -> Gf {
+    let mut out = gf12_sq(in0);
+    let tmp_11 = gf12_mul(out, in0); // 11
 
-    out = gf_sq(tmp_1111);
-    out = gf_sq(out);
-    out = gf_sq(out);
-    out = gf_sq(out);
-    out = gf_mul(out, tmp_1111); // 11111111
+    out = gf12_sq(tmp_11);
+    out = gf12_sq(out);
+    let tmp_1111 = gf12_mul(out, tmp_11); // 1111
 
-    out = gf_sq(out);
-    out = gf_sq(out);
-    out = gf_mul(out, tmp_11); // 1111111111
+    out = gf12_sq(tmp_1111);
+    out = gf12_sq(out);
+    out = gf12_sq(out);
+    out = gf12_sq(out);
+    out = gf12_mul(out, tmp_1111); // 11111111
 
-    out = gf_sq(out);
-    out = gf_mul(out, in0); // 11111111111
+    out = gf12_sq(out);
+    out = gf12_sq(out);
+    out = gf12_mul(out, tmp_11); // 1111111111
 
-    gf_sq(out) // 111111111110
-}
+    out = gf12_sq(out);
+    out = gf12_mul(out, in0); // 11111111111
 
-/// Computes the inverse element of `den` in the Galois field.
-#[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
-pub(crate) fn gf_inv(den: Gf) -> Gf {
-    gf_frac(den, 1 as Gf)
+    gf12_sq(out) // 111111111110
 }
 
 /// Multiply Gf elements `in0` and `in0` in GF((2^m)^t) and store result in `out`.
 /// Called `GF_mul` in the C implementation.
-pub(crate) fn gf_mul_inplace(out: &mut [Gf; SYS_T], in0: &[Gf; SYS_T], in1: &[Gf; SYS_T]) {
-    let mut prod: [Gf; SYS_T * 2 - 1] = [0; SYS_T * 2 - 1];
+pub(crate) fn gf_mul_inplace<P: ParameterSet>(out: &mut [Gf], in0: &[Gf], in1: &[Gf]) {
+    debug_assert_eq!(out.len(), P::SYS_T);
+    debug_assert_eq!(in0.len(), P::SYS_T);
+    debug_assert_eq!(in1.len(), P::SYS_T);
+
+    let mut prod: [Gf; MAX_SYS_T * 2 - 1] = [0; MAX_SYS_T * 2 - 1];
 
-    for i in 0..SYS_T {
-        for j in 0..SYS_T {
-            prod[i + j] ^= gf_mul(in0[i], in1[j]);
+    for i in 0..P::SYS_T {
+        for j in 0..P::SYS_T {
+            prod[i + j] ^= gf_mul::<P>(in0[i], in1[j]);
         }
     }
 
-    for i in (SYS_T..=(SYS_T - 1) * 2).rev() {
-        #[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-        {
-            prod[i - SYS_T + 3] ^= prod[i];
-            prod[i - SYS_T + 1] ^= prod[i];
-            prod[i - SYS_T] ^= gf_mul(prod[i], 2);
-        }
-        #[cfg(any(feature = "mceliece460896", feature = "mceliece460896f"))]
-        {
-            prod[i - SYS_T + 10] ^= prod[i];
-            prod[i - SYS_T + 9] ^= prod[i];
-            prod[i - SYS_T + 6] ^= prod[i];
-            prod[i - SYS_T] ^= prod[i];
-        }
-        #[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-        {
-            prod[i - SYS_T + 8] ^= prod[i];
-            prod[i - SYS_T + 0] ^= prod[i];
-        }
-        #[cfg(any(
-            feature = "mceliece6688128",
-            feature = "mceliece6688128f",
-            feature = "mceliece8192128",
-            feature = "mceliece8192128f"
-        ))]
-        {
-            prod[i - SYS_T + 7] ^= prod[i];
-            prod[i - SYS_T + 2] ^= prod[i];
-            prod[i - SYS_T + 1] ^= prod[i];
-            prod[i - SYS_T + 0] ^= prod[i];
+    for i in (P::SYS_T..=(P::SYS_T - 1) * 2).rev() {
+        match P::SYS_T {
+            64 => {
+                prod[i - P::SYS_T + 3] ^= prod[i];
+                prod[i - P::SYS_T + 1] ^= prod[i];
+                prod[i - P::SYS_T] ^= gf_mul::<P>(prod[i], 2);
+            }
+            96 => {
+                prod[i - P::SYS_T + 10] ^= prod[i];
+                prod[i - P::SYS_T + 9] ^= prod[i];
+                prod[i - P::SYS_T + 6] ^= prod[i];
+                prod[i - P::SYS_T] ^= prod[i];
+            }
+            119 => {
+                prod[i - P::SYS_T + 8] ^= prod[i];
+                prod[i - P::SYS_T] ^= prod[i];
+            }
+            128 => {
+                prod[i - P::SYS_T + 7] ^= prod[i];
+                prod[i - P::SYS_T + 2] ^= prod[i];
+                prod[i - P::SYS_T + 1] ^= prod[i];
+                prod[i - P::SYS_T] ^= prod[i];
+            }
+            _ => unreachable!("no field polynomial for t = {}", P::SYS_T),
         }
     }
 
-    out[0..SYS_T].copy_from_slice(&prod[0..SYS_T]);
+    out.copy_from_slice(&prod[0..P::SYS_T]);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::params::{Mceliece348864, Mceliece460896, Mceliece6688128, Mceliece6960119};
 
     // Unit tests
     #[test]
@@ -330,197 +345,188 @@ mod tests {
         assert_eq!(gf_add(0xF0F0, 0x0F0F), 0xFFFF);
     }
 
-    #[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
     #[test]
-    fn test_gf_mul() {
-        assert_eq!(gf_mul(0, 0), 0);
-        assert_eq!(gf_mul(0, 1), 0);
-        assert_eq!(gf_mul(1, 0), 0);
-        assert_eq!(gf_mul(0, 5), 0);
-        assert_eq!(gf_mul(5, 0), 0);
-        assert_eq!(gf_mul(0, 1024), 0);
-        assert_eq!(gf_mul(1024, 0), 0);
-        assert_eq!(gf_mul(2, 6), 12);
-        assert_eq!(gf_mul(6, 2), 12);
-        assert_eq!(gf_mul(3, 8), 24);
-        assert_eq!(gf_mul(8, 3), 24);
-        assert_eq!(gf_mul(125, 19), 1879);
-        assert_eq!(gf_mul(19, 125), 1879);
-        assert_eq!(gf_mul(125, 37), 3625);
-        assert_eq!(gf_mul(37, 125), 3625);
-        assert_eq!(gf_mul(4095, 1), 4095);
-        assert_eq!(gf_mul(1, 4095), 4095);
-        assert_eq!(gf_mul(8191, 1), 4086);
-        assert_eq!(gf_mul(1, 8191), 4095);
+    fn test_gf12_mul() {
+        assert_eq!(gf_mul::<Mceliece348864>(0, 0), 0);
+        assert_eq!(gf_mul::<Mceliece348864>(0, 1), 0);
+        assert_eq!(gf_mul::<Mceliece348864>(1, 0), 0);
+        assert_eq!(gf_mul::<Mceliece348864>(0, 5), 0);
+        assert_eq!(gf_mul::<Mceliece348864>(5, 0), 0);
+        assert_eq!(gf_mul::<Mceliece348864>(0, 1024), 0);
+        assert_eq!(gf_mul::<Mceliece348864>(1024, 0), 0);
+        assert_eq!(gf_mul::<Mceliece348864>(2, 6), 12);
+        assert_eq!(gf_mul::<Mceliece348864>(6, 2), 12);
+        assert_eq!(gf_mul::<Mceliece348864>(3, 8), 24);
+        assert_eq!(gf_mul::<Mceliece348864>(8, 3), 24);
+        assert_eq!(gf_mul::<Mceliece348864>(125, 19), 1879);
+        assert_eq!(gf_mul::<Mceliece348864>(19, 125), 1879);
+        assert_eq!(gf_mul::<Mceliece348864>(125, 37), 3625);
+        assert_eq!(gf_mul::<Mceliece348864>(37, 125), 3625);
+        assert_eq!(gf_mul::<Mceliece348864>(4095, 1), 4095);
+        assert_eq!(gf_mul::<Mceliece348864>(1, 4095), 4095);
+        assert_eq!(gf_mul::<Mceliece348864>(8191, 1), 4086);
+        assert_eq!(gf_mul::<Mceliece348864>(1, 8191), 4095);
     }
 
-    #[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
     #[test]
-    fn test_gf_mul() {
-        assert_eq!(gf_mul(0, 0), 0);
-        assert_eq!(gf_mul(0, 1), 0);
-        assert_eq!(gf_mul(1, 0), 0);
-        assert_eq!(gf_mul(0, 5), 0);
-        assert_eq!(gf_mul(5, 0), 0);
-        assert_eq!(gf_mul(0, 1024), 0);
-        assert_eq!(gf_mul(1024, 0), 0);
-        assert_eq!(gf_mul(2, 6), 12);
-        assert_eq!(gf_mul(6, 2), 12);
-        assert_eq!(gf_mul(3, 8), 24);
-        assert_eq!(gf_mul(8, 3), 24);
-        assert_eq!(gf_mul(125, 19), 1879);
-        assert_eq!(gf_mul(19, 125), 1879);
-        assert_eq!(gf_mul(125, 37), 3625);
-        assert_eq!(gf_mul(37, 125), 3625);
-        assert_eq!(gf_mul(4095, 1), 4095);
-        assert_eq!(gf_mul(1, 4095), 4095);
-        assert_eq!(gf_mul(8191, 1), 8191);
-        assert_eq!(gf_mul(1, 8191), 8191);
+    fn test_gf13_mul() {
+        assert_eq!(gf_mul::<Mceliece460896>(0, 0), 0);
+        assert_eq!(gf_mul::<Mceliece460896>(0, 1), 0);
+        assert_eq!(gf_mul::<Mceliece460896>(1, 0), 0);
+        assert_eq!(gf_mul::<Mceliece460896>(0, 5), 0);
+        assert_eq!(gf_mul::<Mceliece460896>(5, 0), 0);
+        assert_eq!(gf_mul::<Mceliece460896>(0, 1024), 0);
+        assert_eq!(gf_mul::<Mceliece460896>(1024, 0), 0);
+        assert_eq!(gf_mul::<Mceliece460896>(2, 6), 12);
+        assert_eq!(gf_mul::<Mceliece460896>(6, 2), 12);
+        assert_eq!(gf_mul::<Mceliece460896>(3, 8), 24);
+        assert_eq!(gf_mul::<Mceliece460896>(8, 3), 24);
+        assert_eq!(gf_mul::<Mceliece460896>(125, 19), 1879);
+        assert_eq!(gf_mul::<Mceliece460896>(19, 125), 1879);
+        assert_eq!(gf_mul::<Mceliece460896>(125, 37), 3625);
+        assert_eq!(gf_mul::<Mceliece460896>(37, 125), 3625);
+        assert_eq!(gf_mul::<Mceliece460896>(4095, 1), 4095);
+        assert_eq!(gf_mul::<Mceliece460896>(1, 4095), 4095);
+        assert_eq!(gf_mul::<Mceliece460896>(8191, 1), 8191);
+        assert_eq!(gf_mul::<Mceliece460896>(1, 8191), 8191);
     }
 
-    #[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
     #[test]
-    fn test_gf_sq() {
-        assert_eq!(gf_sq(0), 0);
-        assert_eq!(gf_sq(1), 1);
-        assert_eq!(gf_sq(2), 4);
-        assert_eq!(gf_sq(3), 5);
-        assert_eq!(gf_sq(4), 16);
-        assert_eq!(gf_sq(4095), 2746);
-        assert_eq!(gf_sq(4096), 0);
-        assert_eq!(gf_sq(8191), 2746);
-        assert_eq!(gf_sq(8192), 0);
-        assert_eq!(gf_sq(0xFFFF), 2746);
+    fn test_gf12_sq() {
+        assert_eq!(gf12_sq(0), 0);
+        assert_eq!(gf12_sq(1), 1);
+        assert_eq!(gf12_sq(2), 4);
+        assert_eq!(gf12_sq(3), 5);
+        assert_eq!(gf12_sq(4), 16);
+        assert_eq!(gf12_sq(4095), 2746);
+        assert_eq!(gf12_sq(4096), 0);
+        assert_eq!(gf12_sq(8191), 2746);
+        assert_eq!(gf12_sq(8192), 0);
+        assert_eq!(gf12_sq(0xFFFF), 2746);
     }
 
-    #[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
     #[test]
-    fn test_gf_sq2() {
-        assert_eq!(gf_sq2(0), 0);
-        assert_eq!(gf_sq2(1), 1);
-        assert_eq!(gf_sq2(2), 16);
-        assert_eq!(gf_sq2(3), 17);
-        assert_eq!(gf_sq2(4), 256);
-        assert_eq!(gf_sq2(4095), 2883);
-        assert_eq!(gf_sq2(4096), 7941);
-        assert_eq!(gf_sq2(8191), 5190);
-        assert_eq!(gf_sq2(8192), 0);
-        assert_eq!(gf_sq2(0xFFFF), 5190);
+    fn test_gf13_sq2() {
+        assert_eq!(gf13_sq2(0), 0);
+        assert_eq!(gf13_sq2(1), 1);
+        assert_eq!(gf13_sq2(2), 16);
+        assert_eq!(gf13_sq2(3), 17);
+        assert_eq!(gf13_sq2(4), 256);
+        assert_eq!(gf13_sq2(4095), 2883);
+        assert_eq!(gf13_sq2(4096), 7941);
+        assert_eq!(gf13_sq2(8191), 5190);
+        assert_eq!(gf13_sq2(8192), 0);
+        assert_eq!(gf13_sq2(0xFFFF), 5190);
     }
 
-    #[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
     #[test]
-    fn test_gf_sqmul() {
-        assert_eq!(gf_sqmul(0, 0), 0);
-        assert_eq!(gf_sqmul(0, 1), 0);
-        assert_eq!(gf_sqmul(1, 0), 0);
-        assert_eq!(gf_sqmul(0, 5), 0);
-        assert_eq!(gf_sqmul(5, 0), 0);
-        assert_eq!(gf_sqmul(0, 1024), 0);
-        assert_eq!(gf_sqmul(1024, 0), 0);
-        assert_eq!(gf_sqmul(2, 6), 24);
-        assert_eq!(gf_sqmul(6, 2), 40);
-        assert_eq!(gf_sqmul(3, 8), 40);
-        assert_eq!(gf_sqmul(8, 3), 192);
-        assert_eq!(gf_sqmul(125, 19), 2582);
-        assert_eq!(gf_sqmul(19, 125), 7332);
-        assert_eq!(gf_sqmul(125, 37), 3012);
-        assert_eq!(gf_sqmul(37, 125), 4916);
-        assert_eq!(gf_sqmul(4095, 1), 3392);
-        assert_eq!(gf_sqmul(1, 4095), 4095);
-        assert_eq!(gf_sqmul(8191, 1), 5402);
-        assert_eq!(gf_sqmul(1, 8191), 8191);
+    fn test_gf13_sqmul() {
+        assert_eq!(gf13_sqmul(0, 0), 0);
+        assert_eq!(gf13_sqmul(0, 1), 0);
+        assert_eq!(gf13_sqmul(1, 0), 0);
+        assert_eq!(gf13_sqmul(0, 5), 0);
+        assert_eq!(gf13_sqmul(5, 0), 0);
+        assert_eq!(gf13_sqmul(0, 1024), 0);
+        assert_eq!(gf13_sqmul(1024, 0), 0);
+        assert_eq!(gf13_sqmul(2, 6), 24);
+        assert_eq!(gf13_sqmul(6, 2), 40);
+        assert_eq!(gf13_sqmul(3, 8), 40);
+        assert_eq!(gf13_sqmul(8, 3), 192);
+        assert_eq!(gf13_sqmul(125, 19), 2582);
+        assert_eq!(gf13_sqmul(19, 125), 7332);
+        assert_eq!(gf13_sqmul(125, 37), 3012);
+        assert_eq!(gf13_sqmul(37, 125), 4916);
+        assert_eq!(gf13_sqmul(4095, 1), 3392);
+        assert_eq!(gf13_sqmul(1, 4095), 4095);
+        assert_eq!(gf13_sqmul(8191, 1), 5402);
+        assert_eq!(gf13_sqmul(1, 8191), 8191);
     }
 
-    #[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
     #[test]
-    fn test_gf_sq2mul() {
-        assert_eq!(gf_sq2mul(0, 0), 0);
-        assert_eq!(gf_sq2mul(0, 1), 0);
-        assert_eq!(gf_sq2mul(1, 0), 0);
-        assert_eq!(gf_sq2mul(0, 5), 0);
-        assert_eq!(gf_sq2mul(5, 0), 0);
-        assert_eq!(gf_sq2mul(0, 1024), 0);
-        assert_eq!(gf_sq2mul(1024, 0), 0);
-        assert_eq!(gf_sq2mul(2, 6), 96);
-        assert_eq!(gf_sq2mul(6, 2), 544);
-        assert_eq!(gf_sq2mul(3, 8), 136);
-        assert_eq!(gf_sq2mul(8, 3), 4123);
-        assert_eq!(gf_sq2mul(125, 19), 3075);
-        assert_eq!(gf_sq2mul(19, 125), 590);
-        assert_eq!(gf_sq2mul(125, 37), 5123);
-        assert_eq!(gf_sq2mul(37, 125), 854);
-        assert_eq!(gf_sq2mul(4095, 1), 2883);
-        assert_eq!(gf_sq2mul(1, 4095), 4095);
-        assert_eq!(gf_sq2mul(8191, 1), 5190);
-        assert_eq!(gf_sq2mul(1, 8191), 8191);
+    fn test_gf13_sq2mul() {
+        assert_eq!(gf13_sq2mul(0, 0), 0);
+        assert_eq!(gf13_sq2mul(0, 1), 0);
+        assert_eq!(gf13_sq2mul(1, 0), 0);
+        assert_eq!(gf13_sq2mul(0, 5), 0);
+        assert_eq!(gf13_sq2mul(5, 0), 0);
+        assert_eq!(gf13_sq2mul(0, 1024), 0);
+        assert_eq!(gf13_sq2mul(1024, 0), 0);
+        assert_eq!(gf13_sq2mul(2, 6), 96);
+        assert_eq!(gf13_sq2mul(6, 2), 544);
+        assert_eq!(gf13_sq2mul(3, 8), 136);
+        assert_eq!(gf13_sq2mul(8, 3), 4123);
+        assert_eq!(gf13_sq2mul(125, 19), 3075);
+        assert_eq!(gf13_sq2mul(19, 125), 590);
+        assert_eq!(gf13_sq2mul(125, 37), 5123);
+        assert_eq!(gf13_sq2mul(37, 125), 854);
+        assert_eq!(gf13_sq2mul(4095, 1), 2883);
+        assert_eq!(gf13_sq2mul(1, 4095), 4095);
+        assert_eq!(gf13_sq2mul(8191, 1), 5190);
+        assert_eq!(gf13_sq2mul(1, 8191), 8191);
     }
 
-    #[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
     #[test]
-    fn test_gf_frac() {
-        assert_eq!(gf_frac(1, 6733), 2637);
-        assert_eq!(gf_frac(2, 0), 0);
-        assert_eq!(gf_frac(2, 4), 2);
-        assert_eq!(gf_frac(2, 4096), 0);
-        assert_eq!(gf_frac(3, 9), 7);
-        assert_eq!(gf_frac(5, 4591), 99);
-        assert_eq!(gf_frac(550, 10), 3344);
-        assert_eq!(gf_frac(5501, 3), 1763);
+    fn test_gf12_frac() {
+        assert_eq!(gf_frac::<Mceliece348864>(1, 6733), 2637);
+        assert_eq!(gf_frac::<Mceliece348864>(2, 0), 0);
+        assert_eq!(gf_frac::<Mceliece348864>(2, 4), 2);
+        assert_eq!(gf_frac::<Mceliece348864>(2, 4096), 0);
+        assert_eq!(gf_frac::<Mceliece348864>(3, 9), 7);
+        assert_eq!(gf_frac::<Mceliece348864>(5, 4591), 99);
+        assert_eq!(gf_frac::<Mceliece348864>(550, 10), 3344);
+        assert_eq!(gf_frac::<Mceliece348864>(5501, 3), 1763);
     }
 
-    #[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
     #[test]
-    fn test_gf_frac() {
-        assert_eq!(gf_frac(1, 6733), 6733);
-        assert_eq!(gf_frac(2, 0), 0);
-        assert_eq!(gf_frac(2, 4), 2);
-        assert_eq!(gf_frac(2, 4096), 2048);
-        assert_eq!(gf_frac(3, 9), 7);
-        assert_eq!(gf_frac(5, 4591), 4205);
-        assert_eq!(gf_frac(550, 10), 7759);
-        assert_eq!(gf_frac(5501, 3), 1770);
+    fn test_gf13_frac() {
+        assert_eq!(gf_frac::<Mceliece460896>(1, 6733), 6733);
+        assert_eq!(gf_frac::<Mceliece460896>(2, 0), 0);
+        assert_eq!(gf_frac::<Mceliece460896>(2, 4), 2);
+        assert_eq!(gf_frac::<Mceliece460896>(2, 4096), 2048);
+        assert_eq!(gf_frac::<Mceliece460896>(3, 9), 7);
+        assert_eq!(gf_frac::<Mceliece460896>(5, 4591), 4205);
+        assert_eq!(gf_frac::<Mceliece460896>(550, 10), 7759);
+        assert_eq!(gf_frac::<Mceliece460896>(5501, 3), 1770);
     }
 
-    #[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
     #[test]
-    fn test_gf_inv() {
-        assert_eq!(gf_inv(0), 0);
-        assert_eq!(gf_inv(1), 1);
-        assert_eq!(gf_inv(2), 2052);
-        assert_eq!(gf_inv(3), 4088);
-        assert_eq!(gf_inv(4), 1026);
-        assert_eq!(gf_inv(4095), 1539);
-        assert_eq!(gf_inv(4096), 0);
-        assert_eq!(gf_inv(8191), 1539);
-        assert_eq!(gf_inv(8192), 0);
-        assert_eq!(gf_inv(0xFFFF), 1539);
+    fn test_gf12_inv() {
+        assert_eq!(gf_inv::<Mceliece348864>(0), 0);
+        assert_eq!(gf_inv::<Mceliece348864>(1), 1);
+        assert_eq!(gf_inv::<Mceliece348864>(2), 2052);
+        assert_eq!(gf_inv::<Mceliece348864>(3), 4088);
+        assert_eq!(gf_inv::<Mceliece348864>(4), 1026);
+        assert_eq!(gf_inv::<Mceliece348864>(4095), 1539);
+        assert_eq!(gf_inv::<Mceliece348864>(4096), 0);
+        assert_eq!(gf_inv::<Mceliece348864>(8191), 1539);
+        assert_eq!(gf_inv::<Mceliece348864>(8192), 0);
+        assert_eq!(gf_inv::<Mceliece348864>(0xFFFF), 1539);
     }
 
-    #[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
     #[test]
-    fn test_gf_inv() {
-        assert_eq!(gf_inv(0), 0);
-        assert_eq!(gf_inv(1), 1);
-        assert_eq!(gf_inv(2), 4109);
-        assert_eq!(gf_inv(3), 8182);
-        assert_eq!(gf_inv(4), 6155);
-        assert_eq!(gf_inv(4095), 4657);
-        assert_eq!(gf_inv(4096), 911);
-        assert_eq!(gf_inv(8191), 5953);
-        assert_eq!(gf_inv(8192), 0);
-        assert_eq!(gf_inv(0xFFFF), 4378);
+    fn test_gf13_inv() {
+        assert_eq!(gf_inv::<Mceliece460896>(0), 0);
+        assert_eq!(gf_inv::<Mceliece460896>(1), 1);
+        assert_eq!(gf_inv::<Mceliece460896>(2), 4109);
+        assert_eq!(gf_inv::<Mceliece460896>(3), 8182);
+        assert_eq!(gf_inv::<Mceliece460896>(4), 6155);
+        assert_eq!(gf_inv::<Mceliece460896>(4095), 4657);
+        assert_eq!(gf_inv::<Mceliece460896>(4096), 911);
+        assert_eq!(gf_inv::<Mceliece460896>(8191), 5953);
+        assert_eq!(gf_inv::<Mceliece460896>(8192), 0);
+        assert_eq!(gf_inv::<Mceliece460896>(0xFFFF), 4378);
     }
 
-    #[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
     #[test]
-    fn test_gf_mul_inplace() {
+    fn test_gf_mul_inplace_348864() {
+        const SYS_T: usize = <Mceliece348864 as ParameterSet>::SYS_T;
+
         let mut res = [0u16; SYS_T];
         let mut arg1 = [0u16; SYS_T];
         let mut arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -534,7 +540,7 @@ mod tests {
         arg2 = [1u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -548,7 +554,7 @@ mod tests {
         arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -562,7 +568,7 @@ mod tests {
         arg2 = [5u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -576,7 +582,7 @@ mod tests {
         arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -590,7 +596,7 @@ mod tests {
         arg2 = [1024u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -606,7 +612,7 @@ mod tests {
         arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -622,7 +628,7 @@ mod tests {
         arg2 = [6u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -636,7 +642,7 @@ mod tests {
         arg2 = [2u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -650,7 +656,7 @@ mod tests {
         arg2 = [8u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -665,7 +671,7 @@ mod tests {
         arg2 = [3u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -680,7 +686,7 @@ mod tests {
         arg2 = [19u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -696,7 +702,7 @@ mod tests {
         arg2 = [125u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -712,7 +718,7 @@ mod tests {
         arg2 = [37u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -727,7 +733,7 @@ mod tests {
         arg2 = [125u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -742,7 +748,7 @@ mod tests {
         arg2 = [1u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -758,7 +764,7 @@ mod tests {
         arg2 = [4095u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -774,7 +780,7 @@ mod tests {
         arg2 = [1u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -790,7 +796,7 @@ mod tests {
         arg2 = [8191u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece348864>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -803,15 +809,16 @@ mod tests {
         );
     }
 
-    #[cfg(any(feature = "mceliece460896", feature = "mceliece460896f"))]
     #[test]
-    fn test_gf_mul_inplace() {
+    fn test_gf_mul_inplace_460896() {
+        const SYS_T: usize = <Mceliece460896 as ParameterSet>::SYS_T;
+
         let mut res = [0u16; SYS_T];
         let mut arg1 = [0u16; SYS_T];
         let mut arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -826,7 +833,7 @@ mod tests {
         arg2 = [1u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -841,7 +848,7 @@ mod tests {
         arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -856,7 +863,7 @@ mod tests {
         arg2 = [5u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -871,7 +878,7 @@ mod tests {
         arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -886,7 +893,7 @@ mod tests {
         arg2 = [1024u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -904,7 +911,7 @@ mod tests {
         arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -922,7 +929,7 @@ mod tests {
         arg2 = [6u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -937,7 +944,7 @@ mod tests {
         arg2 = [2u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -952,7 +959,7 @@ mod tests {
         arg2 = [8u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -968,7 +975,7 @@ mod tests {
         arg2 = [3u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -984,7 +991,7 @@ mod tests {
         arg2 = [19u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1002,7 +1009,7 @@ mod tests {
         arg2 = [125u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1020,7 +1027,7 @@ mod tests {
         arg2 = [37u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1037,7 +1044,7 @@ mod tests {
         arg2 = [125u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1054,7 +1061,7 @@ mod tests {
         arg2 = [1u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1071,7 +1078,7 @@ mod tests {
         arg2 = [4095u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1088,7 +1095,7 @@ mod tests {
         arg2 = [1u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1105,7 +1112,7 @@ mod tests {
         arg2 = [8191u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece460896>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1119,15 +1126,16 @@ mod tests {
         );
     }
 
-    #[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
     #[test]
-    fn test_gf_mul_inplace() {
+    fn test_gf_mul_inplace_6960119() {
+        const SYS_T: usize = <Mceliece6960119 as ParameterSet>::SYS_T;
+
         let mut res = [0u16; SYS_T];
         let mut arg1 = [0u16; SYS_T];
         let mut arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1143,7 +1151,7 @@ mod tests {
         arg2 = [1u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1159,7 +1167,7 @@ mod tests {
         arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1175,7 +1183,7 @@ mod tests {
         arg2 = [5u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1191,7 +1199,7 @@ mod tests {
         arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1207,7 +1215,7 @@ mod tests {
         arg2 = [1024u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1227,7 +1235,7 @@ mod tests {
         arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1247,7 +1255,7 @@ mod tests {
         arg2 = [6u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1263,7 +1271,7 @@ mod tests {
         arg2 = [2u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1279,7 +1287,7 @@ mod tests {
         arg2 = [8u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1296,7 +1304,7 @@ mod tests {
         arg2 = [3u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1313,7 +1321,7 @@ mod tests {
         arg2 = [19u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1332,7 +1340,7 @@ mod tests {
         arg2 = [125u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1351,7 +1359,7 @@ mod tests {
         arg2 = [37u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1370,7 +1378,7 @@ mod tests {
         arg2 = [125u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1389,7 +1397,7 @@ mod tests {
         arg2 = [1u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1407,7 +1415,7 @@ mod tests {
         arg2 = [4095u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1425,7 +1433,7 @@ mod tests {
         arg2 = [1u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1443,7 +1451,7 @@ mod tests {
         arg2 = [8191u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6960119>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1458,20 +1466,16 @@ mod tests {
         );
     }
 
-    #[cfg(any(
-        feature = "mceliece6688128",
-        feature = "mceliece6688128f",
-        feature = "mceliece8192128",
-        feature = "mceliece8192128f"
-    ))]
     #[test]
-    fn test_gf_mul_inplace() {
+    fn test_gf_mul_inplace_6688128() {
+        const SYS_T: usize = <Mceliece6688128 as ParameterSet>::SYS_T;
+
         let mut res = [0u16; SYS_T];
         let mut arg1 = [0u16; SYS_T];
         let mut arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1487,7 +1491,7 @@ mod tests {
         arg2 = [1u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1503,7 +1507,7 @@ mod tests {
         arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1519,7 +1523,7 @@ mod tests {
         arg2 = [5u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1535,7 +1539,7 @@ mod tests {
         arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1551,7 +1555,7 @@ mod tests {
         arg2 = [1024u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1572,7 +1576,7 @@ mod tests {
         arg2 = [0u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1593,7 +1597,7 @@ mod tests {
         arg2 = [6u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1609,7 +1613,7 @@ mod tests {
         arg2 = [2u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1625,7 +1629,7 @@ mod tests {
         arg2 = [8u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1643,7 +1647,7 @@ mod tests {
         arg2 = [3u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1661,7 +1665,7 @@ mod tests {
         arg2 = [19u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1681,7 +1685,7 @@ mod tests {
         arg2 = [125u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1701,7 +1705,7 @@ mod tests {
         arg2 = [37u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1720,7 +1724,7 @@ mod tests {
         arg2 = [125u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1739,7 +1743,7 @@ mod tests {
         arg2 = [1u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1758,7 +1762,7 @@ mod tests {
         arg2 = [4095u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1777,7 +1781,7 @@ mod tests {
         arg2 = [1u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
@@ -1796,7 +1800,7 @@ mod tests {
         arg2 = [8191u16; SYS_T];
         arg1[0] = 1;
         arg2[0] = 1;
-        gf_mul_inplace(&mut res, &arg1, &arg2);
+        gf_mul_inplace::<Mceliece6688128>(&mut res, &arg1, &arg2);
         assert_eq!(
             res,
             [
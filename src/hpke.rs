@@ -0,0 +1,545 @@
+//! HPKE (RFC 9180) with Classic McEliece as the KEM
+//!
+//! [`Hpke`] implements the base and the PSK mode of HPKE with HKDF-SHA256 as the KDF and
+//! one of the AEADs of the [`aead`](crate::aead) module. The authenticated modes need a KEM with
+//! `AuthEncap`, which Classic McEliece does not provide.
+//!
+//! The KEM functions of RFC 9180, section 4, map to this crate as follows:
+//!
+//! * `GenerateKeyPair()` is [`generic::keypair_boxed`].
+//! * `DeriveKeyPair(ikm)` is [`Hpke::derive_keypair`]. It expands `ikm` into the 32 byte seed
+//!   that the key generation of Classic McEliece starts from, using the labeled HKDF functions
+//!   of HPKE: `seed = LabeledExpand(LabeledExtract("", "dkp_prk", ikm), "sk", "", 32)`.
+//! * `SerializePublicKey(pk)` is the raw public key ([`PublicKey::as_array`]), hence
+//!   `Npk = P::PUBLIC_KEY_BYTES`. `DeserializePublicKey` is [`PublicKey::try_from_bytes`], which
+//!   validates the key. These keys are far too large for the stack, all of them are boxed.
+//! * `Encap(pkR)` is [`generic::try_encapsulate_boxed`]. `enc` is the McEliece ciphertext, so
+//!   `Nenc = P::CIPHERTEXT_BYTES`, and its shared secret is used as is, so `Nsecret = 32`.
+//! * `Decap(enc, skR)` is [`generic::try_decapsulate_boxed`].
+//!
+//! Classic McEliece has no KEM identifiers in the HPKE registry yet. [`kem_id`] returns
+//! provisional ones, which only interoperate with this crate and will be replaced by the
+//! registered values once there are any.
+//!
+//! ```
+//! use classic_mceliece_rust::aead::ChaCha20Poly1305;
+//! use classic_mceliece_rust::hpke::Hpke;
+//! use classic_mceliece_rust::Mceliece348864;
+//!
+//! # std::thread::Builder::new().stack_size(4 * 1024 * 1024).spawn(|| {
+//! type Suite = Hpke<Mceliece348864, ChaCha20Poly1305>;
+//! let mut rng = rand::thread_rng();
+//!
+//! let (public_key, secret_key) = Suite::derive_keypair(b"input keying material");
+//!
+//! let info = b"example application";
+//! let (enc, mut sender) = Suite::setup_base_sender(&public_key, info, &mut rng).unwrap();
+//! let ciphertext = sender.seal(b"header", b"message").unwrap();
+//!
+//! let mut recipient = Suite::setup_base_receiver(&enc, &secret_key, info).unwrap();
+//! assert_eq!(recipient.open(b"header", &ciphertext).unwrap(), b"message");
+//! # }).unwrap().join().unwrap();
+//! ```
+
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use core::marker::PhantomData;
+
+use hkdf::{Hkdf, HkdfExtract};
+use rand::{CryptoRng, RngCore};
+use sha2::Sha256;
+
+use crate::aead::{Aead, ChaCha20Poly1305, NONCE_BYTES};
+use crate::{
+    generic, Ciphertext, DefaultParameterSet, Error, ParameterSet, PublicKey, SecretKey,
+    SharedSecret, Variant, CRYPTO_BYTES,
+};
+
+/// The identifier of HKDF-SHA256 in the HPKE registry
+const KDF_ID: u16 = 0x0001;
+
+/// `Nh`, the output length of the KDF
+const HASH_BYTES: usize = 32;
+
+/// The longest key of the AEADs in [`crate::aead`]
+const MAX_KEY_BYTES: usize = 32;
+
+/// The mode identifiers of RFC 9180, section 5
+const MODE_BASE: u8 = 0x00;
+const MODE_PSK: u8 = 0x01;
+
+/// The provisional HPKE KEM identifier of the parameter set `variant`.
+///
+/// These are `0x4d01` to `0x4d0a` in the order of [`Variant::ALL`]. They are not registered
+/// with IANA and will change once Classic McEliece gets registered identifiers.
+pub const fn kem_id(variant: Variant) -> u16 {
+    0x4d01
+        + match variant {
+            Variant::Mceliece348864 => 0,
+            Variant::Mceliece348864f => 1,
+            Variant::Mceliece460896 => 2,
+            Variant::Mceliece460896f => 3,
+            Variant::Mceliece6688128 => 4,
+            Variant::Mceliece6688128f => 5,
+            Variant::Mceliece6960119 => 6,
+            Variant::Mceliece6960119f => 7,
+            Variant::Mceliece8192128 => 8,
+            Variant::Mceliece8192128f => 9,
+        }
+}
+
+/// The HPKE ciphersuite of the Classic McEliece parameter set `P`, HKDF-SHA256 and the AEAD `A`
+pub struct Hpke<P: ParameterSet = DefaultParameterSet, A: Aead = ChaCha20Poly1305>(
+    PhantomData<(P, A)>,
+);
+
+impl<P: ParameterSet, A: Aead> Hpke<P, A> {
+    /// The provisional KEM identifier of `P`, see [`kem_id`]
+    pub const KEM_ID: u16 = kem_id(P::VARIANT);
+
+    /// Derives a key pair from the input keying material `ikm`, which should have at least 32
+    /// bytes of entropy.
+    pub fn derive_keypair(ikm: &[u8]) -> (PublicKey<'static, P>, SecretKey<'static, P>) {
+        let suite_id = kem_suite_id(Self::KEM_ID);
+        let mut dkp_prk = labeled_extract(&suite_id, b"", b"dkp_prk", ikm);
+        let mut seed = [0u8; 32];
+        labeled_expand(&dkp_prk, &suite_id, b"sk", b"", &mut seed)
+            .expect("32 bytes are a valid output length");
+        let keypair = generic::keypair_from_seed_boxed(&seed);
+
+        #[cfg(feature = "zeroize")]
+        {
+            use zeroize::Zeroize;
+            dkp_prk.zeroize();
+            seed.zeroize();
+        }
+        #[cfg(not(feature = "zeroize"))]
+        let _ = &mut dkp_prk;
+        keypair
+    }
+
+    /// Sets up a context in the base mode to encrypt to `public_key`. Returns the
+    /// encapsulated key `enc`, which the recipient needs to set up its context.
+    pub fn setup_base_sender<R: CryptoRng + RngCore>(
+        public_key: &PublicKey<'_, P>,
+        info: &[u8],
+        rng: &mut R,
+    ) -> Result<(Ciphertext<P>, SenderContext<A>), Error> {
+        Self::setup_sender(MODE_BASE, public_key, info, b"", b"", rng)
+    }
+
+    /// Sets up a context in the base mode to decrypt from the sender of `enc`
+    pub fn setup_base_receiver(
+        enc: &Ciphertext<P>,
+        secret_key: &SecretKey<'_, P>,
+        info: &[u8],
+    ) -> Result<RecipientContext<A>, Error> {
+        Self::setup_receiver(MODE_BASE, enc, secret_key, info, b"", b"")
+    }
+
+    /// Sets up a context in the PSK mode to encrypt to `public_key`. Only a recipient
+    /// holding the pre-shared key `psk`, identified by `psk_id`, can decrypt. Fails with
+    /// [`Error::InvalidPsk`] if `psk` or `psk_id` is empty.
+    pub fn setup_psk_sender<R: CryptoRng + RngCore>(
+        public_key: &PublicKey<'_, P>,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        rng: &mut R,
+    ) -> Result<(Ciphertext<P>, SenderContext<A>), Error> {
+        Self::setup_sender(MODE_PSK, public_key, info, psk, psk_id, rng)
+    }
+
+    /// Sets up a context in the PSK mode to decrypt from the sender of `enc`. Fails with
+    /// [`Error::InvalidPsk`] if `psk` or `psk_id` is empty.
+    pub fn setup_psk_receiver(
+        enc: &Ciphertext<P>,
+        secret_key: &SecretKey<'_, P>,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Result<RecipientContext<A>, Error> {
+        Self::setup_receiver(MODE_PSK, enc, secret_key, info, psk, psk_id)
+    }
+
+    /// Encrypts a single message to `public_key` in the base mode. Returns the encapsulated
+    /// key `enc` and the ciphertext.
+    pub fn seal_base<R: CryptoRng + RngCore>(
+        public_key: &PublicKey<'_, P>,
+        info: &[u8],
+        aad: &[u8],
+        plaintext: &[u8],
+        rng: &mut R,
+    ) -> Result<(Ciphertext<P>, Vec<u8>), Error> {
+        let (enc, mut context) = Self::setup_base_sender(public_key, info, rng)?;
+        let ciphertext = context.seal(aad, plaintext)?;
+        Ok((enc, ciphertext))
+    }
+
+    /// Decrypts a single message sealed with [`Hpke::seal_base`]
+    pub fn open_base(
+        enc: &Ciphertext<P>,
+        secret_key: &SecretKey<'_, P>,
+        info: &[u8],
+        aad: &[u8],
+        ciphertext: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        Self::setup_base_receiver(enc, secret_key, info)?.open(aad, ciphertext)
+    }
+
+    fn setup_sender<R: CryptoRng + RngCore>(
+        mode: u8,
+        public_key: &PublicKey<'_, P>,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+        rng: &mut R,
+    ) -> Result<(Ciphertext<P>, SenderContext<A>), Error> {
+        verify_psk_inputs(mode, psk, psk_id)?;
+        let (enc, shared_secret) = generic::try_encapsulate_boxed(public_key, rng)?;
+        let context = key_schedule(Self::KEM_ID, mode, &shared_secret, info, psk, psk_id);
+        Ok((enc, SenderContext(context)))
+    }
+
+    fn setup_receiver(
+        mode: u8,
+        enc: &Ciphertext<P>,
+        secret_key: &SecretKey<'_, P>,
+        info: &[u8],
+        psk: &[u8],
+        psk_id: &[u8],
+    ) -> Result<RecipientContext<A>, Error> {
+        verify_psk_inputs(mode, psk, psk_id)?;
+        let shared_secret = generic::try_decapsulate_boxed(enc, secret_key)?;
+        let context = key_schedule(Self::KEM_ID, mode, &shared_secret, info, psk, psk_id);
+        Ok(RecipientContext(context))
+    }
+}
+
+fn kem_suite_id(kem_id: u16) -> [u8; 5] {
+    let [high, low] = kem_id.to_be_bytes();
+    [b'K', b'E', b'M', high, low]
+}
+
+fn hpke_suite_id<A: Aead>(kem_id: u16) -> [u8; 10] {
+    let mut suite_id = [0u8; 10];
+    suite_id[..4].copy_from_slice(b"HPKE");
+    suite_id[4..6].copy_from_slice(&kem_id.to_be_bytes());
+    suite_id[6..8].copy_from_slice(&KDF_ID.to_be_bytes());
+    suite_id[8..].copy_from_slice(&A::ID.to_be_bytes());
+    suite_id
+}
+
+fn labeled_extract(suite_id: &[u8], salt: &[u8], label: &[u8], ikm: &[u8]) -> [u8; HASH_BYTES] {
+    let mut extract = HkdfExtract::<Sha256>::new(Some(salt));
+    extract.input_ikm(b"HPKE-v1");
+    extract.input_ikm(suite_id);
+    extract.input_ikm(label);
+    extract.input_ikm(ikm);
+    extract.finalize().0.into()
+}
+
+fn labeled_expand(
+    prk: &[u8; HASH_BYTES],
+    suite_id: &[u8],
+    label: &[u8],
+    info: &[u8],
+    output: &mut [u8],
+) -> Result<(), Error> {
+    let length = u16::try_from(output.len()).map_err(|_| Error::ExportTooLong)?;
+    let hkdf = Hkdf::<Sha256>::from_prk(prk).expect("PRK has the hash length");
+    hkdf.expand_multi_info(
+        &[&length.to_be_bytes(), b"HPKE-v1", suite_id, label, info],
+        output,
+    )
+    .map_err(|_| Error::ExportTooLong)
+}
+
+/// `VerifyPSKInputs` of RFC 9180, section 5.1
+fn verify_psk_inputs(mode: u8, psk: &[u8], psk_id: &[u8]) -> Result<(), Error> {
+    let expected = mode == MODE_PSK;
+    if psk.is_empty() == expected || psk_id.is_empty() == expected {
+        return Err(Error::InvalidPsk);
+    }
+    Ok(())
+}
+
+/// `KeySchedule<ROLE>` of RFC 9180, section 5.1
+fn key_schedule<A: Aead>(
+    kem_id: u16,
+    mode: u8,
+    shared_secret: &SharedSecret<'_>,
+    info: &[u8],
+    psk: &[u8],
+    psk_id: &[u8],
+) -> Context<A> {
+    let suite_id = hpke_suite_id::<A>(kem_id);
+    let psk_id_hash = labeled_extract(&suite_id, b"", b"psk_id_hash", psk_id);
+    let info_hash = labeled_extract(&suite_id, b"", b"info_hash", info);
+    let mut key_schedule_context = [0u8; 1 + 2 * HASH_BYTES];
+    key_schedule_context[0] = mode;
+    key_schedule_context[1..1 + HASH_BYTES].copy_from_slice(&psk_id_hash);
+    key_schedule_context[1 + HASH_BYTES..].copy_from_slice(&info_hash);
+
+    let secret: &[u8; CRYPTO_BYTES] = shared_secret.as_array();
+    let mut secret = labeled_extract(&suite_id, secret, b"secret", psk);
+    let mut context = Context {
+        suite_id,
+        key: [0u8; MAX_KEY_BYTES],
+        base_nonce: [0u8; NONCE_BYTES],
+        sequence_number: 0,
+        exporter_secret: [0u8; HASH_BYTES],
+        aead: PhantomData,
+    };
+    for (label, output) in [
+        (&b"key"[..], &mut context.key[..A::KEY_BYTES]),
+        (b"base_nonce", &mut context.base_nonce[..]),
+        (b"exp", &mut context.exporter_secret[..]),
+    ] {
+        labeled_expand(&secret, &suite_id, label, &key_schedule_context, output)
+            .expect("output lengths of the key schedule are valid");
+    }
+
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(&mut secret);
+    #[cfg(not(feature = "zeroize"))]
+    let _ = &mut secret;
+    context
+}
+
+/// The state shared by both roles of RFC 9180, section 5.2
+struct Context<A: Aead> {
+    suite_id: [u8; 10],
+    key: [u8; MAX_KEY_BYTES],
+    base_nonce: [u8; NONCE_BYTES],
+    sequence_number: u64,
+    exporter_secret: [u8; HASH_BYTES],
+    aead: PhantomData<A>,
+}
+
+impl<A: Aead> Context<A> {
+    /// `ComputeNonce(seq)`
+    fn nonce(&self) -> [u8; NONCE_BYTES] {
+        let mut nonce = self.base_nonce;
+        for (byte, seq) in nonce[NONCE_BYTES - 8..]
+            .iter_mut()
+            .zip(self.sequence_number.to_be_bytes())
+        {
+            *byte ^= seq;
+        }
+        nonce
+    }
+
+    /// `IncrementSeq()`, which fails before the sequence number would be reused
+    fn increment_sequence_number(&mut self) -> Result<(), Error> {
+        self.sequence_number = self
+            .sequence_number
+            .checked_add(1)
+            .ok_or(Error::MessageLimitReached)?;
+        Ok(())
+    }
+
+    fn check_sequence_number(&self) -> Result<(), Error> {
+        match self.sequence_number {
+            u64::MAX => Err(Error::MessageLimitReached),
+            _ => Ok(()),
+        }
+    }
+
+    fn export(&self, exporter_context: &[u8], output: &mut [u8]) -> Result<(), Error> {
+        labeled_expand(
+            &self.exporter_secret,
+            &self.suite_id,
+            b"sec",
+            exporter_context,
+            output,
+        )
+    }
+}
+
+impl<A: Aead> Drop for Context<A> {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        {
+            use zeroize::Zeroize;
+            self.key.zeroize();
+            self.base_nonce.zeroize();
+            self.exporter_secret.zeroize();
+        }
+    }
+}
+
+/// The encryption context of an HPKE sender
+pub struct SenderContext<A: Aead = ChaCha20Poly1305>(Context<A>);
+
+impl<A: Aead> SenderContext<A> {
+    /// Encrypts the next message with the associated data `aad`. Messages must be opened in
+    /// the order they were sealed.
+    pub fn seal(&mut self, aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        let context = &mut self.0;
+        context.check_sequence_number()?;
+        let ciphertext = A::seal(
+            &context.key[..A::KEY_BYTES],
+            &context.nonce(),
+            aad,
+            plaintext,
+        )?;
+        context.increment_sequence_number()?;
+        Ok(ciphertext)
+    }
+
+    /// Fills `output` with a secret derived from the context and `exporter_context`. Fails
+    /// with [`Error::ExportTooLong`] for more than 8160 bytes.
+    pub fn export(&self, exporter_context: &[u8], output: &mut [u8]) -> Result<(), Error> {
+        self.0.export(exporter_context, output)
+    }
+}
+
+/// The decryption context of an HPKE recipient
+pub struct RecipientContext<A: Aead = ChaCha20Poly1305>(Context<A>);
+
+impl<A: Aead> RecipientContext<A> {
+    /// Decrypts the next message with the associated data `aad`. Fails with [`Error::Aead`]
+    /// if the ciphertext or `aad` were modified, or if the message is not the next one.
+    pub fn open(&mut self, aad: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, Error> {
+        let context = &mut self.0;
+        context.check_sequence_number()?;
+        let plaintext = A::open(
+            &context.key[..A::KEY_BYTES],
+            &context.nonce(),
+            aad,
+            ciphertext,
+        )?;
+        context.increment_sequence_number()?;
+        Ok(plaintext)
+    }
+
+    /// Fills `output` with a secret derived from the context and `exporter_context`. Fails
+    /// with [`Error::ExportTooLong`] for more than 8160 bytes.
+    pub fn export(&self, exporter_context: &[u8], output: &mut [u8]) -> Result<(), Error> {
+        self.0.export(exporter_context, output)
+    }
+}
+
+impl<A: Aead> Debug for SenderContext<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SenderContext")
+            .field(&"-- redacted --")
+            .finish()
+    }
+}
+
+impl<A: Aead> Debug for RecipientContext<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RecipientContext")
+            .field(&"-- redacted --")
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Mceliece348864;
+
+    /// The key schedule only depends on the identifier of the KEM and its shared secret, so it
+    /// is checked against the test vector of RFC 9180, appendix A.1.1 (DHKEM(X25519,
+    /// HKDF-SHA256), HKDF-SHA256, AES-128-GCM, base mode).
+    #[test]
+    #[cfg(feature = "aes-gcm")]
+    fn test_key_schedule_rfc9180() {
+        use crate::aead::Aes128Gcm;
+        use crate::KeyBufferMut;
+        use alloc::boxed::Box;
+
+        let shared_secret =
+            hex::decode("fe0e18c9f024ce43799ae393c7e8fe8fce9d218875e8227b0187c04e7d2ea1fc")
+                .unwrap();
+        let shared_secret = SharedSecret(KeyBufferMut::Owned(Box::new(
+            shared_secret.try_into().unwrap(),
+        )));
+        let info = hex::decode("4f6465206f6e2061204772656369616e2055726e").unwrap();
+        let context = key_schedule::<Aes128Gcm>(0x0020, MODE_BASE, &shared_secret, &info, b"", b"");
+        assert_eq!(
+            hex::encode(&context.key[..16]),
+            "4531685d41d65f03dc48f6b8302c05b0"
+        );
+        assert_eq!(hex::encode(context.base_nonce), "56d890e5accaaf011cff4b7d");
+        assert_eq!(
+            hex::encode(context.exporter_secret),
+            "45ff1c2e220db587171952c0592d5f5ebe103f1561a2614e38f2ffd47e99e3f8"
+        );
+
+        let mut sender = SenderContext(context);
+        let ciphertext = sender
+            .seal(
+                &hex::decode("436f756e742d30").unwrap(),
+                &hex::decode("4265617574792069732074727574682c20747275746820626561757479").unwrap(),
+            )
+            .unwrap();
+        assert_eq!(
+            hex::encode(ciphertext),
+            "f938558b5d72f1a23810b4be2ab4f84331acc02fc97babc53a52ae8218a355a96d8770ac83d07bea87e13c512a"
+        );
+
+        let mut exported = [0u8; 32];
+        sender.export(b"", &mut exported).unwrap();
+        assert_eq!(
+            hex::encode(exported),
+            "3853fe2b4035195a573ffc53856e77058e15d9ea064de3e59f4961d0095250ee"
+        );
+    }
+
+    #[test]
+    fn test_hpke_roundtrip() {
+        std::thread::Builder::new()
+            .stack_size(4 * 1024 * 1024)
+            .spawn(|| {
+                type Suite = Hpke<Mceliece348864, ChaCha20Poly1305>;
+                let mut rng = crate::nist_aes_rng::NistDrbg::new([0u8; 48]);
+
+                let (public_key, secret_key) = Suite::derive_keypair(&[7u8; 32]);
+                let (public_key2, _) = Suite::derive_keypair(&[7u8; 32]);
+                assert_eq!(public_key.as_array(), public_key2.as_array());
+
+                let (enc, mut sender) =
+                    Suite::setup_psk_sender(&public_key, b"info", b"psk", b"id", &mut rng).unwrap();
+                let first = sender.seal(b"aad", b"first").unwrap();
+                let second = sender.seal(b"aad", b"second").unwrap();
+
+                let mut recipient =
+                    Suite::setup_psk_receiver(&enc, &secret_key, b"info", b"psk", b"id").unwrap();
+                assert_eq!(recipient.open(b"aad", &second), Err(Error::Aead));
+                assert_eq!(recipient.open(b"aad", &first).unwrap(), b"first");
+                assert_eq!(recipient.open(b"aad", &second).unwrap(), b"second");
+
+                let mut sender_secret = [0u8; 64];
+                let mut recipient_secret = [0u8; 64];
+                sender.export(b"context", &mut sender_secret).unwrap();
+                recipient.export(b"context", &mut recipient_secret).unwrap();
+                assert_eq!(sender_secret, recipient_secret);
+                assert_eq!(
+                    sender.export(b"context", &mut [0u8; 8161]),
+                    Err(Error::ExportTooLong)
+                );
+
+                let mut recipient =
+                    Suite::setup_psk_receiver(&enc, &secret_key, b"info", b"other", b"id").unwrap();
+                assert_eq!(recipient.open(b"aad", &first), Err(Error::Aead));
+                assert_eq!(
+                    Suite::setup_psk_receiver(&enc, &secret_key, b"info", b"", b"id").unwrap_err(),
+                    Error::InvalidPsk
+                );
+
+                let (enc, ciphertext) =
+                    Suite::seal_base(&public_key, b"info", b"", b"message", &mut rng).unwrap();
+                let plaintext = Suite::open_base(&enc, &secret_key, b"info", b"", &ciphertext);
+                assert_eq!(plaintext.unwrap(), b"message");
+                let plaintext = Suite::open_base(&enc, &secret_key, b"other", b"", &ciphertext);
+                assert_eq!(plaintext, Err(Error::Aead));
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
@@ -0,0 +1,382 @@
+//! Hybrid KEM combining X25519 with Classic McEliece.
+//!
+//! [`X25519McEliece`] runs an X25519 key exchange and a Classic McEliece encapsulation side
+//! by side. The shared secret stays secure as long as either of them is unbroken. Like the
+//! hybrid constructions of the IETF drafts, the two shared secrets are combined with
+//! SHAKE256 over a label, both shared secrets, both ciphertexts and both public keys:
+//!
+//! ```text
+//! ss = SHAKE256(label || ss_M || ss_X || ct_M || ct_X || SHAKE256(pk_M) || pk_X, 32)
+//! ```
+//!
+//! `label` is `"X25519-"` followed by the name of the parameter set, e.g.
+//! `X25519-mceliece460896f`. The McEliece public key enters as its 32 byte SHAKE256 digest,
+//! which the secret key stores instead of the megabyte-sized key.
+//!
+//! The serialized forms concatenate the X25519 part and the McEliece part:
+//!
+//! | | encoding |
+//! |---|---|
+//! | public key | `pk_X` (32 bytes) ‖ `pk_M` |
+//! | secret key | `sk_X` (32 bytes) ‖ `SHAKE256(pk_M)` (32 bytes) ‖ `sk_M` |
+//! | ciphertext | `ct_X` (32 bytes) ‖ `ct_M` |
+//!
+//! ```
+//! use classic_mceliece_rust::hybrid::X25519McEliece;
+//! use classic_mceliece_rust::Mceliece348864;
+//!
+//! # std::thread::Builder::new().stack_size(4 * 1024 * 1024).spawn(|| {
+//! type Kem = X25519McEliece<Mceliece348864>;
+//! let mut rng = rand::thread_rng();
+//!
+//! let (public_key, secret_key) = Kem::keypair(&mut rng).unwrap();
+//! let (ciphertext, shared_secret_bob) = Kem::encapsulate(&public_key, &mut rng).unwrap();
+//! let shared_secret_alice = Kem::decapsulate(&ciphertext, &secret_key);
+//! assert_eq!(shared_secret_bob.as_array(), shared_secret_alice.as_array());
+//! # }).unwrap().join().unwrap();
+//! ```
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use core::marker::PhantomData;
+
+use rand::{CryptoRng, RngCore};
+use x25519_dalek::StaticSecret;
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::crypto_hash::shake256;
+use crate::params::{ByteArray, ParameterSet};
+use crate::{generic, DefaultParameterSet, Error, KeyBufferMut, SharedSecret, CRYPTO_BYTES};
+
+/// The length of X25519 keys and shares
+const X25519_BYTES: usize = 32;
+
+/// The length of the digest of the McEliece public key
+const DIGEST_BYTES: usize = 32;
+
+/// The hybrid KEM of X25519 and the Classic McEliece parameter set `P`
+pub struct X25519McEliece<P: ParameterSet = DefaultParameterSet>(PhantomData<P>);
+
+/// The public key of [`X25519McEliece`]
+pub struct PublicKey<P: ParameterSet = DefaultParameterSet> {
+    x25519: x25519_dalek::PublicKey,
+    mceliece: crate::PublicKey<'static, P>,
+}
+
+/// The secret key of [`X25519McEliece`]
+pub struct SecretKey<P: ParameterSet = DefaultParameterSet> {
+    x25519: StaticSecret,
+    mceliece_public_key_digest: [u8; DIGEST_BYTES],
+    mceliece: crate::SecretKey<'static, P>,
+}
+
+/// The ciphertext of [`X25519McEliece`]
+pub struct Ciphertext<P: ParameterSet = DefaultParameterSet> {
+    x25519: x25519_dalek::PublicKey,
+    mceliece: crate::Ciphertext<P>,
+}
+
+impl<P: ParameterSet> X25519McEliece<P> {
+    /// The length of a serialized public key
+    pub const PUBLIC_KEY_BYTES: usize = X25519_BYTES + P::PUBLIC_KEY_BYTES;
+    /// The length of a serialized secret key
+    pub const SECRET_KEY_BYTES: usize = X25519_BYTES + DIGEST_BYTES + P::SECRET_KEY_BYTES;
+    /// The length of a serialized ciphertext
+    pub const CIPHERTEXT_BYTES: usize = X25519_BYTES + P::CIPHERTEXT_BYTES;
+
+    /// Generates a key pair. Fails with [`Error::Rng`] if `rng` fails and with
+    /// [`Error::Allocation`] if the keys can not be allocated.
+    pub fn keypair<R: CryptoRng + RngCore>(
+        rng: &mut R,
+    ) -> Result<(PublicKey<P>, SecretKey<P>), Error> {
+        let x25519 = random_x25519_secret(rng)?;
+        let (mceliece_public_key, mceliece_secret_key) = generic::try_keypair_boxed::<P, R>(rng)?;
+
+        let public_key = PublicKey {
+            x25519: x25519_dalek::PublicKey::from(&x25519),
+            mceliece: mceliece_public_key,
+        };
+        let secret_key = SecretKey {
+            x25519,
+            mceliece_public_key_digest: public_key.mceliece_digest(),
+            mceliece: mceliece_secret_key,
+        };
+        Ok((public_key, secret_key))
+    }
+
+    /// Encapsulates a fresh shared secret to `public_key`. Fails if the McEliece public key
+    /// is malformed (see [`crate::PublicKey::validate`]) or if `rng` fails.
+    pub fn encapsulate<R: CryptoRng + RngCore>(
+        public_key: &PublicKey<P>,
+        rng: &mut R,
+    ) -> Result<(Ciphertext<P>, SharedSecret<'static>), Error> {
+        let (mceliece_ciphertext, mceliece_shared_secret) =
+            generic::try_encapsulate_boxed(&public_key.mceliece, rng)?;
+
+        let ephemeral = random_x25519_secret(rng)?;
+        let x25519_shared_secret = ephemeral.diffie_hellman(&public_key.x25519);
+        let ciphertext = Ciphertext {
+            x25519: x25519_dalek::PublicKey::from(&ephemeral),
+            mceliece: mceliece_ciphertext,
+        };
+
+        let shared_secret = combine::<P>(
+            mceliece_shared_secret.as_array(),
+            x25519_shared_secret.as_bytes(),
+            &ciphertext,
+            &public_key.mceliece_digest(),
+            &public_key.x25519,
+        );
+        Ok((ciphertext, shared_secret))
+    }
+
+    /// Decapsulates the shared secret of `ciphertext` with `secret_key`. Like the McEliece
+    /// decapsulation, this never fails: an invalid ciphertext results in an unrelated
+    /// shared secret.
+    pub fn decapsulate(
+        ciphertext: &Ciphertext<P>,
+        secret_key: &SecretKey<P>,
+    ) -> SharedSecret<'static> {
+        let mceliece_shared_secret =
+            generic::decapsulate_boxed(&ciphertext.mceliece, &secret_key.mceliece);
+        let x25519_shared_secret = secret_key.x25519.diffie_hellman(&ciphertext.x25519);
+
+        combine::<P>(
+            mceliece_shared_secret.as_array(),
+            x25519_shared_secret.as_bytes(),
+            ciphertext,
+            &secret_key.mceliece_public_key_digest,
+            &x25519_dalek::PublicKey::from(&secret_key.x25519),
+        )
+    }
+}
+
+fn random_x25519_secret<R: CryptoRng + RngCore>(rng: &mut R) -> Result<StaticSecret, Error> {
+    let mut bytes = [0u8; X25519_BYTES];
+    rng.try_fill_bytes(&mut bytes)?;
+    let secret = StaticSecret::from(bytes);
+    bytes.zeroize();
+    Ok(secret)
+}
+
+/// The SHAKE256 combiner described in the [module documentation](self)
+fn combine<P: ParameterSet>(
+    mceliece_shared_secret: &[u8; CRYPTO_BYTES],
+    x25519_shared_secret: &[u8; X25519_BYTES],
+    ciphertext: &Ciphertext<P>,
+    mceliece_public_key_digest: &[u8; DIGEST_BYTES],
+    x25519_public_key: &x25519_dalek::PublicKey,
+) -> SharedSecret<'static> {
+    let mut input = Vec::with_capacity(
+        7 + P::NAME.len()
+            + CRYPTO_BYTES
+            + X25519_BYTES
+            + P::CIPHERTEXT_BYTES
+            + X25519_BYTES
+            + DIGEST_BYTES
+            + X25519_BYTES,
+    );
+    input.extend_from_slice(b"X25519-");
+    input.extend_from_slice(P::NAME.as_bytes());
+    input.extend_from_slice(mceliece_shared_secret);
+    input.extend_from_slice(x25519_shared_secret);
+    input.extend_from_slice(ciphertext.mceliece.as_ref());
+    input.extend_from_slice(ciphertext.x25519.as_bytes());
+    input.extend_from_slice(mceliece_public_key_digest);
+    input.extend_from_slice(x25519_public_key.as_bytes());
+
+    let mut shared_secret = Box::new([0u8; CRYPTO_BYTES]);
+    shake256(&mut shared_secret[..], &input);
+    input.zeroize();
+    SharedSecret(KeyBufferMut::Owned(shared_secret))
+}
+
+fn check_length<P: ParameterSet>(expected: usize, bytes: &[u8]) -> Result<(), Error> {
+    if bytes.len() != expected {
+        return Err(Error::InvalidLength {
+            variant: P::VARIANT,
+            expected,
+            actual: bytes.len(),
+        });
+    }
+    Ok(())
+}
+
+fn x25519_bytes(bytes: &[u8]) -> [u8; X25519_BYTES] {
+    bytes[..X25519_BYTES].try_into().expect("length checked")
+}
+
+impl<P: ParameterSet> PublicKey<P> {
+    /// Parses the concatenation `pk_X || pk_M`. The McEliece public key is validated, see
+    /// [`crate::PublicKey::try_from_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<PublicKey<P>, Error> {
+        check_length::<P>(X25519McEliece::<P>::PUBLIC_KEY_BYTES, bytes)?;
+        Ok(PublicKey {
+            x25519: x25519_dalek::PublicKey::from(x25519_bytes(bytes)),
+            mceliece: crate::PublicKey::try_from_bytes(&bytes[X25519_BYTES..])?,
+        })
+    }
+
+    /// Returns the concatenation `pk_X || pk_M`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.x25519.as_bytes(), self.mceliece.as_ref()].concat()
+    }
+
+    /// The X25519 half of the key
+    pub fn x25519(&self) -> &x25519_dalek::PublicKey {
+        &self.x25519
+    }
+
+    /// The Classic McEliece half of the key
+    pub fn mceliece(&self) -> &crate::PublicKey<'static, P> {
+        &self.mceliece
+    }
+
+    fn mceliece_digest(&self) -> [u8; DIGEST_BYTES] {
+        let mut digest = [0u8; DIGEST_BYTES];
+        shake256(&mut digest, self.mceliece.as_ref());
+        digest
+    }
+}
+
+impl<P: ParameterSet> SecretKey<P> {
+    /// Parses the concatenation `sk_X || SHAKE256(pk_M) || sk_M`. Only the length is
+    /// checked, see [`crate::SecretKey::validate`] for the structure of the McEliece part.
+    pub fn from_bytes(bytes: &[u8]) -> Result<SecretKey<P>, Error> {
+        check_length::<P>(X25519McEliece::<P>::SECRET_KEY_BYTES, bytes)?;
+
+        let mut mceliece = P::SecretKeyArray::zeroed_boxed();
+        (*mceliece)
+            .as_mut()
+            .copy_from_slice(&bytes[X25519_BYTES + DIGEST_BYTES..]);
+        Ok(SecretKey {
+            x25519: StaticSecret::from(x25519_bytes(bytes)),
+            mceliece_public_key_digest: bytes[X25519_BYTES..X25519_BYTES + DIGEST_BYTES]
+                .try_into()
+                .expect("length checked"),
+            mceliece: crate::SecretKey::from(mceliece),
+        })
+    }
+
+    /// Returns the concatenation `sk_X || SHAKE256(pk_M) || sk_M`. The result contains the
+    /// secret key and is zeroed on drop.
+    pub fn to_bytes(&self) -> Zeroizing<Vec<u8>> {
+        let mut x25519 = self.x25519.to_bytes();
+        let bytes = [
+            &x25519[..],
+            &self.mceliece_public_key_digest,
+            self.mceliece.as_ref(),
+        ]
+        .concat();
+        x25519.zeroize();
+        Zeroizing::new(bytes)
+    }
+
+    /// The Classic McEliece half of the key
+    pub fn mceliece(&self) -> &crate::SecretKey<'static, P> {
+        &self.mceliece
+    }
+}
+
+impl<P: ParameterSet> Ciphertext<P> {
+    /// Parses the concatenation `ct_X || ct_M`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Ciphertext<P>, Error> {
+        check_length::<P>(X25519McEliece::<P>::CIPHERTEXT_BYTES, bytes)?;
+
+        let mut mceliece = P::CiphertextArray::zeroed();
+        mceliece.as_mut().copy_from_slice(&bytes[X25519_BYTES..]);
+        Ok(Ciphertext {
+            x25519: x25519_dalek::PublicKey::from(x25519_bytes(bytes)),
+            mceliece: crate::Ciphertext(mceliece),
+        })
+    }
+
+    /// Returns the concatenation `ct_X || ct_M`
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.x25519.as_bytes(), self.mceliece.as_ref()].concat()
+    }
+
+    /// The Classic McEliece half of the ciphertext
+    pub fn mceliece(&self) -> &crate::Ciphertext<P> {
+        &self.mceliece
+    }
+}
+
+impl<P: ParameterSet> Debug for PublicKey<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PublicKey")
+            .field("x25519", &self.x25519)
+            .field("mceliece", &self.mceliece)
+            .finish()
+    }
+}
+
+impl<P: ParameterSet> Debug for SecretKey<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SecretKey").field(&"-- redacted --").finish()
+    }
+}
+
+impl<P: ParameterSet> Debug for Ciphertext<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ciphertext")
+            .field("x25519", &self.x25519)
+            .field("mceliece", &self.mceliece)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Mceliece348864;
+
+    type Kem = X25519McEliece<Mceliece348864>;
+
+    #[test]
+    fn test_hybrid_roundtrip() {
+        std::thread::Builder::new()
+            .stack_size(4 * 1024 * 1024)
+            .spawn(|| {
+                let mut rng = crate::nist_aes_rng::NistDrbg::new([0u8; 48]);
+                let (public_key, secret_key) = Kem::keypair(&mut rng).unwrap();
+                let (ciphertext, ss_bob) = Kem::encapsulate(&public_key, &mut rng).unwrap();
+                let ss_alice = Kem::decapsulate(&ciphertext, &secret_key);
+                assert_eq!(ss_bob.as_array(), ss_alice.as_array());
+
+                // the combined encodings round trip
+                let public_key =
+                    PublicKey::<Mceliece348864>::from_bytes(&public_key.to_bytes()).unwrap();
+                let secret_key =
+                    SecretKey::<Mceliece348864>::from_bytes(&secret_key.to_bytes()).unwrap();
+                let ciphertext =
+                    Ciphertext::<Mceliece348864>::from_bytes(&ciphertext.to_bytes()).unwrap();
+                assert_eq!(public_key.to_bytes().len(), Kem::PUBLIC_KEY_BYTES);
+                assert_eq!(secret_key.to_bytes().len(), Kem::SECRET_KEY_BYTES);
+                let ss_alice = Kem::decapsulate(&ciphertext, &secret_key);
+                assert_eq!(ss_bob.as_array(), ss_alice.as_array());
+
+                // tampering with either half changes the shared secret
+                for index in [0, X25519_BYTES] {
+                    let mut bytes = ciphertext.to_bytes();
+                    bytes[index] ^= 1;
+                    let tampered = Ciphertext::<Mceliece348864>::from_bytes(&bytes).unwrap();
+                    let ss = Kem::decapsulate(&tampered, &secret_key);
+                    assert_ne!(ss_bob.as_array(), ss.as_array());
+                }
+
+                assert_eq!(
+                    Ciphertext::<Mceliece348864>::from_bytes(&[0u8; 32]).unwrap_err(),
+                    Error::InvalidLength {
+                        variant: Mceliece348864::VARIANT,
+                        expected: Kem::CIPHERTEXT_BYTES,
+                        actual: 32,
+                    }
+                );
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
@@ -0,0 +1,464 @@
+//! Generation and verification of known answer tests (KATs)
+//!
+//! NIST's `PQCgenKAT_kem` program derives 100 seeds of 48 bytes from a [`NistDrbg`] seeded
+//! with the bytes `0, 1, …, 47` and writes them to a request file (`.req`). For every seed,
+//! the generator is then re-seeded and one key pair, one encapsulation and one decapsulation
+//! are run. The results are written to a response file (`.rsp`):
+//!
+//! ```text
+//! # kem/mceliece348864
+//!
+//! count = 0
+//! seed = 061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7056A8C266F9EF97ED08541DBD2E1FFA1
+//! pk = …
+//! sk = …
+//! ct = …
+//! ss = …
+//! ```
+//!
+//! This module parses and writes both file types, regenerates the responses for any
+//! [`Variant`] and reports the test cases which do not match.
+//!
+//! ```no_run
+//! use classic_mceliece_rust::kat;
+//!
+//! let rsp = std::fs::read_to_string("PQCkemKAT_6492.rsp").unwrap();
+//! let file = kat::parse_rsp(&rsp).unwrap();
+//! let variant = file.variant.expect("header names the variant");
+//! for mismatch in kat::verify(variant, &file.testcases) {
+//!     println!("{mismatch}");
+//! }
+//! ```
+
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Write};
+
+use rand::RngCore;
+
+use crate::{NistDrbg, Variant, CRYPTO_BYTES};
+
+/// The number of test cases in the files created by NIST's `PQCgenKAT_kem`
+pub const KATNUM: usize = 100;
+
+/// A record of a request or response file. The fields `pk`, `sk`, `ct` and `ss` are empty
+/// in request files.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Testcase {
+    pub count: usize,
+    pub seed: [u8; 48],
+    pub pk: Vec<u8>,
+    pub sk: Vec<u8>,
+    pub ct: Vec<u8>,
+    pub ss: Vec<u8>,
+    /// The shared secret returned by decapsulation, set by [`Testcase::generate`]. It is
+    /// not part of the files and should equal the encapsulated `ss`.
+    pub ss_decapsulated: Vec<u8>,
+}
+
+impl Testcase {
+    /// Returns a test case with the given `seed` and empty results, as in a request file
+    pub fn with_seed(count: usize, seed: [u8; 48]) -> Testcase {
+        Testcase {
+            count,
+            seed,
+            pk: Vec::new(),
+            sk: Vec::new(),
+            ct: Vec::new(),
+            ss: Vec::new(),
+            ss_decapsulated: Vec::new(),
+        }
+    }
+
+    /// Runs key generation, encapsulation and decapsulation of `variant` with randomness
+    /// drawn from a [`NistDrbg`] seeded with `seed`, like `PQCgenKAT_kem` does.
+    ///
+    /// This puts the buffers of the KEM on the stack, so the calling thread needs a
+    /// stack of a few megabytes.
+    pub fn generate(variant: Variant, count: usize, seed: [u8; 48]) -> Testcase {
+        let mut rng = NistDrbg::new(seed);
+
+        let mut pk = vec![0u8; variant.public_key_bytes()];
+        let mut sk = vec![0u8; variant.secret_key_bytes()];
+        let mut ct = vec![0u8; variant.ciphertext_bytes()];
+        let mut ss = [0u8; CRYPTO_BYTES];
+        let mut ss_decapsulated = [0u8; CRYPTO_BYTES];
+
+        variant.keypair(&mut pk, &mut sk, &mut rng);
+        variant.encapsulate(&pk, &mut ct, &mut ss, &mut rng);
+        variant.decapsulate(&ct, &sk, &mut ss_decapsulated);
+
+        Testcase {
+            count,
+            seed,
+            pk,
+            sk,
+            ct,
+            ss: ss.to_vec(),
+            ss_decapsulated: ss_decapsulated.to_vec(),
+        }
+    }
+}
+
+impl fmt::Display for Testcase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "count = {}", self.count)?;
+        writeln!(f, "seed = {}", hex::encode_upper(self.seed))?;
+        for (name, value) in [
+            ("pk", &self.pk),
+            ("sk", &self.sk),
+            ("ct", &self.ct),
+            ("ss", &self.ss),
+        ] {
+            if !value.is_empty() {
+                writeln!(f, "{name} = {}", hex::encode_upper(value))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A parsed response file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResponseFile {
+    /// The parameter set named in the header line (`# kem/mceliece348864`), if any
+    pub variant: Option<Variant>,
+    pub testcases: Vec<Testcase>,
+}
+
+/// A line of a request or response file which could not be parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    /// The line number, starting at 1
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid KAT file at line {}: {}",
+            self.line, self.message
+        )
+    }
+}
+
+impl core::error::Error for ParseError {}
+
+/// The result of a test case which differs from the expected one
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Field {
+    PublicKey,
+    SecretKey,
+    Ciphertext,
+    SharedSecret,
+    /// The decapsulated shared secret differs from the encapsulated one
+    Decapsulation,
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Field::PublicKey => "public key",
+            Field::SecretKey => "secret key",
+            Field::Ciphertext => "ciphertext",
+            Field::SharedSecret => "shared secret",
+            Field::Decapsulation => "decapsulated shared secret",
+        })
+    }
+}
+
+/// A test case whose regenerated results differ from the expected ones
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mismatch {
+    pub count: usize,
+    /// The differing results, in the order of the file, followed by
+    /// [`Field::Decapsulation`]
+    pub fields: Vec<Field>,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "count = {}: ", self.count)?;
+        for (i, field) in self.fields.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{field}")?;
+        }
+        f.write_str(" differ")
+    }
+}
+
+/// Derives the `n` seeds of a request file, like `PQCgenKAT_kem` does
+pub fn request(n: usize) -> Vec<Testcase> {
+    let mut entropy_input = [0u8; 48];
+    for (i, e) in entropy_input.iter_mut().enumerate() {
+        *e = i as u8;
+    }
+    let mut rng = NistDrbg::new(entropy_input);
+
+    (0..n)
+        .map(|count| {
+            let mut seed = [0u8; 48];
+            rng.fill_bytes(&mut seed);
+            Testcase::with_seed(count, seed)
+        })
+        .collect()
+}
+
+/// Regenerates the results of all `testcases` for `variant` from their seeds,
+/// see [`Testcase::generate`]
+pub fn respond(variant: Variant, testcases: &[Testcase]) -> Vec<Testcase> {
+    testcases
+        .iter()
+        .map(|testcase| Testcase::generate(variant, testcase.count, testcase.seed))
+        .collect()
+}
+
+/// Regenerates the results of all `testcases` for `variant` and returns the test cases
+/// whose results differ. An empty result means the implementation conforms.
+pub fn verify(variant: Variant, testcases: &[Testcase]) -> Vec<Mismatch> {
+    verify_with(testcases, |expected| {
+        Testcase::generate(variant, expected.count, expected.seed)
+    })
+}
+
+fn verify_with(testcases: &[Testcase], generate: impl Fn(&Testcase) -> Testcase) -> Vec<Mismatch> {
+    testcases
+        .iter()
+        .filter_map(|expected| {
+            let fields = compare(expected, &generate(expected));
+            (!fields.is_empty()).then_some(Mismatch {
+                count: expected.count,
+                fields,
+            })
+        })
+        .collect()
+}
+
+/// Returns the results of the regenerated test case `actual` which differ from `expected`,
+/// and [`Field::Decapsulation`] if its decapsulation did not return its shared secret
+pub fn compare(expected: &Testcase, actual: &Testcase) -> Vec<Field> {
+    [
+        (Field::PublicKey, &expected.pk, &actual.pk),
+        (Field::SecretKey, &expected.sk, &actual.sk),
+        (Field::Ciphertext, &expected.ct, &actual.ct),
+        (Field::SharedSecret, &expected.ss, &actual.ss),
+        (Field::Decapsulation, &actual.ss, &actual.ss_decapsulated),
+    ]
+    .into_iter()
+    .filter(|(_, expected, actual)| expected != actual)
+    .map(|(field, _, _)| field)
+    .collect()
+}
+
+/// Writes a request file containing the counts and seeds of `testcases`
+pub fn write_req(testcases: &[Testcase]) -> String {
+    let mut out = String::new();
+    for testcase in testcases {
+        // writing to a String does not fail
+        let _ = writeln!(out, "count = {}", testcase.count);
+        let _ = writeln!(out, "seed = {}", hex::encode_upper(testcase.seed));
+        let _ = writeln!(out, "pk =\nsk =\nct =\nss =\n");
+    }
+    out
+}
+
+/// Writes a response file for `variant` containing `testcases`
+pub fn write_rsp(variant: Variant, testcases: &[Testcase]) -> String {
+    let mut out = format!("# kem/{}\n\n", variant.name());
+    for testcase in testcases {
+        let _ = writeln!(out, "{testcase}");
+    }
+    out
+}
+
+/// Parses a request file. Only the `count` and `seed` fields are read.
+pub fn parse_req(input: &str) -> Result<Vec<Testcase>, ParseError> {
+    Ok(parse(input)?.testcases)
+}
+
+/// Parses a response file.
+///
+/// The header line names the parameter set. Records are separated by empty lines and
+/// consist of `name = HEX` assignments. Fields other than `count`, `seed`, `pk`, `sk`,
+/// `ct` and `ss` are ignored, like the `*_kem` fields written by the test suite of this
+/// crate.
+pub fn parse_rsp(input: &str) -> Result<ResponseFile, ParseError> {
+    parse(input)
+}
+
+fn parse(input: &str) -> Result<ResponseFile, ParseError> {
+    let mut file = ResponseFile {
+        variant: None,
+        testcases: Vec::new(),
+    };
+    let mut current: Option<Testcase> = None;
+
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        let err = |message: String| ParseError {
+            line: i + 1,
+            message,
+        };
+
+        if let Some(comment) = line.strip_prefix('#') {
+            let name = comment.trim();
+            let name = name.strip_prefix("kem/").unwrap_or(name);
+            file.variant = file.variant.or(Variant::from_name(name));
+            continue;
+        }
+        if line.is_empty() {
+            file.testcases.extend(current.take());
+            continue;
+        }
+
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| err("expected an assignment 'name = value'".to_string()))?;
+        let (name, value) = (name.trim(), value.trim());
+
+        if name == "count" {
+            file.testcases.extend(current.take());
+            let count = value
+                .parse()
+                .map_err(|_| err(format!("invalid count '{value}'")))?;
+            current = Some(Testcase::with_seed(count, [0u8; 48]));
+            continue;
+        }
+
+        let testcase = current
+            .as_mut()
+            .ok_or_else(|| err(format!("'{name}' before the first 'count'")))?;
+        let decode = |value: &str| {
+            hex::decode(value).map_err(|e| err(format!("invalid hex value of '{name}': {e}")))
+        };
+        match name {
+            "seed" => {
+                testcase.seed = decode(value)?
+                    .try_into()
+                    .map_err(|_| err("seed must have 48 bytes".to_string()))?
+            }
+            "pk" => testcase.pk = decode(value)?,
+            "sk" => testcase.sk = decode(value)?,
+            "ct" => testcase.ct = decode(value)?,
+            "ss" => testcase.ss = decode(value)?,
+            _ => {}
+        }
+    }
+    file.testcases.extend(current);
+
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_seeds() {
+        let testcases = request(2);
+        assert_eq!(testcases[0].count, 0);
+        assert_eq!(
+            hex::encode_upper(testcases[0].seed),
+            "061550234D158C5EC95595FE04EF7A25767F2E24CC2BC479D09D86DC9ABCFDE7056A8C266F9EF97ED08541DBD2E1FFA1"
+        );
+        assert_eq!(parse_req(&write_req(&testcases)).unwrap(), testcases);
+    }
+
+    #[test]
+    fn test_parse_rsp() {
+        let input = "# kem/mceliece460896f\n\ncount = 3\nseed = 00\npk = 0102\n";
+        assert_eq!(
+            parse_rsp(input).unwrap_err(),
+            ParseError {
+                line: 4,
+                message: "seed must have 48 bytes".to_string()
+            }
+        );
+
+        let testcase = Testcase {
+            count: 3,
+            seed: [7u8; 48],
+            pk: vec![1, 2],
+            sk: vec![3],
+            ct: vec![4, 5],
+            ss: vec![6],
+            ss_decapsulated: Vec::new(),
+        };
+        let output = write_rsp(Variant::Mceliece460896f, &[testcase.clone(), testcase]);
+        // unknown fields are skipped
+        let output = output.replacen("ss = 06\n", "ss = 06\npk_kem = FF\n", 1);
+        let file = parse_rsp(&output).unwrap();
+        assert_eq!(file.variant, Some(Variant::Mceliece460896f));
+        assert_eq!(file.testcases.len(), 2);
+        assert_eq!(file.testcases[1].ct, [4, 5]);
+    }
+
+    #[test]
+    fn test_verify() {
+        std::thread::Builder::new()
+            .stack_size(4 * 1024 * 1024)
+            .spawn(|| {
+                let variant = Variant::Mceliece348864;
+                let mut testcases = respond(variant, &request(1));
+                assert_eq!(verify(variant, &testcases), []);
+
+                testcases[0].ss[0] ^= 1;
+                testcases[0].ct[0] ^= 1;
+                let mismatches = verify(variant, &testcases);
+                assert_eq!(
+                    mismatches,
+                    [Mismatch {
+                        count: 0,
+                        fields: vec![Field::Ciphertext, Field::SharedSecret]
+                    }]
+                );
+                assert_eq!(
+                    mismatches[0].to_string(),
+                    "count = 0: ciphertext, shared secret differ"
+                );
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_verify_decapsulation() {
+        std::thread::Builder::new()
+            .stack_size(4 * 1024 * 1024)
+            .spawn(|| {
+                let variant = Variant::Mceliece348864;
+                let testcases = respond(variant, &request(2));
+                assert_eq!(testcases[1].ss, testcases[1].ss_decapsulated);
+
+                // an implementation whose decapsulation disagrees in the second test case
+                let mismatches = verify_with(&testcases, |expected| {
+                    let mut actual = Testcase::generate(variant, expected.count, expected.seed);
+                    if actual.count == 1 {
+                        actual.ss_decapsulated[0] ^= 1;
+                    }
+                    actual
+                });
+                assert_eq!(
+                    mismatches,
+                    [Mismatch {
+                        count: 1,
+                        fields: vec![Field::Decapsulation]
+                    }]
+                );
+                assert_eq!(
+                    mismatches[0].to_string(),
+                    "count = 1: decapsulated shared secret differ"
+                );
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
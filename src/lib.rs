@@ -3,20 +3,51 @@
 #![forbid(unsafe_code)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(any(feature = "chacha20poly1305", feature = "aes-gcm"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "chacha20poly1305", feature = "aes-gcm")))
+)]
+pub mod aead;
+mod any;
 mod api;
+#[cfg(feature = "pkcs8")]
+mod asn1;
 mod benes;
 mod bm;
 mod controlbits;
 mod crypto_hash;
+mod decapsulation_key;
 mod decrypt;
 mod encrypt;
+#[cfg(any(feature = "chacha20poly1305", feature = "aes-gcm"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "chacha20poly1305", feature = "aes-gcm")))
+)]
+pub mod envelope;
+mod error;
+mod fft;
+pub mod generic;
 mod gf;
+#[cfg(feature = "hpke")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hpke")))]
+pub mod hpke;
+#[cfg(feature = "hybrid")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hybrid")))]
+pub mod hybrid;
 mod int32_sort;
+#[cfg(feature = "kat")]
+#[cfg_attr(docsrs, doc(cfg(feature = "kat")))]
+pub mod kat;
+#[cfg(any(test, feature = "nist-drbg"))]
 mod nist_aes_rng;
 mod operations;
 mod params;
 mod pk_gen;
 mod root;
+#[cfg(feature = "serde")]
+mod serialization;
 mod sk_gen;
 mod synd;
 mod test_katkem;
@@ -25,8 +56,12 @@ mod test_utils;
 mod transpose;
 mod uint64_sort;
 mod util;
+mod validate;
+mod variant;
+mod vec;
 
 use core::fmt::Debug;
+use pk_gen::PkMatrix;
 use rand::{CryptoRng, RngCore};
 
 #[cfg(feature = "alloc")]
@@ -37,10 +72,26 @@ use alloc::boxed::Box;
 #[cfg(feature = "kem")]
 pub use kem_api::ClassicMcEliece;
 
+pub use any::{AnyCiphertext, AnyPublicKey, AnySecretKey};
 pub use api::{
-    CRYPTO_BYTES, CRYPTO_CIPHERTEXTBYTES, CRYPTO_PRIMITIVE, CRYPTO_PUBLICKEYBYTES,
-    CRYPTO_SECRETKEYBYTES,
+    DefaultParameterSet, CRYPTO_BYTES, CRYPTO_CIPHERTEXTBYTES, CRYPTO_PRIMITIVE,
+    CRYPTO_PUBLICKEYBYTES, CRYPTO_SECRETKEYBYTES,
 };
+pub use decapsulation_key::DecapsulationKey;
+pub use error::{Error, PublicKeyError, SecretKeyError};
+#[cfg(feature = "nist-drbg")]
+pub use nist_aes_rng::{NistDrbg, NistDrbgSeed};
+pub use params::{
+    ByteArray, Mceliece348864, Mceliece348864f, Mceliece460896, Mceliece460896f, Mceliece6688128,
+    Mceliece6688128f, Mceliece6960119, Mceliece6960119f, Mceliece8192128, Mceliece8192128f,
+    ParameterSet,
+};
+#[cfg(feature = "pkcs8")]
+#[cfg_attr(docsrs, doc(cfg(feature = "pkcs8")))]
+pub use pkcs8;
+#[cfg(feature = "serde")]
+pub use serialization::ExposeSecret;
+pub use variant::Variant;
 
 mod macros {
     /// This macro(A, B, C, T) allows to get “&A[B..B+C]” of type “&[T]” as type “&[T; C]”.
@@ -68,23 +119,24 @@ mod macros {
 }
 
 #[derive(Debug)]
-enum KeyBufferMut<'a, const SIZE: usize> {
-    Borrowed(&'a mut [u8; SIZE]),
+enum KeyBufferMut<'a, A: ByteArray> {
+    Borrowed(&'a mut A),
     #[cfg(feature = "alloc")]
-    Owned(Box<[u8; SIZE]>),
+    Owned(Box<A>),
 }
 
-impl<const SIZE: usize> KeyBufferMut<'_, SIZE> {
+impl<A: ByteArray> KeyBufferMut<'_, A> {
     #[cfg(feature = "alloc")]
-    fn to_owned(&self) -> KeyBufferMut<'static, SIZE> {
-        let mut new_buffer = util::alloc_boxed_array::<SIZE>();
-        new_buffer.copy_from_slice(self.as_ref());
+    fn to_owned(&self) -> KeyBufferMut<'static, A> {
+        let mut new_buffer = A::zeroed_boxed();
+        let source: &A = self.as_ref();
+        (*new_buffer).as_mut().copy_from_slice(source.as_ref());
         KeyBufferMut::Owned(new_buffer)
     }
 }
 
-impl<const SIZE: usize> AsRef<[u8; SIZE]> for KeyBufferMut<'_, SIZE> {
-    fn as_ref(&self) -> &[u8; SIZE] {
+impl<A: ByteArray> AsRef<A> for KeyBufferMut<'_, A> {
+    fn as_ref(&self) -> &A {
         match &self {
             KeyBufferMut::Borrowed(buf) => buf,
             #[cfg(feature = "alloc")]
@@ -93,8 +145,8 @@ impl<const SIZE: usize> AsRef<[u8; SIZE]> for KeyBufferMut<'_, SIZE> {
     }
 }
 
-impl<const SIZE: usize> AsMut<[u8; SIZE]> for KeyBufferMut<'_, SIZE> {
-    fn as_mut(&mut self) -> &mut [u8; SIZE] {
+impl<A: ByteArray> AsMut<A> for KeyBufferMut<'_, A> {
+    fn as_mut(&mut self) -> &mut A {
         match self {
             KeyBufferMut::Borrowed(buf) => buf,
             #[cfg(feature = "alloc")]
@@ -104,66 +156,96 @@ impl<const SIZE: usize> AsMut<[u8; SIZE]> for KeyBufferMut<'_, SIZE> {
 }
 
 #[cfg(feature = "zeroize")]
-impl<const SIZE: usize> zeroize::Zeroize for KeyBufferMut<'_, SIZE> {
+impl<A: ByteArray> zeroize::Zeroize for KeyBufferMut<'_, A> {
     fn zeroize(&mut self) {
-        match self {
-            KeyBufferMut::Borrowed(buf) => buf.zeroize(),
-            #[cfg(feature = "alloc")]
-            KeyBufferMut::Owned(buf) => buf.zeroize(),
-        }
+        let buf: &mut A = self.as_mut();
+        buf.as_mut().zeroize();
     }
 }
 
 /// A Classic McEliece public key. These are very large compared to keys
 /// in most other cryptographic algorithms.
+///
+/// The parameter set `P` is part of the type, so a key of one variant can not be used
+/// with a ciphertext or secret key of another one. It defaults to [`DefaultParameterSet`].
 #[derive(Debug)]
 #[must_use]
-pub struct PublicKey<'a>(KeyBufferMut<'a, CRYPTO_PUBLICKEYBYTES>);
+pub struct PublicKey<'a, P: ParameterSet = DefaultParameterSet>(
+    KeyBufferMut<'a, P::PublicKeyArray>,
+);
+
+impl<P: ParameterSet> PublicKey<'_, P> {
+    /// Copies `bytes` into a public key on the heap, checking its length and structure
+    /// with [`PublicKey::validate`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn try_from_bytes(bytes: &[u8]) -> Result<PublicKey<'static, P>, Error> {
+        if bytes.len() != P::PUBLIC_KEY_BYTES {
+            return Err(Error::InvalidLength {
+                variant: P::VARIANT,
+                expected: P::PUBLIC_KEY_BYTES,
+                actual: bytes.len(),
+            });
+        }
+
+        let mut buf = P::PublicKeyArray::zeroed_boxed();
+        (*buf).as_mut().copy_from_slice(bytes);
+        let public_key = PublicKey::from(buf);
+        public_key.validate()?;
+        Ok(public_key)
+    }
+
+    /// Checks the structure of the key. For the mceliece6960119 variants, the padding bits
+    /// at the end of each matrix row must be zero. The other variants have no padding and
+    /// every key of the right length is accepted.
+    pub fn validate(&self) -> Result<(), Error> {
+        validate::check_pk::<P>(self.as_ref())?;
+        Ok(())
+    }
 
-impl PublicKey<'_> {
     /// Copies the key to the heap and makes it `'static`.
     #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
     #[cfg(feature = "alloc")]
-    pub fn to_owned(&self) -> PublicKey<'static> {
+    pub fn to_owned(&self) -> PublicKey<'static, P> {
         PublicKey(self.0.to_owned())
     }
 
-    pub fn as_array(&self) -> &[u8; CRYPTO_PUBLICKEYBYTES] {
+    pub fn as_array(&self) -> &P::PublicKeyArray {
         self.0.as_ref()
     }
 }
 
-impl AsRef<[u8]> for PublicKey<'_> {
+impl<P: ParameterSet> AsRef<[u8]> for PublicKey<'_, P> {
     fn as_ref(&self) -> &[u8] {
-        self.0.as_ref()
+        self.as_array().as_ref()
     }
 }
 
-impl<'a> From<&'a mut [u8; CRYPTO_PUBLICKEYBYTES]> for PublicKey<'a> {
-    fn from(data: &'a mut [u8; CRYPTO_PUBLICKEYBYTES]) -> Self {
+impl<'a, P: ParameterSet> From<&'a mut P::PublicKeyArray> for PublicKey<'a, P> {
+    fn from(data: &'a mut P::PublicKeyArray) -> Self {
         Self(KeyBufferMut::Borrowed(data))
     }
 }
 
 #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
 #[cfg(feature = "alloc")]
-impl From<Box<[u8; CRYPTO_PUBLICKEYBYTES]>> for PublicKey<'static> {
-    fn from(data: Box<[u8; CRYPTO_PUBLICKEYBYTES]>) -> Self {
+impl<P: ParameterSet> From<Box<P::PublicKeyArray>> for PublicKey<'static, P> {
+    fn from(data: Box<P::PublicKeyArray>) -> Self {
         Self(KeyBufferMut::Owned(data))
     }
 }
 
 #[cfg(feature = "zeroize")]
-impl zeroize::Zeroize for PublicKey<'_> {
+impl<P: ParameterSet> zeroize::Zeroize for PublicKey<'_, P> {
     fn zeroize(&mut self) {
         self.0.zeroize();
     }
 }
 
 #[cfg(feature = "zeroize")]
-impl zeroize::ZeroizeOnDrop for PublicKey<'_> {}
+impl<P: ParameterSet> zeroize::ZeroizeOnDrop for PublicKey<'_, P> {}
 
-impl Drop for PublicKey<'_> {
+impl<P: ParameterSet> Drop for PublicKey<'_, P> {
     fn drop(&mut self) {
         #[cfg(feature = "zeroize")]
         {
@@ -176,15 +258,119 @@ impl Drop for PublicKey<'_> {
 /// A Classic McEliece secret key.
 ///
 /// Should be kept on the device where it's generated. Used to decapsulate the [`SharedSecret`]
-/// from the [`Ciphertext`] received from the encapsulator.
+/// from the [`Ciphertext`] received from the encapsulator. The parameter set `P` defaults
+/// to [`DefaultParameterSet`].
 #[must_use]
-pub struct SecretKey<'a>(KeyBufferMut<'a, CRYPTO_SECRETKEYBYTES>);
+pub struct SecretKey<'a, P: ParameterSet = DefaultParameterSet>(
+    KeyBufferMut<'a, P::SecretKeyArray>,
+);
+
+impl<'a, P: ParameterSet> SecretKey<'a, P> {
+    /// Regenerates the secret key expanded from `seed` into `secret_key_buf`.
+    ///
+    /// For a key generated by this library, `SecretKey::from_seed(&secret_key.seed(), …)`
+    /// results in the same key. Only the secret key is computed, use
+    /// [`generic::keypair_from_seed`] to rebuild the public key as well.
+    pub fn from_seed(seed: &SecretSeed, secret_key_buf: &'a mut P::SecretKeyArray) -> Self {
+        operations::crypto_kem_keypair_from_seed::<P>(
+            None,
+            secret_key_buf.as_mut(),
+            &seed.0,
+            &mut PkMatrix::new(),
+        );
+        Self(KeyBufferMut::Borrowed(secret_key_buf))
+    }
+
+    /// Convenient wrapper around [`SecretKey::from_seed`] that stores the key on the heap
+    /// and returns it with the ``'static`` lifetime.
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn from_seed_boxed(seed: &SecretSeed) -> SecretKey<'static, P> {
+        let mut secret_key_buf = P::SecretKeyArray::zeroed_boxed();
+        operations::crypto_kem_keypair_from_seed::<P>(
+            None,
+            (*secret_key_buf).as_mut(),
+            &seed.0,
+            &mut PkMatrix::new(),
+        );
+        SecretKey(KeyBufferMut::Owned(secret_key_buf))
+    }
+
+    /// Returns the 32 byte seed stored in the first bytes of the secret key.
+    ///
+    /// The whole secret key (and the public key) can be regenerated from it, so it is
+    /// enough to persist the seed. See [`SecretKey::from_seed`].
+    pub fn seed(&self) -> SecretSeed {
+        let mut seed = [0u8; 32];
+        seed.copy_from_slice(&self.as_ref()[..32]);
+        SecretSeed(seed)
+    }
+
+    /// Regenerates the public key belonging to this secret key into `public_key_buf`.
+    ///
+    /// The output is identical to the public key returned by the `keypair` call that created
+    /// this secret key. It is derived from the [`seed`](SecretKey::seed) and takes as long as a
+    /// successful key generation attempt. Fails if the rest of the secret key does not match
+    /// its seed.
+    pub fn public_key_into<'public>(
+        &self,
+        public_key_buf: &'public mut P::PublicKeyArray,
+    ) -> Result<PublicKey<'public, P>, Error> {
+        let mut public_key = PublicKey(KeyBufferMut::Borrowed(public_key_buf));
+        self.write_public_key(&mut public_key)?;
+        Ok(public_key)
+    }
+
+    /// Convenient wrapper around [`SecretKey::public_key_into`] that stores the public key on
+    /// the heap and returns it with the ``'static`` lifetime.
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn public_key(&self) -> Result<PublicKey<'static, P>, Error> {
+        let mut public_key = PublicKey(KeyBufferMut::Owned(P::PublicKeyArray::zeroed_boxed()));
+        self.write_public_key(&mut public_key)?;
+        Ok(public_key)
+    }
+
+    /// Checks the structure of the secret key.
+    ///
+    /// Verifies that the pivots are well-formed for the parameter set, that the Goppa
+    /// polynomial is irreducible of degree `t` and that the support described by the control
+    /// bits contains no root of it. The error names the first section found to be
+    /// malformed. The check is considerably slower than a decapsulation.
+    ///
+    /// This check is not constant-time: the irreducibility test runs the Euclidean
+    /// algorithm on the secret Goppa polynomial, so its running time depends on the key.
+    /// Validate keys when they are loaded or imported, not where an attacker can time
+    /// repeated checks of the same key.
+    pub fn validate(&self) -> Result<(), Error> {
+        validate::check_sk::<P>(self.as_ref())?;
+        Ok(())
+    }
+
+    /// Like [`SecretKey::validate`], but additionally checks that the key belongs to
+    /// `public_key`. This regenerates the public key, see [`SecretKey::public_key`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn validate_with_public_key(&self, public_key: &PublicKey<'_, P>) -> Result<(), Error> {
+        self.validate()?;
+        if self.public_key()?.as_array() != public_key.as_array() {
+            return Err(SecretKeyError::PublicKeyMismatch.into());
+        }
+        Ok(())
+    }
+
+    fn write_public_key(&self, public_key: &mut PublicKey<'_, P>) -> Result<(), Error> {
+        let public_key_buf: &mut P::PublicKeyArray = public_key.0.as_mut();
+        match operations::crypto_kem_pk_from_sk::<P>(public_key_buf.as_mut(), self.as_ref()) {
+            0 => Ok(()),
+            _ => Err(SecretKeyError::Seed.into()),
+        }
+    }
 
-impl SecretKey<'_> {
     /// Copies the key to the heap and makes it `'static`.
     #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
     #[cfg(feature = "alloc")]
-    pub fn to_owned(&self) -> SecretKey<'static> {
+    pub fn to_owned(&self) -> SecretKey<'static, P> {
         SecretKey(self.0.to_owned())
     }
 
@@ -194,50 +380,50 @@ impl SecretKey<'_> {
     /// `SecretKey` can be bad for security. The `SecretKey` type is designed to keep the
     /// backing data in a single location in memory and zeroing it out when it goes out
     /// of scope.
-    pub fn as_array(&self) -> &[u8; CRYPTO_SECRETKEYBYTES] {
+    pub fn as_array(&self) -> &P::SecretKeyArray {
         self.0.as_ref()
     }
 }
 
-impl Debug for SecretKey<'_> {
+impl<P: ParameterSet> Debug for SecretKey<'_, P> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_tuple("SecretKey").field(&"-- redacted --").finish()
     }
 }
 
-impl AsRef<[u8]> for SecretKey<'_> {
+impl<P: ParameterSet> AsRef<[u8]> for SecretKey<'_, P> {
     fn as_ref(&self) -> &[u8] {
-        self.0.as_ref()
+        self.as_array().as_ref()
     }
 }
 
-impl<'a> From<&'a mut [u8; CRYPTO_SECRETKEYBYTES]> for SecretKey<'a> {
+impl<'a, P: ParameterSet> From<&'a mut P::SecretKeyArray> for SecretKey<'a, P> {
     /// Represents a mutable byte array of the correct size as a `SecretKey`.
     /// Please note that the array will be zeroed on drop.
-    fn from(data: &'a mut [u8; CRYPTO_SECRETKEYBYTES]) -> Self {
+    fn from(data: &'a mut P::SecretKeyArray) -> Self {
         Self(KeyBufferMut::Borrowed(data))
     }
 }
 
 #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
 #[cfg(feature = "alloc")]
-impl From<Box<[u8; CRYPTO_SECRETKEYBYTES]>> for SecretKey<'static> {
-    fn from(data: Box<[u8; CRYPTO_SECRETKEYBYTES]>) -> Self {
+impl<P: ParameterSet> From<Box<P::SecretKeyArray>> for SecretKey<'static, P> {
+    fn from(data: Box<P::SecretKeyArray>) -> Self {
         Self(KeyBufferMut::Owned(data))
     }
 }
 
 #[cfg(feature = "zeroize")]
-impl zeroize::Zeroize for SecretKey<'_> {
+impl<P: ParameterSet> zeroize::Zeroize for SecretKey<'_, P> {
     fn zeroize(&mut self) {
         self.0.zeroize();
     }
 }
 
 #[cfg(feature = "zeroize")]
-impl zeroize::ZeroizeOnDrop for SecretKey<'_> {}
+impl<P: ParameterSet> zeroize::ZeroizeOnDrop for SecretKey<'_, P> {}
 
-impl Drop for SecretKey<'_> {
+impl<P: ParameterSet> Drop for SecretKey<'_, P> {
     fn drop(&mut self) {
         #[cfg(feature = "zeroize")]
         {
@@ -247,33 +433,30 @@ impl Drop for SecretKey<'_> {
     }
 }
 
-/// The ciphertext computed by the encapsulator.
+/// The ciphertext computed by the encapsulator. The parameter set `P` defaults
+/// to [`DefaultParameterSet`].
 #[derive(Debug)]
 #[must_use]
-pub struct Ciphertext([u8; CRYPTO_CIPHERTEXTBYTES]);
+pub struct Ciphertext<P: ParameterSet = DefaultParameterSet>(P::CiphertextArray);
 
-impl Ciphertext {
-    pub fn as_array(&self) -> &[u8; CRYPTO_CIPHERTEXTBYTES] {
+impl<P: ParameterSet> Ciphertext<P> {
+    pub fn as_array(&self) -> &P::CiphertextArray {
         &self.0
     }
 }
 
-impl AsRef<[u8]> for Ciphertext {
+impl<P: ParameterSet> AsRef<[u8]> for Ciphertext<P> {
     fn as_ref(&self) -> &[u8] {
         self.0.as_ref()
     }
 }
 
-impl From<[u8; CRYPTO_CIPHERTEXTBYTES]> for Ciphertext {
-    fn from(data: [u8; CRYPTO_CIPHERTEXTBYTES]) -> Self {
-        Self(data)
-    }
-}
-
 /// The shared secret computed by the KEM. Returned from both the
 /// encapsulator and decapsulator.
+///
+/// It has the same size for all parameter sets and hence carries no parameter set.
 #[must_use]
-pub struct SharedSecret<'a>(KeyBufferMut<'a, CRYPTO_BYTES>);
+pub struct SharedSecret<'a>(KeyBufferMut<'a, [u8; CRYPTO_BYTES]>);
 
 impl SharedSecret<'_> {
     /// Copies the secret to the heap and makes it `'static`.
@@ -322,22 +505,75 @@ impl Drop for SharedSecret<'_> {
     }
 }
 
+/// The 32 byte seed a [`SecretKey`] is expanded from.
+///
+/// This is the compact "seed-only" encoding of a secret key. It has the same size for all
+/// parameter sets, but expands to a different key for each of them.
+#[must_use]
+pub struct SecretSeed([u8; 32]);
+
+impl SecretSeed {
+    /// Returns the seed as an array of bytes.
+    ///
+    /// Everything said about [`SecretKey::as_array`] applies here as well.
+    pub fn as_array(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl Debug for SecretSeed {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("SecretSeed")
+            .field(&"-- redacted --")
+            .finish()
+    }
+}
+
+impl AsRef<[u8]> for SecretSeed {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for SecretSeed {
+    fn from(data: [u8; 32]) -> Self {
+        Self(data)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for SecretSeed {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for SecretSeed {}
+
+impl Drop for SecretSeed {
+    fn drop(&mut self) {
+        #[cfg(feature = "zeroize")]
+        {
+            use zeroize::Zeroize;
+            self.zeroize();
+        }
+    }
+}
+
 /// KEM Keypair generation.
 ///
 /// Generate a public and secret key.
 /// The public key is meant to be shared with any party,
 /// but access to the secret key must be limited to the generating party.
+///
+/// Panics if `rng` fails to provide random bytes, see [`try_keypair`].
 pub fn keypair<'public, 'secret, R: CryptoRng + RngCore>(
     public_key_buf: &'public mut [u8; CRYPTO_PUBLICKEYBYTES],
     secret_key_buf: &'secret mut [u8; CRYPTO_SECRETKEYBYTES],
     rng: &mut R,
 ) -> (PublicKey<'public>, SecretKey<'secret>) {
-    operations::crypto_kem_keypair(public_key_buf, secret_key_buf, rng);
-
-    (
-        PublicKey(KeyBufferMut::Borrowed(public_key_buf)),
-        SecretKey(KeyBufferMut::Borrowed(secret_key_buf)),
-    )
+    generic::keypair::<DefaultParameterSet, _>(public_key_buf, secret_key_buf, rng)
 }
 
 /// Convenient wrapper around [`keypair`] that stores the public and private keys on the heap
@@ -347,15 +583,53 @@ pub fn keypair<'public, 'secret, R: CryptoRng + RngCore>(
 pub fn keypair_boxed<R: CryptoRng + RngCore>(
     rng: &mut R,
 ) -> (PublicKey<'static>, SecretKey<'static>) {
-    let mut public_key_buf = util::alloc_boxed_array::<CRYPTO_PUBLICKEYBYTES>();
-    let mut secret_key_buf = util::alloc_boxed_array::<CRYPTO_SECRETKEYBYTES>();
+    generic::keypair_boxed::<DefaultParameterSet, _>(rng)
+}
+
+/// Fallible KEM Keypair generation.
+///
+/// Like [`keypair`], but returns [`Error::Rng`] instead of panicking if `rng` fails. Both
+/// buffers are then set to all 0's, so no partially generated key material is left behind.
+/// Returns [`Error::Allocation`] without touching the buffers if the scratch space of key
+/// generation can not be allocated, which is on the heap with the `alloc` feature.
+pub fn try_keypair<'public, 'secret, R: CryptoRng + RngCore>(
+    public_key_buf: &'public mut [u8; CRYPTO_PUBLICKEYBYTES],
+    secret_key_buf: &'secret mut [u8; CRYPTO_SECRETKEYBYTES],
+    rng: &mut R,
+) -> Result<(PublicKey<'public>, SecretKey<'secret>), Error> {
+    generic::try_keypair::<DefaultParameterSet, _>(public_key_buf, secret_key_buf, rng)
+}
 
-    operations::crypto_kem_keypair(&mut public_key_buf, &mut secret_key_buf, rng);
+/// Convenient wrapper around [`try_keypair`] that stores the public and private keys on the
+/// heap and returns them with the ``'static`` lifetime. Returns [`Error::Allocation`] if the
+/// keys or the scratch space of key generation can not be allocated.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn try_keypair_boxed<R: CryptoRng + RngCore>(
+    rng: &mut R,
+) -> Result<(PublicKey<'static>, SecretKey<'static>), Error> {
+    generic::try_keypair_boxed::<DefaultParameterSet, _>(rng)
+}
+
+/// Deterministic KEM Keypair generation.
+///
+/// Like [`keypair`], but derives the keys from the 32 bytes `seed` instead of drawing
+/// randomness. The same seed always results in the same keys, so it must be kept as secret
+/// as the secret key itself.
+pub fn keypair_from_seed<'public, 'secret>(
+    public_key_buf: &'public mut [u8; CRYPTO_PUBLICKEYBYTES],
+    secret_key_buf: &'secret mut [u8; CRYPTO_SECRETKEYBYTES],
+    seed: &[u8; 32],
+) -> (PublicKey<'public>, SecretKey<'secret>) {
+    generic::keypair_from_seed::<DefaultParameterSet>(public_key_buf, secret_key_buf, seed)
+}
 
-    (
-        PublicKey(KeyBufferMut::Owned(public_key_buf)),
-        SecretKey(KeyBufferMut::Owned(secret_key_buf)),
-    )
+/// Convenient wrapper around [`keypair_from_seed`] that stores the public and private keys
+/// on the heap and returns them with the ``'static`` lifetime.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn keypair_from_seed_boxed(seed: &[u8; 32]) -> (PublicKey<'static>, SecretKey<'static>) {
+    generic::keypair_from_seed_boxed::<DefaultParameterSet>(seed)
 }
 
 /// KEM Encapsulation.
@@ -364,22 +638,17 @@ pub fn keypair_boxed<R: CryptoRng + RngCore>(
 /// The returned ciphertext should be sent back to the entity holding
 /// the secret key corresponding to public key given here, so they can compute
 /// the same shared key.
+///
+/// If `public_key` is malformed (see [`PublicKey::validate`]), the syndrome part of the
+/// ciphertext and the shared secret are all zero. Check untrusted keys with
+/// [`PublicKey::validate`] first, or use [`try_encapsulate`], which reports the error.
+/// Panics if `rng` fails to provide random bytes.
 pub fn encapsulate<'shared_secret, R: CryptoRng + RngCore>(
     public_key: &PublicKey<'_>,
     shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
     rng: &mut R,
 ) -> (Ciphertext, SharedSecret<'shared_secret>) {
-    let mut shared_secret_buf = KeyBufferMut::Borrowed(shared_secret_buf);
-    let mut ciphertext_buf = [0u8; CRYPTO_CIPHERTEXTBYTES];
-
-    operations::crypto_kem_enc(
-        &mut ciphertext_buf,
-        shared_secret_buf.as_mut(),
-        public_key.0.as_ref(),
-        rng,
-    );
-
-    (Ciphertext(ciphertext_buf), SharedSecret(shared_secret_buf))
+    generic::encapsulate(public_key, shared_secret_buf, rng)
 }
 
 /// Convenient wrapper around [`encapsulate`] that stores the shared secret on the heap
@@ -390,17 +659,55 @@ pub fn encapsulate_boxed<R: CryptoRng + RngCore>(
     public_key: &PublicKey<'_>,
     rng: &mut R,
 ) -> (Ciphertext, SharedSecret<'static>) {
-    let mut shared_secret_buf = KeyBufferMut::Owned(Box::new([0u8; CRYPTO_BYTES]));
-    let mut ciphertext_buf = [0u8; CRYPTO_CIPHERTEXTBYTES];
+    generic::encapsulate_boxed(public_key, rng)
+}
 
-    operations::crypto_kem_enc(
-        &mut ciphertext_buf,
-        shared_secret_buf.as_mut(),
-        public_key.0.as_ref(),
-        rng,
-    );
+/// Fallible KEM Encapsulation.
+///
+/// Like [`encapsulate`], but returns an error for a malformed `public_key` (see
+/// [`PublicKey::validate`]) instead of an all-zero shared secret, and [`Error::Rng`] instead
+/// of panicking if `rng` fails.
+pub fn try_encapsulate<'shared_secret, R: CryptoRng + RngCore>(
+    public_key: &PublicKey<'_>,
+    shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
+    rng: &mut R,
+) -> Result<(Ciphertext, SharedSecret<'shared_secret>), Error> {
+    generic::try_encapsulate(public_key, shared_secret_buf, rng)
+}
+
+/// Convenient wrapper around [`try_encapsulate`] that stores the shared secret on the heap
+/// and returns it with the ``'static`` lifetime.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn try_encapsulate_boxed<R: CryptoRng + RngCore>(
+    public_key: &PublicKey<'_>,
+    rng: &mut R,
+) -> Result<(Ciphertext, SharedSecret<'static>), Error> {
+    generic::try_encapsulate_boxed(public_key, rng)
+}
 
-    (Ciphertext(ciphertext_buf), SharedSecret(shared_secret_buf))
+/// Derandomized KEM Encapsulation.
+///
+/// Like [`try_encapsulate`], but derives the error vector from the 32 bytes `coins`
+/// instead of drawing randomness, see [`generic::encapsulate_derand`]. The same coins and
+/// public key always result in the same ciphertext and shared secret.
+pub fn encapsulate_derand<'shared_secret>(
+    public_key: &PublicKey<'_>,
+    coins: &[u8; 32],
+    shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
+) -> Result<(Ciphertext, SharedSecret<'shared_secret>), Error> {
+    generic::encapsulate_derand(public_key, coins, shared_secret_buf)
+}
+
+/// Convenient wrapper around [`encapsulate_derand`] that stores the shared secret on the
+/// heap and returns it with the ``'static`` lifetime.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn encapsulate_derand_boxed(
+    public_key: &PublicKey<'_>,
+    coins: &[u8; 32],
+) -> Result<(Ciphertext, SharedSecret<'static>), Error> {
+    generic::encapsulate_derand_boxed(public_key, coins)
 }
 
 /// KEM Decapsulation.
@@ -412,15 +719,7 @@ pub fn decapsulate<'shared_secret>(
     secret_key: &SecretKey,
     shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
 ) -> SharedSecret<'shared_secret> {
-    let mut shared_secret_buf = KeyBufferMut::Borrowed(shared_secret_buf);
-
-    operations::crypto_kem_dec(
-        shared_secret_buf.as_mut(),
-        ciphertext.as_array(),
-        secret_key.as_array(),
-    );
-
-    SharedSecret(shared_secret_buf)
+    generic::decapsulate(ciphertext, secret_key, shared_secret_buf)
 }
 
 /// Convenient wrapper around [`decapsulate`] that stores the shared secret on the heap
@@ -428,15 +727,31 @@ pub fn decapsulate<'shared_secret>(
 #[cfg(feature = "alloc")]
 #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
 pub fn decapsulate_boxed(ciphertext: &Ciphertext, secret_key: &SecretKey) -> SharedSecret<'static> {
-    let mut shared_secret_buf = KeyBufferMut::Owned(Box::new([0u8; CRYPTO_BYTES]));
+    generic::decapsulate_boxed(ciphertext, secret_key)
+}
 
-    operations::crypto_kem_dec(
-        shared_secret_buf.as_mut(),
-        ciphertext.as_array(),
-        secret_key.as_array(),
-    );
+/// Fallible KEM Decapsulation.
+///
+/// Like [`decapsulate`], but returns [`Error::InvalidCiphertext`] if the padding bits of
+/// `ciphertext` are not zero (mceliece6960119 only). A ciphertext which does not decode
+/// still results in a pseudo-random shared secret (implicit rejection), not in an error.
+pub fn try_decapsulate<'shared_secret>(
+    ciphertext: &Ciphertext,
+    secret_key: &SecretKey,
+    shared_secret_buf: &'shared_secret mut [u8; CRYPTO_BYTES],
+) -> Result<SharedSecret<'shared_secret>, Error> {
+    generic::try_decapsulate(ciphertext, secret_key, shared_secret_buf)
+}
 
-    SharedSecret(shared_secret_buf)
+/// Convenient wrapper around [`try_decapsulate`] that stores the shared secret on the heap
+/// and returns it with the ``'static`` lifetime.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub fn try_decapsulate_boxed(
+    ciphertext: &Ciphertext,
+    secret_key: &SecretKey,
+) -> Result<SharedSecret<'static>, Error> {
+    generic::try_decapsulate_boxed(ciphertext, secret_key)
 }
 
 #[cfg(feature = "kem")]
@@ -445,7 +760,7 @@ mod kem_api {
     use kem::{Decapsulator, EncappedKey, Encapsulator, SharedSecret};
     use rand::{CryptoRng, RngCore};
 
-    use crate::{Ciphertext, PublicKey, SecretKey};
+    use crate::{Ciphertext, DefaultParameterSet, PublicKey, SecretKey};
     use crate::{CRYPTO_BYTES, CRYPTO_CIPHERTEXTBYTES};
 
     /// A struct for encapsulating a shared key using Classic McEliece.
@@ -467,12 +782,14 @@ mod kem_api {
                 .try_into()
                 .expect("GenericArray should be CRYPTO_BYTES long");
 
-            crate::operations::crypto_kem_enc(
+            recip_pubkey.validate().map_err(|_| kem::Error)?;
+            crate::operations::crypto_kem_enc::<DefaultParameterSet, _>(
                 &mut ciphertext_buf,
                 shared_secret_buf,
                 recip_pubkey.0.as_ref(),
                 csprng,
-            );
+            )
+            .map_err(|_| kem::Error)?;
             Ok((
                 Ciphertext(ciphertext_buf),
                 SharedSecret::<Ciphertext>::new(shared_secret),
@@ -510,7 +827,7 @@ mod kem_api {
                 .try_into()
                 .expect("GenericArray should be CRYPTO_BYTES long");
 
-            crate::operations::crypto_kem_dec(
+            crate::operations::crypto_kem_dec::<DefaultParameterSet>(
                 shared_secret_buf,
                 ciphertext.as_array(),
                 self.as_array(),
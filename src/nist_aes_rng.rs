@@ -1,5 +1,3 @@
-#![cfg(test)]
-
 //! Implementation of a pseudo-random number generator
 //! based on AES256 in CTR mode.
 //!
@@ -8,28 +6,89 @@
 
 use aes::cipher::BlockEncrypt;
 use aes::cipher::KeyInit;
-use rand::CryptoRng;
-use rand::RngCore;
-use std::fmt;
-
-/// AesState is a struct storing data of a pseudo-random number generator.
-/// Using `randombytes_init`, it can be initialized once. Using the `RngCore` interface,
-/// one can successively fetch new pseudo-random numbers.
-#[derive(Clone, Debug, PartialEq)]
-pub struct AesState {
-    pub key: [u8; 32],
-    pub v: [u8; 16],
-    pub reseed_counter: i32,
+use rand::{CryptoRng, RngCore, SeedableRng};
+
+/// The deterministic random bit generator based on AES-256 in counter mode used by NIST to
+/// generate the known answer tests (KATs) of the post-quantum competition, equivalent to
+/// `randombytes_init` and `randombytes` in `rng.c` of the submission packages.
+///
+/// **This generator is only meant for testing**, for example to reproduce the official
+/// `.rsp` files. It is not reseeded, its state is not zeroized and its output is fully
+/// determined by the 48 bytes seed. Never use it to generate real keys.
+///
+/// ```
+/// use classic_mceliece_rust::NistDrbg;
+/// use rand::RngCore;
+///
+/// let mut entropy_input = [0u8; 48];
+/// for (i, e) in entropy_input.iter_mut().enumerate() {
+///     *e = i as u8;
+/// }
+/// let mut rng = NistDrbg::new(entropy_input);
+/// let mut seed = [0u8; 48];
+/// rng.fill_bytes(&mut seed);
+/// // the seed of the first test vector of the .rsp files
+/// assert_eq!(seed[..4], [0x06, 0x15, 0x50, 0x23]);
+/// ```
+#[derive(Clone, Debug)]
+#[cfg_attr(docsrs, doc(cfg(feature = "nist-drbg")))]
+pub struct NistDrbg {
+    key: [u8; 32],
+    v: [u8; 16],
+    reseed_counter: i32,
+}
+
+/// The 48 bytes seed of [`NistDrbg`] for [`SeedableRng`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "nist-drbg")))]
+pub struct NistDrbgSeed(pub [u8; 48]);
+
+impl Default for NistDrbgSeed {
+    fn default() -> Self {
+        Self([0u8; 48])
+    }
+}
+
+impl AsRef<[u8]> for NistDrbgSeed {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsMut<[u8]> for NistDrbgSeed {
+    fn as_mut(&mut self) -> &mut [u8] {
+        &mut self.0
+    }
 }
 
-impl AesState {
-    /// Returns a fresh RNG state
-    pub fn new() -> AesState {
-        AesState {
+impl From<[u8; 48]> for NistDrbgSeed {
+    fn from(seed: [u8; 48]) -> Self {
+        Self(seed)
+    }
+}
+
+impl NistDrbg {
+    /// Returns a generator seeded with `entropy_input`, like `randombytes_init` with an
+    /// empty personalization string.
+    pub fn new(entropy_input: [u8; 48]) -> NistDrbg {
+        let mut rng = NistDrbg {
             key: [0; 32],
             v: [0; 16],
             reseed_counter: 0,
-        }
+        };
+        rng.randombytes_init(entropy_input, None);
+        rng
+    }
+
+    /// Returns a generator seeded with `entropy_input` and `personalization_string`, like
+    /// `randombytes_init`.
+    pub fn with_personalization(
+        entropy_input: [u8; 48],
+        personalization_string: &[u8; 48],
+    ) -> NistDrbg {
+        let mut rng = NistDrbg::new([0u8; 48]);
+        rng.randombytes_init(entropy_input, Some(personalization_string));
+        rng
     }
 
     /// This runs AES256 in ECB mode. Here `key` is a 256-bit AES key,
@@ -70,41 +129,46 @@ impl AesState {
     }
 
     /// Initialize/reset the state based on the seed provided as `entropy_input`
-    pub fn randombytes_init(&mut self, entropy_input: [u8; 48]) {
+    /// and the optional `personalization_string`
+    pub fn randombytes_init(
+        &mut self,
+        entropy_input: [u8; 48],
+        personalization_string: Option<&[u8; 48]>,
+    ) {
+        let mut seed_material = entropy_input;
+        if let Some(personalization_string) = personalization_string {
+            for (s, p) in seed_material.iter_mut().zip(personalization_string) {
+                *s ^= p;
+            }
+        }
+
         self.key = [0u8; 32];
         self.v = [0u8; 16];
-        self.reseed_counter = 1i32;
 
-        Self::aes256_ctr_update(&mut Some(entropy_input), &mut self.key, &mut self.v);
+        Self::aes256_ctr_update(&mut Some(seed_material), &mut self.key, &mut self.v);
         self.reseed_counter = 1;
     }
 }
 
-impl Default for AesState {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-impl Eq for AesState {}
+impl SeedableRng for NistDrbg {
+    type Seed = NistDrbgSeed;
 
-impl fmt::Display for AesState {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "AesState {{")?;
-        writeln!(f, "  key = {:?}", self.key)?;
-        writeln!(f, "  v   = {:?}", self.v)?;
-        writeln!(f, "  reseed_counter = {}", self.reseed_counter)?;
-        writeln!(f, "}}")
+    fn from_seed(seed: NistDrbgSeed) -> Self {
+        NistDrbg::new(seed.0)
     }
 }
 
-impl RngCore for AesState {
+impl RngCore for NistDrbg {
     fn next_u32(&mut self) -> u32 {
-        unimplemented!()
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
     }
 
     fn next_u64(&mut self) -> u64 {
-        unimplemented!()
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
     }
 
     fn fill_bytes(&mut self, dest: &mut [u8]) {
@@ -127,7 +191,8 @@ impl RngCore for AesState {
         Ok(())
     }
 }
-impl CryptoRng for AesState {}
+
+impl CryptoRng for NistDrbg {}
 
 #[cfg(test)]
 mod tests {
@@ -141,15 +206,12 @@ mod tests {
     fn test_rng_rand_interface() {
         let mut data = [0u8; 256];
         let mut entropy_input = [0u8; 48];
-        let mut personalization_string = [0u8; 48];
-        let mut rng_state = AesState::new();
 
-        for i in 0..48 {
-            entropy_input[i] = i as u8;
-            personalization_string[i] = 0;
+        for (i, e) in entropy_input.iter_mut().enumerate() {
+            *e = i as u8;
         }
 
-        rng_state.randombytes_init(entropy_input);
+        let mut rng_state = NistDrbg::new(entropy_input);
 
         rng_state.fill_bytes(&mut data);
         let ref1_src = hex::decode(RNG_REF1).unwrap();
@@ -161,4 +223,26 @@ mod tests {
         let ref2 = <[u8; 256]>::try_from(ref2_src).unwrap();
         assert_eq!(data, ref2);
     }
+
+    #[test]
+    fn test_rng_personalization() {
+        let entropy_input = [0x55u8; 48];
+        let mut personalization_string = [0u8; 48];
+        personalization_string[0] = 0xFF;
+
+        // the personalization string is XORed into the seed material
+        let mut seed_material = entropy_input;
+        seed_material[0] ^= 0xFF;
+
+        let mut a = NistDrbg::with_personalization(entropy_input, &personalization_string);
+        let mut b = NistDrbg::from_seed(seed_material.into());
+        let mut c = NistDrbg::new(entropy_input);
+        assert_eq!(a.next_u64(), b.next_u64());
+        assert_ne!(a.next_u64(), c.next_u64());
+
+        // an all-zero personalization string changes nothing
+        let mut a = NistDrbg::with_personalization(entropy_input, &[0u8; 48]);
+        let mut c = NistDrbg::new(entropy_input);
+        assert_eq!(a.next_u64(), c.next_u64());
+    }
 }
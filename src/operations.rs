@@ -1,39 +1,37 @@
 //! KEM API
 
 use crate::controlbits::controlbitsfrompermutation;
-#[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-use crate::params::{PK_NCOLS, PK_NROWS, PK_ROW_BYTES};
 use crate::{
-    api::{CRYPTO_BYTES, CRYPTO_CIPHERTEXTBYTES, CRYPTO_PUBLICKEYBYTES, CRYPTO_SECRETKEYBYTES},
-    crypto_hash::shake256,
-    decrypt::decrypt,
+    api::CRYPTO_BYTES,
+    crypto_hash::{shake256, ShakeRng},
+    decrypt::{decode, decrypt},
     encrypt::encrypt,
     macros::sub,
-    params::{COND_BYTES, GFBITS, IRR_BYTES, SYND_BYTES, SYS_N, SYS_T},
-    pk_gen::pk_gen,
+    params::{
+        ParameterSet, MAX_GFBITS, MAX_SECRET_KEY_BYTES, MAX_SYND_BYTES, MAX_SYS_N, MAX_SYS_T,
+    },
+    pk_gen::{pk_gen, PkMatrix},
     sk_gen::genpoly_gen,
     util::{load_gf, store_gf},
 };
 use rand::{CryptoRng, RngCore};
 
 /// This function determines (in a constant-time manner) whether the padding bits of `pk` are all zero.
-#[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-fn check_pk_padding(pk: &[u8; PK_NROWS * PK_ROW_BYTES]) -> u8 {
+pub(crate) fn check_pk_padding<P: ParameterSet>(pk: &[u8]) -> u8 {
     let mut b = 0u8;
-    for i in 0..PK_NROWS {
-        b |= pk[i * PK_ROW_BYTES + PK_ROW_BYTES - 1];
+    for i in 0..P::PK_NROWS {
+        b |= pk[i * P::PK_ROW_BYTES + P::PK_ROW_BYTES - 1];
     }
 
-    b >>= PK_NCOLS % 8;
+    b >>= P::PK_NCOLS % 8;
     b = b.wrapping_sub(1);
     b >>= 7;
     b.wrapping_sub(1)
 }
 
 /// This function determines (in a constant-time manner) whether the padding bits of `c` are all zero.
-#[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-fn check_c_padding(c: &[u8; SYND_BYTES]) -> u8 {
-    let mut b = c[SYND_BYTES - 1] >> (PK_NROWS % 8);
+fn check_c_padding<P: ParameterSet>(c: &[u8]) -> u8 {
+    let mut b = c[P::SYND_BYTES - 1] >> (P::PK_NROWS % 8);
     b = b.wrapping_sub(1);
     b >>= 7;
     b.wrapping_sub(1)
@@ -44,127 +42,150 @@ fn check_c_padding(c: &[u8; SYND_BYTES]) -> u8 {
 /// Given a public key `pk`, sample a shared key.
 /// This shared key is returned through parameter `key` whereas
 /// the ciphertext (meant to be used for decapsulation) is returned as `c`.
-#[cfg(not(any(feature = "mceliece6960119", feature = "mceliece6960119f")))]
-pub(crate) fn crypto_kem_enc<R: CryptoRng + RngCore>(
-    c: &mut [u8; CRYPTO_CIPHERTEXTBYTES],
-    key: &mut [u8; CRYPTO_BYTES],
-    pk: &[u8; CRYPTO_PUBLICKEYBYTES],
-    rng: &mut R,
-) {
-    let mut e = [0u8; SYS_N / 8];
-
-    let mut one_ec = [0u8; 1 + SYS_N / 8 + SYND_BYTES];
-    one_ec[0] = 1;
-
-    encrypt(c, pk, sub!(mut e, 0, SYS_N / 8), rng);
-
-    one_ec[1..1 + SYS_N / 8].copy_from_slice(&e[..SYS_N / 8]);
-    one_ec[1 + SYS_N / 8..1 + SYS_N / 8 + SYND_BYTES].copy_from_slice(&c[0..SYND_BYTES]);
-
-    shake256(&mut key[0..32], &one_ec);
-}
-
-/// KEM Encapsulation.
 ///
-/// Given a public key `pk`, sample a shared key.
-/// This shared key is returned through parameter `key` whereas
-/// the ciphertext (meant to be used for decapsulation) is returned as `c`.
-#[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-pub(crate) fn crypto_kem_enc<R: CryptoRng + RngCore>(
-    c: &mut [u8; CRYPTO_CIPHERTEXTBYTES],
+/// Only mceliece6960119 has padding bits in the public key. If they are not all zero,
+/// the outputs are cleared and a non-zero value is returned. Otherwise 0 is returned.
+/// If generation of pseudo-random numbers fails, an error is returned and the outputs
+/// are set to all 0's.
+pub(crate) fn crypto_kem_enc<P: ParameterSet, R: CryptoRng + RngCore>(
+    c: &mut [u8],
     key: &mut [u8; CRYPTO_BYTES],
-    pk: &[u8; CRYPTO_PUBLICKEYBYTES],
+    pk: &[u8],
     rng: &mut R,
-) -> u8 {
-    let mut e = [0u8; SYS_N / 8];
+) -> Result<u8, rand::Error> {
+    debug_assert_eq!(c.len(), P::CIPHERTEXT_BYTES);
+    debug_assert_eq!(pk.len(), P::PUBLIC_KEY_BYTES);
+
+    let mut e_buf = [0u8; MAX_SYS_N / 8];
+    let e = &mut e_buf[..P::SYS_N / 8];
 
-    let mut one_ec = [0u8; 1 + SYS_N / 8 + SYND_BYTES];
+    let mut one_ec_buf = [0u8; 1 + MAX_SYS_N / 8 + MAX_SYND_BYTES];
+    let one_ec = &mut one_ec_buf[..1 + P::SYS_N / 8 + P::SYND_BYTES];
     one_ec[0] = 1;
 
-    let padding_ok = check_pk_padding(pk);
+    let padding_ok = if P::PK_NROWS % 8 != 0 {
+        check_pk_padding::<P>(pk)
+    } else {
+        0
+    };
 
-    encrypt(c, pk, sub!(mut e, 0, SYS_N / 8), rng);
+    if let Err(error) = encrypt::<P, R>(c, pk, e, rng) {
+        c.fill(0);
+        key.fill(0);
+        return Err(error);
+    }
 
-    one_ec[1..1 + (SYS_N / 8)].copy_from_slice(&e[..SYS_N / 8]);
-    one_ec[1 + (SYS_N / 8)..1 + (SYS_N / 8) + SYND_BYTES].copy_from_slice(&c[0..SYND_BYTES]);
+    one_ec[1..1 + (P::SYS_N / 8)].copy_from_slice(e);
+    one_ec[1 + (P::SYS_N / 8)..1 + (P::SYS_N / 8) + P::SYND_BYTES]
+        .copy_from_slice(&c[0..P::SYND_BYTES]);
 
-    shake256(&mut key[0..32], &one_ec);
+    shake256(&mut key[0..32], one_ec);
 
-    // clear outputs (set to all 0's) if padding bits are not all zero
+    if P::PK_NROWS % 8 != 0 {
+        // clear outputs (set to all 0's) if padding bits are not all zero
 
-    let mask = padding_ok ^ 0xFF;
+        let mask = padding_ok ^ 0xFF;
 
-    for i in 0..SYND_BYTES {
-        c[i] &= mask;
+        for itr_c in c[..P::SYND_BYTES].iter_mut() {
+            *itr_c &= mask;
+        }
+
+        for itr_key in key.iter_mut() {
+            *itr_key &= mask;
+        }
     }
 
-    for i in 0..32 {
-        key[i] &= mask;
+    #[cfg(feature = "zeroize")]
+    {
+        use zeroize::Zeroize;
+        e_buf.zeroize();
+        one_ec_buf.zeroize();
     }
 
-    padding_ok
+    Ok(padding_ok)
+}
+
+/// The random number generator for derandomized encapsulation: the error vector is
+/// sampled from the output of SHAKE256(3 || `coins`), just like [`crypto_kem_enc`]
+/// samples it from `randombytes`.
+pub(crate) fn coins_rng(coins: &[u8; 32]) -> ShakeRng {
+    ShakeRng::new(3, coins)
 }
 
 /// KEM Decapsulation.
 ///
 /// Given a secret key `sk` and a ciphertext `c`,
 /// determine the shared text `key` negotiated by both parties.
-#[cfg(not(any(feature = "mceliece6960119", feature = "mceliece6960119f")))]
-pub(crate) fn crypto_kem_dec(
+///
+/// Only mceliece6960119 has padding bits in the ciphertext. If they are not all zero,
+/// the shared key is set to all 1's and a non-zero value is returned. Otherwise 0 is returned.
+pub(crate) fn crypto_kem_dec<P: ParameterSet>(
     key: &mut [u8; CRYPTO_BYTES],
-    c: &[u8; CRYPTO_CIPHERTEXTBYTES],
-    sk: &[u8; CRYPTO_SECRETKEYBYTES],
+    c: &[u8],
+    sk: &[u8],
 ) -> u8 {
-    let mut e = [0u8; SYS_N / 8];
+    debug_assert_eq!(c.len(), P::CIPHERTEXT_BYTES);
+    debug_assert_eq!(sk.len(), P::SECRET_KEY_BYTES);
 
-    let mut preimage = [0u8; 1 + SYS_N / 8 + SYND_BYTES];
+    let mut e_buf = [0u8; MAX_SYS_N / 8];
+    let e = &mut e_buf[..P::SYS_N / 8];
 
-    let ret_decrypt: u8 = decrypt(
-        sub!(mut e, 0, SYS_N / 8),
-        sub!(sk, 40, IRR_BYTES + COND_BYTES),
-        sub!(c, 0, SYND_BYTES),
+    let ret_decrypt: u8 = decrypt::<P>(
+        e,
+        &sk[40..40 + P::IRR_BYTES + P::COND_BYTES],
+        &c[..P::SYND_BYTES],
     );
 
-    let mut m = ret_decrypt as u16;
-    m = m.wrapping_sub(1);
-    m >>= 8;
-
-    preimage[0] = (m & 1) as u8;
-
-    let s = &sk[40 + IRR_BYTES + COND_BYTES..];
+    derive_dec_key::<P>(
+        key,
+        c,
+        e,
+        ret_decrypt,
+        &sk[40 + P::IRR_BYTES + P::COND_BYTES..],
+    )
+}
 
-    for i in 0..SYS_N / 8 {
-        preimage[1 + i] = (!m as u8 & s[i]) | (m as u8 & e[i]);
-    }
+/// Like [`crypto_kem_dec`], but with the parts of the secret key already expanded: the
+/// weights `1 / g(a)^2` of the Goppa polynomial, the control bits `cond` and the
+/// implicit rejection value `s`.
+pub(crate) fn crypto_kem_dec_precomputed<P: ParameterSet>(
+    key: &mut [u8; CRYPTO_BYTES],
+    c: &[u8],
+    weights: &[u16],
+    cond: &[u8],
+    s: &[u8],
+) -> u8 {
+    debug_assert_eq!(c.len(), P::CIPHERTEXT_BYTES);
 
-    (&mut preimage[1 + (SYS_N / 8)..])[0..SYND_BYTES].copy_from_slice(&c[0..SYND_BYTES]);
+    let mut e_buf = [0u8; MAX_SYS_N / 8];
+    let e = &mut e_buf[..P::SYS_N / 8];
 
-    shake256(&mut key[0..32], &preimage);
+    let ret_decrypt: u8 = decode::<P>(e, weights, cond, &c[..P::SYND_BYTES]);
 
-    0
+    derive_dec_key::<P>(key, c, e, ret_decrypt, s)
 }
 
-/// KEM Decapsulation.
-///
-/// Given a secret key `sk` and a ciphertext `c`,
-/// determine the shared text `key` negotiated by both parties.
-#[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-pub(crate) fn crypto_kem_dec(
+/// Derive the shared `key` of decapsulation from the error vector `e` of ciphertext `c`,
+/// or from the implicit rejection value `s` if decryption failed (`ret_decrypt` is 1).
+/// Also checks the padding of `c` as described at [`crypto_kem_dec`].
+fn derive_dec_key<P: ParameterSet>(
     key: &mut [u8; CRYPTO_BYTES],
-    c: &[u8; CRYPTO_CIPHERTEXTBYTES],
-    sk: &[u8; CRYPTO_SECRETKEYBYTES],
+    c: &[u8],
+    e: &[u8],
+    ret_decrypt: u8,
+    s: &[u8],
 ) -> u8 {
-    let mut e = [0u8; SYS_N / 8];
+    debug_assert_eq!(e.len(), P::SYS_N / 8);
+    debug_assert_eq!(s.len(), P::SYS_N / 8);
 
-    let mut preimage = [0u8; 1 + SYS_N / 8 + SYND_BYTES];
+    let mut preimage_buf = [0u8; 1 + MAX_SYS_N / 8 + MAX_SYND_BYTES];
+    let preimage = &mut preimage_buf[..1 + P::SYS_N / 8 + P::SYND_BYTES];
 
-    let padding_ok = check_c_padding(sub!(c, 0, SYND_BYTES));
-
-    let ret_decrypt: u8 = decrypt(
-        sub!(mut e, 0, SYS_N / 8),
-        sub!(sk, 40, IRR_BYTES + COND_BYTES),
-        sub!(c, 0, SYND_BYTES),
-    );
+    let padding_ok = if P::PK_NROWS % 8 != 0 {
+        check_c_padding::<P>(&c[..P::SYND_BYTES])
+    } else {
+        0
+    };
 
     let mut m = ret_decrypt as u16;
     m = m.wrapping_sub(1);
@@ -172,22 +193,22 @@ pub(crate) fn crypto_kem_dec(
 
     preimage[0] = (m & 1) as u8;
 
-    let s = &sk[40 + IRR_BYTES + COND_BYTES..];
-
-    for i in 0..SYS_N / 8 {
+    for i in 0..P::SYS_N / 8 {
         preimage[1 + i] = (!m as u8 & s[i]) | (m as u8 & e[i]);
     }
 
-    (&mut preimage[1 + (SYS_N / 8)..])[0..SYND_BYTES].copy_from_slice(&c[0..SYND_BYTES]);
+    (&mut preimage[1 + (P::SYS_N / 8)..])[0..P::SYND_BYTES].copy_from_slice(&c[0..P::SYND_BYTES]);
 
-    shake256(&mut key[0..32], &preimage);
+    shake256(&mut key[0..32], preimage);
 
-    // clear outputs (set to all 1's) if padding bits are not all zero
+    if P::PK_NROWS % 8 != 0 {
+        // clear outputs (set to all 1's) if padding bits are not all zero
 
-    let mask = padding_ok;
+        let mask = padding_ok;
 
-    for i in 0..32 {
-        key[i] |= mask;
+        for itr_key in key.iter_mut() {
+            *itr_key |= mask;
+        }
     }
 
     padding_ok
@@ -199,49 +220,79 @@ pub(crate) fn crypto_kem_dec(
 /// The public key is meant to be shared with any party,
 /// but access to the secret key must be limited to the generating party.
 ///
+/// The keys are derived from 32 bytes drawn from `rng`, see [`crypto_kem_keypair_from_seed`].
+/// If generation of pseudo-random numbers fails, an error is returned and both keys
+/// are set to all 0's. `mat` is scratch space.
+pub(crate) fn crypto_kem_keypair<P: ParameterSet, R: CryptoRng + RngCore>(
+    pk: &mut [u8],
+    sk: &mut [u8],
+    rng: &mut R,
+    mat: &mut PkMatrix<P>,
+) -> Result<(), rand::Error> {
+    let mut seed = [0u8; 32];
+    let result = rng.try_fill_bytes(&mut seed);
+
+    match result {
+        Ok(()) => crypto_kem_keypair_from_seed::<P>(Some(pk), sk, &seed, mat),
+        Err(_) => {
+            pk.fill(0);
+            sk.fill(0);
+        }
+    }
+
+    #[cfg(feature = "zeroize")]
+    {
+        use zeroize::Zeroize;
+        seed.zeroize();
+    }
+
+    result
+}
+
+/// Deterministic KEM Keypair generation.
+///
+/// Generate the public and secret key derived from the 32 bytes `initial_seed`. This
+/// does not consume any randomness, the same seed always results in the same keys.
+/// If `pk` is `None`, only the secret key is generated. `mat` is scratch space.
+///
 /// The structure of the secret key is given by the following segments:
 /// (32 bytes seed, 8 bytes pivots, IRR_BYTES bytes, COND_BYTES bytes, SYS_N/8 bytes).
 /// The structure of the public key is simple: a matrix of PK_NROWS times PK_ROW_BYTES bytes.
-pub(crate) fn crypto_kem_keypair<R: CryptoRng + RngCore>(
-    pk: &mut [u8; CRYPTO_PUBLICKEYBYTES],
-    sk: &mut [u8; CRYPTO_SECRETKEYBYTES],
-    rng: &mut R,
+pub(crate) fn crypto_kem_keypair_from_seed<P: ParameterSet>(
+    mut pk: Option<&mut [u8]>,
+    sk: &mut [u8],
+    initial_seed: &[u8; 32],
+    mat: &mut PkMatrix<P>,
 ) {
+    debug_assert!(pk.as_ref().is_none_or(|pk| pk.len() == P::PUBLIC_KEY_BYTES));
+    debug_assert_eq!(sk.len(), P::SECRET_KEY_BYTES);
+
     let mut seed = [0u8; 33];
     seed[0] = 64;
 
-    const S_BASE: usize = 32 + 8 + IRR_BYTES + COND_BYTES;
+    let s_base: usize = 32 + 8 + P::IRR_BYTES + P::COND_BYTES;
 
-    const SEED: usize = SYS_N / 8 + (1 << GFBITS) * 4 + SYS_T * 2;
-    const IRR_POLYS: usize = SYS_N / 8 + (1 << GFBITS) * 4;
-    const PERM: usize = SYS_N / 8;
+    let seed_offset: usize = P::SYS_N / 8 + (1 << P::GFBITS) * 4 + P::SYS_T * 2;
+    let irr_polys: usize = P::SYS_N / 8 + (1 << P::GFBITS) * 4;
+    let perm_offset: usize = P::SYS_N / 8;
 
-    let mut r = [0u8; SEED + 32];
+    const MAX_R_BYTES: usize = MAX_SYS_N / 8 + (1 << MAX_GFBITS) * 4 + MAX_SYS_T * 2 + 32;
+    let mut r_buf = [0u8; MAX_R_BYTES];
+    let r = &mut r_buf[..seed_offset + 32];
 
-    #[cfg(any(
-        feature = "mceliece348864f",
-        feature = "mceliece460896f",
-        feature = "mceliece6688128f",
-        feature = "mceliece6960119f",
-        feature = "mceliece8192128f"
-    ))]
     let mut pivots = 0u64;
-    #[cfg(any(
-        feature = "mceliece348864",
-        feature = "mceliece460896",
-        feature = "mceliece6688128",
-        feature = "mceliece6960119",
-        feature = "mceliece8192128"
-    ))]
-    let pivots: u64;
 
-    let mut f = [0u16; SYS_T];
-    let mut irr = [0u16; SYS_T];
+    let mut f_buf = [0u16; MAX_SYS_T];
+    let f = &mut f_buf[..P::SYS_T];
+    let mut irr_buf = [0u16; MAX_SYS_T];
+    let irr = &mut irr_buf[..P::SYS_T];
 
-    let mut perm = [0u32; 1 << GFBITS];
-    let mut pi = [0i16; 1 << GFBITS];
+    let mut perm_buf = [0u32; 1 << MAX_GFBITS];
+    let perm = &mut perm_buf[..1 << P::GFBITS];
+    let mut pi_buf = [0i16; 1 << MAX_GFBITS];
+    let pi = &mut pi_buf[..1 << P::GFBITS];
 
-    rng.fill_bytes(&mut seed[1..]);
+    seed[1..].copy_from_slice(initial_seed);
 
     loop {
         // expanding and updating the seed
@@ -252,77 +303,50 @@ pub(crate) fn crypto_kem_keypair<R: CryptoRng + RngCore>(
 
         // generating irreducible polynomial
 
-        for (i, chunk) in r[IRR_POLYS..SEED].chunks(2).enumerate() {
-            f[i] = load_gf(sub!(chunk, 0, 2));
+        for (i, chunk) in r[irr_polys..seed_offset].chunks(2).enumerate() {
+            f[i] = load_gf::<P>(sub!(chunk, 0, 2));
         }
 
-        if genpoly_gen(&mut irr, &f) != 0 {
+        if genpoly_gen::<P>(irr, f) != 0 {
             continue;
         }
 
-        for (i, chunk) in sk[40..40 + IRR_BYTES].chunks_mut(2).enumerate() {
+        for (i, chunk) in sk[40..40 + P::IRR_BYTES].chunks_mut(2).enumerate() {
             store_gf(sub!(mut chunk, 0, 2), irr[i]);
         }
 
         // generating permutation
 
-        for (i, chunk) in r[PERM..IRR_POLYS].chunks(4).enumerate() {
+        for (i, chunk) in r[perm_offset..irr_polys].chunks(4).enumerate() {
             perm[i] = u32::from_le_bytes(*sub!(chunk, 0, 4));
         }
 
-        #[cfg(any(
-            feature = "mceliece348864f",
-            feature = "mceliece460896f",
-            feature = "mceliece6688128f",
-            feature = "mceliece6960119f",
-            feature = "mceliece8192128f"
-        ))]
+        if pk_gen::<P>(
+            pk.as_deref_mut(),
+            &sk[40..40 + P::IRR_BYTES],
+            perm,
+            pi,
+            &mut pivots,
+            mat,
+        ) != 0
         {
-            if pk_gen(
-                pk,
-                sub!(mut sk, 40, IRR_BYTES),
-                &mut perm,
-                &mut pi,
-                &mut pivots,
-            ) != 0
-            {
-                continue;
-            }
-        }
-        #[cfg(any(
-            feature = "mceliece348864",
-            feature = "mceliece460896",
-            feature = "mceliece6688128",
-            feature = "mceliece6960119",
-            feature = "mceliece8192128"
-        ))]
-        {
-            if pk_gen(pk, sub!(mut sk, 40, IRR_BYTES), &perm, &mut pi) != 0 {
-                continue;
-            }
+            continue;
         }
 
         controlbitsfrompermutation(
-            &mut sk[(40 + IRR_BYTES)..(40 + IRR_BYTES + COND_BYTES)],
-            &pi,
-            GFBITS,
-            1 << GFBITS,
+            &mut sk[(40 + P::IRR_BYTES)..(40 + P::IRR_BYTES + P::COND_BYTES)],
+            pi,
+            P::GFBITS,
+            1 << P::GFBITS,
         );
 
         // storing the random string s
 
-        sk[S_BASE..(S_BASE + SYS_N / 8)].clone_from_slice(&r[0..SYS_N / 8]);
+        sk[s_base..(s_base + P::SYS_N / 8)].clone_from_slice(&r[0..P::SYS_N / 8]);
 
         // storing positions of the 32 pivots
 
-        #[cfg(any(
-            feature = "mceliece348864",
-            feature = "mceliece460896",
-            feature = "mceliece6688128",
-            feature = "mceliece6960119",
-            feature = "mceliece8192128"
-        ))]
-        {
+        if !P::SEMI_SYSTEMATIC {
             pivots = 0xFFFFFFFF;
         }
 
@@ -332,26 +356,59 @@ pub(crate) fn crypto_kem_keypair<R: CryptoRng + RngCore>(
     }
 }
 
-#[cfg(all(test, feature = "mceliece8192128f"))]
+/// Public key regeneration.
+///
+/// Expands the seed stored in the secret key `sk` once more and writes the resulting public
+/// key to `pk`. Returns 0 if the expansion reproduces `sk`, otherwise `sk` was not generated
+/// from its seed, `pk` is meaningless and a non-zero value is returned.
+pub(crate) fn crypto_kem_pk_from_sk<P: ParameterSet>(pk: &mut [u8], sk: &[u8]) -> u8 {
+    debug_assert_eq!(pk.len(), P::PUBLIC_KEY_BYTES);
+    debug_assert_eq!(sk.len(), P::SECRET_KEY_BYTES);
+
+    let mut seed = [0u8; 32];
+    seed.copy_from_slice(&sk[..32]);
+
+    let mut expanded_sk_buf = [0u8; MAX_SECRET_KEY_BYTES];
+    let expanded_sk = &mut expanded_sk_buf[..P::SECRET_KEY_BYTES];
+
+    crypto_kem_keypair_from_seed::<P>(Some(pk), expanded_sk, &seed, &mut PkMatrix::new());
+
+    let diff = expanded_sk
+        .iter()
+        .zip(sk)
+        .fold(0u8, |acc, (a, b)| acc | (a ^ b));
+
+    #[cfg(feature = "zeroize")]
+    {
+        use zeroize::Zeroize;
+        seed.zeroize();
+        expanded_sk_buf.zeroize();
+    }
+
+    diff
+}
+
+#[cfg(test)]
 mod tests {
     use super::*;
-    use crate::nist_aes_rng::AesState;
+    use crate::nist_aes_rng::NistDrbg;
+    use crate::params::Mceliece8192128f;
     use crate::test_utils::TestData;
     use std::convert::TryFrom;
 
     #[test]
     fn test_crypto_kem_dec() {
-        use crate::api::{CRYPTO_CIPHERTEXTBYTES, CRYPTO_SECRETKEYBYTES};
+        type P = Mceliece8192128f;
 
-        let mut sk = TestData::new().u8vec("mceliece8192128f_sk1");
-        let mut c = TestData::new().u8vec("mceliece8192128f_ct1");
+        let sk = TestData::new().u8vec("mceliece8192128f_sk1");
+        let c = TestData::new().u8vec("mceliece8192128f_ct1");
         let mut test_key = [0u8; 32];
         let compare_key = TestData::new().u8vec("mceliece8192128f_operations_ss");
 
-        crypto_kem_dec(
+        crypto_kem_dec::<P>(
             &mut test_key,
-            sub!(mut c, 0, CRYPTO_CIPHERTEXTBYTES),
-            sub!(mut sk, 0, CRYPTO_SECRETKEYBYTES),
+            &c[..P::CIPHERTEXT_BYTES],
+            &sk[..P::SECRET_KEY_BYTES],
         );
 
         assert_eq!(test_key, compare_key.as_slice());
@@ -359,12 +416,12 @@ mod tests {
 
     #[test]
     fn test_crypto_kem_enc() {
-        use crate::api::{CRYPTO_BYTES, CRYPTO_CIPHERTEXTBYTES, CRYPTO_PUBLICKEYBYTES};
+        type P = Mceliece8192128f;
 
-        let mut c = [0u8; CRYPTO_CIPHERTEXTBYTES];
+        let mut c = [0u8; P::CIPHERTEXT_BYTES];
         let mut ss = [0u8; CRYPTO_BYTES];
-        let mut pk = TestData::new().u8vec("mceliece8192128f_pk1");
-        assert_eq!(pk.len(), CRYPTO_PUBLICKEYBYTES);
+        let pk = TestData::new().u8vec("mceliece8192128f_pk1");
+        assert_eq!(pk.len(), P::PUBLIC_KEY_BYTES);
 
         let compare_ss = TestData::new().u8vec("mceliece8192128f_operations_ss");
         let compare_ct = TestData::new().u8vec("mceliece8192128f_operations_enc1_ct");
@@ -377,20 +434,14 @@ mod tests {
         )
         .unwrap();
 
-        let mut rng_state = AesState::new();
-        rng_state.randombytes_init(entropy_input);
+        let mut rng_state = NistDrbg::new(entropy_input);
 
         let mut second_seed = [0u8; 33];
         second_seed[0] = 64;
 
         rng_state.fill_bytes(&mut second_seed[1..]);
 
-        crypto_kem_enc(
-            &mut c,
-            &mut ss,
-            sub!(mut pk, 0, CRYPTO_PUBLICKEYBYTES),
-            &mut rng_state,
-        );
+        crypto_kem_enc::<P, _>(&mut c, &mut ss, &pk, &mut rng_state).unwrap();
 
         assert_eq!(ss, compare_ss.as_slice());
 
@@ -399,7 +450,9 @@ mod tests {
 
     #[test]
     fn test_crypto_kem_keypair() {
-        use crate::api::{CRYPTO_PUBLICKEYBYTES, CRYPTO_SECRETKEYBYTES};
+        type P = Mceliece8192128f;
+        const CRYPTO_PUBLICKEYBYTES: usize = P::PUBLIC_KEY_BYTES;
+        const CRYPTO_SECRETKEYBYTES: usize = P::SECRET_KEY_BYTES;
 
         let mut pk_input = [0; CRYPTO_PUBLICKEYBYTES].to_vec();
         let mut sk_input = [0; CRYPTO_SECRETKEYBYTES].to_vec();
@@ -417,16 +470,75 @@ mod tests {
         let compare_pk = TestData::new().u8vec("mceliece8192128f_operations_pk_expected");
         assert_eq!(compare_pk.len(), CRYPTO_PUBLICKEYBYTES);
 
-        let mut rng_state = AesState::new();
-        rng_state.randombytes_init(entropy_input);
+        let mut rng_state = NistDrbg::new(entropy_input);
 
-        crypto_kem_keypair(
-            sub!(mut pk_input, 0, CRYPTO_PUBLICKEYBYTES),
-            sub!(mut sk_input, 0, CRYPTO_SECRETKEYBYTES),
+        crypto_kem_keypair::<P, _>(
+            &mut pk_input,
+            &mut sk_input,
             &mut rng_state,
-        );
+            &mut PkMatrix::new(),
+        )
+        .unwrap();
 
         assert_eq!(compare_sk, sk_input);
         assert_eq!(compare_pk, pk_input);
     }
 }
+
+#[cfg(all(test, feature = "alloc"))]
+mod seed_tests {
+    use super::*;
+    use crate::nist_aes_rng::NistDrbg;
+    use crate::params::Mceliece348864f;
+    use std::vec;
+
+    #[test]
+    fn test_crypto_kem_keypair_from_seed_matches_rng() {
+        type P = Mceliece348864f;
+
+        let mut pk_rng = vec![0u8; P::PUBLIC_KEY_BYTES];
+        let mut sk_rng = vec![0u8; P::SECRET_KEY_BYTES];
+        let mut rng_state = NistDrbg::new([7u8; 48]);
+        crypto_kem_keypair::<P, _>(
+            &mut pk_rng,
+            &mut sk_rng,
+            &mut rng_state,
+            &mut PkMatrix::new(),
+        )
+        .unwrap();
+
+        let mut seed = [0u8; 32];
+        let mut rng_state = NistDrbg::new([7u8; 48]);
+        rng_state.fill_bytes(&mut seed);
+
+        let mut pk_seed = vec![0u8; P::PUBLIC_KEY_BYTES];
+        let mut sk_seed = vec![0u8; P::SECRET_KEY_BYTES];
+        crypto_kem_keypair_from_seed::<P>(
+            Some(&mut pk_seed),
+            &mut sk_seed,
+            &seed,
+            &mut PkMatrix::new(),
+        );
+
+        assert_eq!(sk_rng, sk_seed);
+        assert_eq!(pk_rng, pk_seed);
+    }
+
+    #[test]
+    fn test_crypto_kem_pk_from_sk() {
+        type P = Mceliece348864f;
+
+        let mut pk = vec![0u8; P::PUBLIC_KEY_BYTES];
+        let mut sk = vec![0u8; P::SECRET_KEY_BYTES];
+        let mut rng_state = NistDrbg::new([3u8; 48]);
+        crypto_kem_keypair::<P, _>(&mut pk, &mut sk, &mut rng_state, &mut PkMatrix::new()).unwrap();
+
+        let mut derived_pk = vec![0u8; P::PUBLIC_KEY_BYTES];
+        assert_eq!(crypto_kem_pk_from_sk::<P>(&mut derived_pk, &sk), 0);
+        assert_eq!(pk, derived_pk);
+
+        // the control bits no longer match the seed
+        sk[40 + P::IRR_BYTES] ^= 1;
+        assert_ne!(crypto_kem_pk_from_sk::<P>(&mut derived_pk, &sk), 0);
+    }
+}
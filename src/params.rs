@@ -1,44 +1,247 @@
 //! Global paramaters for the different Classic McEliece variants
 
-#[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-pub const GFBITS: usize = 12;
-#[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-pub const SYS_N: usize = 3488;
-#[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-pub const SYS_T: usize = 64;
-
-#[cfg(any(feature = "mceliece460896", feature = "mceliece460896f"))]
-pub const GFBITS: usize = 13;
-#[cfg(any(feature = "mceliece460896", feature = "mceliece460896f"))]
-pub const SYS_N: usize = 4608;
-#[cfg(any(feature = "mceliece460896", feature = "mceliece460896f"))]
-pub const SYS_T: usize = 96;
-
-#[cfg(any(feature = "mceliece6688128", feature = "mceliece6688128f"))]
-pub const GFBITS: usize = 13;
-#[cfg(any(feature = "mceliece6688128", feature = "mceliece6688128f"))]
-pub const SYS_N: usize = 6688;
-#[cfg(any(feature = "mceliece6688128", feature = "mceliece6688128f"))]
-pub const SYS_T: usize = 128;
-
-#[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-pub const GFBITS: usize = 13;
-#[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-pub const SYS_N: usize = 6960;
-#[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-pub const SYS_T: usize = 119;
-
-#[cfg(any(feature = "mceliece8192128", feature = "mceliece8192128f"))]
-pub const GFBITS: usize = 13;
-#[cfg(any(feature = "mceliece8192128", feature = "mceliece8192128f"))]
-pub const SYS_N: usize = 8192;
-#[cfg(any(feature = "mceliece8192128", feature = "mceliece8192128f"))]
-pub const SYS_T: usize = 128;
-
-pub const COND_BYTES: usize = (1 << (GFBITS - 4)) * (2 * GFBITS - 1);
-pub const IRR_BYTES: usize = SYS_T * 2;
-pub const PK_NROWS: usize = SYS_T * GFBITS;
-pub const PK_NCOLS: usize = SYS_N - PK_NROWS;
-pub const PK_ROW_BYTES: usize = PK_NCOLS.div_ceil(8);
-pub const SYND_BYTES: usize = PK_NROWS.div_ceil(8);
-pub const GFMASK: usize = (1 << GFBITS) - 1;
+use crate::variant::Variant;
+use core::fmt::Debug;
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
+mod private {
+    #[cfg(feature = "alloc")]
+    use alloc::boxed::Box;
+
+    pub trait Sealed {}
+
+    /// A fixed-size array of words `[u64; N]` used as scratch space by key generation
+    pub trait WordArray: AsRef<[u64]> + AsMut<[u64]> + Send + Sync + 'static {
+        /// Returns an array filled with zeros
+        fn zeroed() -> Self;
+
+        /// Returns a heap allocated array filled with zeros, without putting it on the
+        /// stack first
+        #[cfg(feature = "alloc")]
+        fn zeroed_boxed() -> Box<Self>;
+
+        /// Like [`WordArray::zeroed_boxed`], but returns `None` if the allocation fails
+        #[cfg(feature = "alloc")]
+        fn try_zeroed_boxed() -> Option<Box<Self>>;
+    }
+
+    impl<const N: usize> WordArray for [u64; N] {
+        fn zeroed() -> Self {
+            [0u64; N]
+        }
+
+        #[cfg(feature = "alloc")]
+        fn zeroed_boxed() -> Box<Self> {
+            crate::util::alloc_boxed_array::<u64, N>()
+        }
+
+        #[cfg(feature = "alloc")]
+        fn try_zeroed_boxed() -> Option<Box<Self>> {
+            crate::util::try_alloc_boxed_array::<u64, N>()
+        }
+    }
+}
+
+pub(crate) use private::WordArray;
+
+/// A Classic McEliece parameter set.
+///
+/// This trait is implemented by the marker types [`Mceliece348864`], [`Mceliece348864f`], …,
+/// [`Mceliece8192128f`]. The KEM operations are generic over it, hence one binary can use
+/// several parameter sets at once. The trait is sealed and cannot be implemented outside
+/// of this crate.
+pub trait ParameterSet:
+    private::Sealed + Copy + Clone + Debug + Default + PartialEq + Eq + Send + Sync + 'static
+{
+    /// The runtime tag of this parameter set
+    const VARIANT: Variant;
+    /// Name of the variant
+    const NAME: &'static str;
+    /// `m`, the extension degree of the finite field `GF(2^m)`
+    const GFBITS: usize;
+    /// `n`, the code length
+    const SYS_N: usize;
+    /// `t`, the guaranteed error-correction capability
+    const SYS_T: usize;
+    /// Whether key generation uses the semi-systematic form (the `f` variants)
+    const SEMI_SYSTEMATIC: bool;
+
+    /// `[u8; PUBLIC_KEY_BYTES]`, the storage of a [`PublicKey`](crate::PublicKey)
+    type PublicKeyArray: ByteArray;
+    /// `[u8; SECRET_KEY_BYTES]`, the storage of a [`SecretKey`](crate::SecretKey)
+    type SecretKeyArray: ByteArray;
+    /// `[u8; CIPHERTEXT_BYTES]`, the storage of a [`Ciphertext`](crate::Ciphertext)
+    type CiphertextArray: ByteArray;
+    /// `[u64; PK_NROWS * SYS_N.div_ceil(64)]`, the matrix reduced during key generation
+    #[doc(hidden)]
+    type PkMatrixArray: private::WordArray;
+
+    /// The number of bytes required to store the Beneš network control bits
+    const COND_BYTES: usize = (1 << (Self::GFBITS - 4)) * (2 * Self::GFBITS - 1);
+    /// The number of bytes required to store the Goppa polynomial
+    const IRR_BYTES: usize = Self::SYS_T * 2;
+    /// The number of rows of the public key matrix
+    const PK_NROWS: usize = Self::SYS_T * Self::GFBITS;
+    /// The number of columns of the public key matrix
+    const PK_NCOLS: usize = Self::SYS_N - Self::PK_NROWS;
+    /// The number of bytes required to store one row of the public key matrix
+    const PK_ROW_BYTES: usize = Self::PK_NCOLS.div_ceil(8);
+    /// The number of bytes required to store a syndrome
+    const SYND_BYTES: usize = Self::PK_NROWS.div_ceil(8);
+    /// Bitmask to reduce a `u16` to a field element
+    const GFMASK: usize = (1 << Self::GFBITS) - 1;
+
+    /// The number of bytes required to store the public key
+    const PUBLIC_KEY_BYTES: usize = Self::PK_NROWS * Self::PK_ROW_BYTES;
+    /// The number of bytes required to store the secret key
+    const SECRET_KEY_BYTES: usize = 32 + 8 + Self::IRR_BYTES + Self::COND_BYTES + Self::SYS_N / 8;
+    /// The number of bytes required to store the ciphertext resulting from the encryption
+    const CIPHERTEXT_BYTES: usize = Self::SYND_BYTES;
+}
+
+/// A fixed-size byte array `[u8; N]`.
+///
+/// Stable Rust cannot use an associated constant as array length in generic code, hence every
+/// [`ParameterSet`] names its key and ciphertext arrays as associated types bounded by this
+/// trait. The trait is sealed and only implemented for `[u8; N]`.
+pub trait ByteArray:
+    private::Sealed + AsRef<[u8]> + AsMut<[u8]> + Debug + PartialEq + Eq + Send + Sync + 'static
+{
+    /// The length of the array
+    const LEN: usize;
+
+    /// Returns an array filled with zeros
+    fn zeroed() -> Self;
+
+    /// Returns a heap allocated array filled with zeros. The array is not put on the stack
+    /// first, so this is safe to use for large public keys.
+    #[cfg(feature = "alloc")]
+    fn zeroed_boxed() -> Box<Self>;
+
+    /// Like [`ByteArray::zeroed_boxed`], but returns `None` if the allocation fails.
+    #[cfg(feature = "alloc")]
+    fn try_zeroed_boxed() -> Option<Box<Self>>;
+}
+
+impl<const N: usize> private::Sealed for [u8; N] {}
+
+impl<const N: usize> ByteArray for [u8; N] {
+    const LEN: usize = N;
+
+    fn zeroed() -> Self {
+        [0u8; N]
+    }
+
+    #[cfg(feature = "alloc")]
+    fn zeroed_boxed() -> Box<Self> {
+        crate::util::alloc_boxed_array::<u8, N>()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn try_zeroed_boxed() -> Option<Box<Self>> {
+        crate::util::try_alloc_boxed_array::<u8, N>()
+    }
+}
+
+/// Upper bounds of the parameters over all parameter sets. Generic code uses them to size
+/// scratch buffers on the stack and only works on the leading part required by the parameter set.
+pub(crate) const MAX_GFBITS: usize = 13;
+pub(crate) const MAX_SYS_N: usize = 8192;
+pub(crate) const MAX_SYS_T: usize = 128;
+pub(crate) const MAX_PK_NROWS: usize = MAX_SYS_T * MAX_GFBITS;
+pub(crate) const MAX_SYND_BYTES: usize = MAX_PK_NROWS.div_ceil(8);
+pub(crate) const MAX_COND_BYTES: usize = (1 << (MAX_GFBITS - 4)) * (2 * MAX_GFBITS - 1);
+pub(crate) const MAX_SECRET_KEY_BYTES: usize =
+    32 + 8 + MAX_SYS_T * 2 + MAX_COND_BYTES + MAX_SYS_N / 8;
+
+macro_rules! parameter_set {
+    ($(#[$meta:meta])* $name:ident, $variant:ident, $primitive:literal,
+     gfbits = $gfbits:literal, n = $n:literal, t = $t:literal, semi_systematic = $f:literal) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+        pub struct $name;
+
+        impl private::Sealed for $name {}
+
+        impl ParameterSet for $name {
+            const VARIANT: Variant = Variant::$variant;
+            const NAME: &'static str = $primitive;
+            const GFBITS: usize = $gfbits;
+            const SYS_N: usize = $n;
+            const SYS_T: usize = $t;
+            const SEMI_SYSTEMATIC: bool = $f;
+
+            type PublicKeyArray = [u8; <$name as ParameterSet>::PUBLIC_KEY_BYTES];
+            type SecretKeyArray = [u8; <$name as ParameterSet>::SECRET_KEY_BYTES];
+            type CiphertextArray = [u8; <$name as ParameterSet>::CIPHERTEXT_BYTES];
+            type PkMatrixArray = [u64; <$name as ParameterSet>::PK_NROWS
+                * <$name as ParameterSet>::SYS_N.div_ceil(64)];
+        }
+
+        impl From<[u8; <$name as ParameterSet>::CIPHERTEXT_BYTES]> for crate::Ciphertext<$name> {
+            fn from(data: [u8; <$name as ParameterSet>::CIPHERTEXT_BYTES]) -> Self {
+                Self(data)
+            }
+        }
+
+        const _: () = assert!(
+            <$name as ParameterSet>::GFBITS <= MAX_GFBITS
+                && <$name as ParameterSet>::SYS_N <= MAX_SYS_N
+                && <$name as ParameterSet>::SYS_T <= MAX_SYS_T
+                && <$name as ParameterSet>::SECRET_KEY_BYTES <= MAX_SECRET_KEY_BYTES
+        );
+    };
+}
+
+parameter_set!(
+    /// Parameter set `mceliece348864`
+    Mceliece348864, Mceliece348864, "mceliece348864",
+    gfbits = 12, n = 3488, t = 64, semi_systematic = false
+);
+parameter_set!(
+    /// Parameter set `mceliece348864f`
+    Mceliece348864f, Mceliece348864f, "mceliece348864f",
+    gfbits = 12, n = 3488, t = 64, semi_systematic = true
+);
+parameter_set!(
+    /// Parameter set `mceliece460896`
+    Mceliece460896, Mceliece460896, "mceliece460896",
+    gfbits = 13, n = 4608, t = 96, semi_systematic = false
+);
+parameter_set!(
+    /// Parameter set `mceliece460896f`
+    Mceliece460896f, Mceliece460896f, "mceliece460896f",
+    gfbits = 13, n = 4608, t = 96, semi_systematic = true
+);
+parameter_set!(
+    /// Parameter set `mceliece6688128`
+    Mceliece6688128, Mceliece6688128, "mceliece6688128",
+    gfbits = 13, n = 6688, t = 128, semi_systematic = false
+);
+parameter_set!(
+    /// Parameter set `mceliece6688128f`
+    Mceliece6688128f, Mceliece6688128f, "mceliece6688128f",
+    gfbits = 13, n = 6688, t = 128, semi_systematic = true
+);
+parameter_set!(
+    /// Parameter set `mceliece6960119`
+    Mceliece6960119, Mceliece6960119, "mceliece6960119",
+    gfbits = 13, n = 6960, t = 119, semi_systematic = false
+);
+parameter_set!(
+    /// Parameter set `mceliece6960119f`
+    Mceliece6960119f, Mceliece6960119f, "mceliece6960119f",
+    gfbits = 13, n = 6960, t = 119, semi_systematic = true
+);
+parameter_set!(
+    /// Parameter set `mceliece8192128`
+    Mceliece8192128, Mceliece8192128, "mceliece8192128",
+    gfbits = 13, n = 8192, t = 128, semi_systematic = false
+);
+parameter_set!(
+    /// Parameter set `mceliece8192128f`
+    Mceliece8192128f, Mceliece8192128f, "mceliece8192128f",
+    gfbits = 13, n = 8192, t = 128, semi_systematic = true
+);
@@ -1,22 +1,22 @@
 //! Generation of public key
 
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+
 use crate::{
+    error::Error,
     gf::{gf_inv, gf_mul},
     macros::sub,
-    params::{GFBITS, GFMASK, PK_NROWS, PK_ROW_BYTES, SYS_N, SYS_T},
+    params::{ParameterSet, WordArray, MAX_GFBITS, MAX_PK_NROWS, MAX_SYS_N, MAX_SYS_T},
     root::root,
+    transpose,
     uint64_sort::uint64_sort,
     util::{bitrev, load_gf},
 };
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
 /// Return number of trailing zeros of the non-zero input `input`
-#[cfg(any(
-    feature = "mceliece348864f",
-    feature = "mceliece460896f",
-    feature = "mceliece6688128f",
-    feature = "mceliece6960119f",
-    feature = "mceliece8192128f"
-))]
 fn ctz(input: u64) -> i32 {
     let (mut m, mut r) = (0i32, 0i32);
 
@@ -30,13 +30,6 @@ fn ctz(input: u64) -> i32 {
 }
 
 /// Takes two 16-bit integers and determines whether they are equal (u64::MAX) or different (0)
-#[cfg(any(
-    feature = "mceliece348864f",
-    feature = "mceliece460896f",
-    feature = "mceliece6688128f",
-    feature = "mceliece6960119f",
-    feature = "mceliece8192128f"
-))]
 fn same_mask(x: u16, y: u16) -> u64 {
     let mut mask = (x ^ y) as u64;
     mask = mask.wrapping_sub(1);
@@ -46,45 +39,104 @@ fn same_mask(x: u16, y: u16) -> u64 {
     mask
 }
 
-/// Move columns in matrix `mat`
-#[cfg(any(
-    feature = "mceliece348864f",
-    feature = "mceliece460896f",
-    feature = "mceliece6688128f",
-    feature = "mceliece6960119f",
-    feature = "mceliece8192128f"
-))]
-fn mov_columns(
-    mat: &mut [[u8; SYS_N / 8]; PK_NROWS],
-    pi: &mut [i16; 1 << GFBITS],
-    pivots: &mut u64,
-) -> i32 {
-    let mut buf = [0u64; 64];
-    let mut ctz_list = [0u64; 32];
+/// The matrix reduced by [`pk_gen`]. It has `PK_NROWS` rows of `SYS_N.div_ceil(64)` words
+/// each, see [`row_words`]. Column `c` of a row is bit `c % 64` of its word `c / 64`, and the
+/// columns from `SYS_N` on are always zero.
+///
+/// With the `alloc` feature the matrix is on the heap, otherwise on the stack. It is filled
+/// anew by each call of [`pk_gen`], so key generation allocates it once for all attempts.
+pub(crate) struct PkMatrix<P: ParameterSet> {
+    #[cfg(feature = "alloc")]
+    words: Box<P::PkMatrixArray>,
+    #[cfg(not(feature = "alloc"))]
+    words: P::PkMatrixArray,
+}
 
-    let row = PK_NROWS - 32;
-    let block_idx = row / 8;
+impl<P: ParameterSet> PkMatrix<P> {
+    /// Returns a zeroed matrix. Aborts if it can not be allocated, like `Box::new`.
+    pub(crate) fn new() -> Self {
+        Self {
+            #[cfg(feature = "alloc")]
+            words: P::PkMatrixArray::zeroed_boxed(),
+            #[cfg(not(feature = "alloc"))]
+            words: P::PkMatrixArray::zeroed(),
+        }
+    }
 
-    #[cfg(feature = "mceliece6960119f")]
-    let tail = row % 8;
-    #[cfg(feature = "mceliece6960119f")]
-    let mut tmp = [0u8; 9];
+    /// Like [`PkMatrix::new`], but returns [`Error::Allocation`] if the matrix can not be
+    /// allocated
+    pub(crate) fn try_new() -> Result<Self, Error> {
+        Ok(Self {
+            #[cfg(feature = "alloc")]
+            words: P::PkMatrixArray::try_zeroed_boxed().ok_or(Error::Allocation)?,
+            #[cfg(not(feature = "alloc"))]
+            words: P::PkMatrixArray::zeroed(),
+        })
+    }
 
-    #[cfg(not(feature = "mceliece6960119f"))]
-    for i in 0..32 {
-        buf[i] = u64::from_le_bytes(*sub!(mat[row + i], block_idx, 8));
+    fn as_mut(&mut self) -> &mut [u64] {
+        let words: &mut P::PkMatrixArray = &mut self.words;
+        words.as_mut()
     }
+}
 
-    #[cfg(feature = "mceliece6960119f")]
-    for i in 0..32 {
-        for j in 0..9 {
-            tmp[j] = mat[row + i][block_idx + j];
-        }
-        for j in 0..8 {
-            tmp[j] = (tmp[j] >> tail) | (tmp[j + 1] << (8 - tail));
+impl<P: ParameterSet> Drop for PkMatrix<P> {
+    fn drop(&mut self) {
+        // the matrix is derived from the Goppa polynomial and the support
+        #[cfg(feature = "zeroize")]
+        {
+            use zeroize::Zeroize;
+            self.as_mut().zeroize();
         }
+    }
+}
+
+/// The number of words of a row of [`PkMatrix`]
+fn row_words<P: ParameterSet>() -> usize {
+    P::SYS_N.div_ceil(64)
+}
+
+/// Return the 64 columns of `row` starting at column `col`. Columns past the end of the row
+/// are zero.
+fn load_bits(row: &[u64], col: usize) -> u64 {
+    let (i, s) = (col / 64, col % 64);
+
+    if s == 0 {
+        row[i]
+    } else {
+        let high = row.get(i + 1).map_or(0, |&word| word << (64 - s));
+        (row[i] >> s) | high
+    }
+}
 
-        buf[i] = u64::from_le_bytes(*sub!(tmp, 0, 8));
+/// Overwrite the 64 columns of `row` starting at column `col` with `t`
+fn store_bits(row: &mut [u64], col: usize, t: u64) {
+    let (i, s) = (col / 64, col % 64);
+
+    if s == 0 {
+        row[i] = t;
+    } else {
+        row[i] = (row[i] & (u64::MAX >> (64 - s))) | (t << s);
+        row[i + 1] = (row[i + 1] & (u64::MAX << s)) | (t >> (64 - s));
+    }
+}
+
+/// Move columns in matrix `mat`
+#[allow(clippy::needless_range_loop)]
+fn mov_columns<P: ParameterSet>(mat: &mut [u64], pi: &mut [i16], pivots: &mut u64) -> i32 {
+    debug_assert_eq!(mat.len(), P::PK_NROWS * row_words::<P>());
+    debug_assert_eq!(pi.len(), 1 << P::GFBITS);
+
+    let mut buf = [0u64; 64];
+    let mut ctz_list = [0u64; 32];
+
+    let row = P::PK_NROWS - 32;
+
+    for (itr_buf, mat_row) in buf
+        .iter_mut()
+        .zip(mat.chunks_exact(row_words::<P>()).skip(row))
+    {
+        *itr_buf = load_bits(mat_row, row);
     }
 
     // Compute the column indices of pivots by Gaussian elimination.
@@ -130,9 +182,8 @@ fn mov_columns(
     }
 
     // moving columns of mat according to the column indices of pivots
-    #[cfg(not(feature = "mceliece6960119f"))]
-    for i in 0..PK_NROWS {
-        let mut t = u64::from_le_bytes(*sub!(mat[i], block_idx, 8));
+    for mat_row in mat.chunks_exact_mut(row_words::<P>()) {
+        let mut t = load_bits(mat_row, row);
 
         for j in 0..32 {
             let mut d: u64 = t >> j;
@@ -143,230 +194,283 @@ fn mov_columns(
             t ^= d << j;
         }
 
-        *sub!(mut mat[i], block_idx, 8) = t.to_le_bytes();
+        store_bits(mat_row, row, t);
+    }
+
+    0
+}
+
+/// Compute the columns of the parity-check matrix for up to 64 support elements `l`.
+/// Bit `c` of `out[i * GFBITS + k]` is bit `k` of `l[c]^i / g(l[c])`.
+fn fill_columns<P: ParameterSet>(out: &mut [u64], g: &[u16], l: &[u16]) {
+    debug_assert_eq!(out.len(), P::PK_NROWS);
+    debug_assert!(l.len() <= 64);
+
+    let mut inv_buf = [0u16; 64];
+    let inv = &mut inv_buf[..l.len()];
+
+    root::<P>(inv, g, l);
+
+    for itr_inv in inv.iter_mut() {
+        *itr_inv = gf_inv::<P>(*itr_inv);
     }
 
-    #[cfg(feature = "mceliece6960119f")]
-    for i in 0..PK_NROWS {
-        for k in 0..9 {
-            tmp[k] = mat[i][block_idx + k];
+    for rows in out.chunks_mut(P::GFBITS) {
+        let mut bits = [0u64; 64];
+        for (itr_bits, &itr_inv) in bits.iter_mut().zip(inv.iter()) {
+            *itr_bits = itr_inv as u64;
         }
-        for k in 0..8 {
-            tmp[k] = (tmp[k] >> tail) | (tmp[k + 1] << (8 - tail));
+        transpose::transpose_64x64_inplace(&mut bits);
+        rows.copy_from_slice(&bits[..P::GFBITS]);
+
+        for (itr_inv, &a) in inv.iter_mut().zip(l) {
+            *itr_inv = gf_mul::<P>(*itr_inv, a);
+        }
+    }
+}
+
+/// Fill `mat` with the parity-check matrix of the Goppa code with polynomial `g` and support `l`
+///
+/// With the `parallel` feature the columns are computed in parallel into a buffer of the size
+/// of `mat`. If that buffer can not be allocated, they are computed one chunk after another.
+fn fill_mat<P: ParameterSet>(mat: &mut [u64], g: &[u16], l: &[u16]) {
+    #[cfg(feature = "parallel")]
+    if let Some(mut columns) = crate::util::try_alloc_vec::<u64>(row_words::<P>() * P::PK_NROWS) {
+        columns
+            .par_chunks_mut(P::PK_NROWS)
+            .zip(l.par_chunks(64))
+            .for_each(|(columns, chunk)| fill_columns::<P>(columns, g, chunk));
+
+        for (c, columns) in columns.chunks(P::PK_NROWS).enumerate() {
+            for (mat_row, &t) in mat.chunks_exact_mut(row_words::<P>()).zip(columns) {
+                mat_row[c] = t;
+            }
         }
 
-        let mut t = u64::from_le_bytes(*sub!(tmp, 0, 8));
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut columns[..]);
 
-        for j in 0..32 {
-            let mut d = t >> j;
-            d ^= t >> ctz_list[j];
-            d &= 1;
+        return;
+    }
 
-            t ^= d << ctz_list[j];
-            t ^= d << j;
-        }
+    let mut columns_buf = [0u64; MAX_PK_NROWS];
+    let columns = &mut columns_buf[..P::PK_NROWS];
 
-        *sub!(mut tmp, 0, 8) = t.to_le_bytes();
+    for (c, chunk) in l.chunks(64).enumerate() {
+        fill_columns::<P>(columns, g, chunk);
+        for (mat_row, &t) in mat.chunks_exact_mut(row_words::<P>()).zip(columns.iter()) {
+            mat_row[c] = t;
+        }
+    }
 
-        mat[i][block_idx + 8] = (mat[i][block_idx + 8] >> tail << tail) | (tmp[7] >> (8 - tail));
-        mat[i][block_idx + 0] = (tmp[0] << tail) | (mat[i][block_idx] << (8 - tail) >> (8 - tail));
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(&mut columns_buf);
+}
 
-        for k in (1..=7).rev() {
-            mat[i][block_idx + k] = (tmp[k] << tail) | (tmp[k - 1] >> (8 - tail));
+/// Number of matrix rows processed by a single task in the row reduction
+#[cfg(feature = "parallel")]
+const ROWS_PER_TASK: usize = 64;
+
+/// Add the rows below `row` to it where necessary to set its bit in the pivot column `row`
+///
+/// The columns left of the pivot column are already reduced and zero in all rows involved,
+/// so the row operations start at the word `i` of the pivot column.
+#[cfg(not(feature = "parallel"))]
+fn add_to_pivot<P: ParameterSet>(mat: &mut [u64], row: usize) {
+    let (i, j) = (row / 64, row % 64);
+    let words = row_words::<P>();
+
+    let (upper, lower) = mat.split_at_mut((row + 1) * words);
+    let pivot_row = &mut upper[row * words..];
+
+    for other in lower.chunks_exact(words) {
+        let mut mask = pivot_row[i] ^ other[i];
+        mask >>= j;
+        mask &= 1;
+        mask = 0u64.wrapping_sub(mask);
+
+        for c in i..words {
+            pivot_row[c] ^= other[c] & mask;
         }
     }
+}
 
-    0
+/// Add the rows below `row` to it where necessary to set its bit in the pivot column `row`
+///
+/// Whether a row is added only depends on the word `i` of the pivot column. So the masks
+/// are determined first and the selected rows are then summed up in parallel.
+#[cfg(feature = "parallel")]
+fn add_to_pivot<P: ParameterSet>(mat: &mut [u64], row: usize) {
+    let (i, j) = (row / 64, row % 64);
+    let words = row_words::<P>();
+
+    let (upper, lower) = mat.split_at_mut((row + 1) * words);
+    let pivot_row = &mut upper[row * words..];
+
+    let mut masks_buf = [0u64; MAX_PK_NROWS];
+    let masks = &mut masks_buf[..lower.len() / words];
+    let mut t = pivot_row[i];
+    for (mask, other) in masks.iter_mut().zip(lower.chunks_exact(words)) {
+        *mask = t ^ other[i];
+        *mask >>= j;
+        *mask &= 1;
+        *mask = 0u64.wrapping_sub(*mask);
+
+        t ^= other[i] & *mask;
+    }
+
+    let sum = lower
+        .par_chunks(ROWS_PER_TASK * words)
+        .zip(masks.par_chunks(ROWS_PER_TASK))
+        .map(|(others, masks)| {
+            let mut sum = [0u64; MAX_SYS_N / 64];
+            for (other, &mask) in others.chunks_exact(words).zip(masks) {
+                for (itr_sum, &t) in sum[i..words].iter_mut().zip(&other[i..words]) {
+                    *itr_sum ^= t & mask;
+                }
+            }
+            sum
+        })
+        .reduce(
+            || [0u64; MAX_SYS_N / 64],
+            |mut sum, other| {
+                for (itr_sum, &t) in sum[i..words].iter_mut().zip(&other[i..words]) {
+                    *itr_sum ^= t;
+                }
+                sum
+            },
+        );
+
+    for (itr_pivot, &t) in pivot_row[i..words].iter_mut().zip(&sum[i..words]) {
+        *itr_pivot ^= t;
+    }
+
+    #[cfg(feature = "zeroize")]
+    zeroize::Zeroize::zeroize(&mut masks_buf);
+}
+
+/// Clear the pivot column `row` in all other rows by adding the pivot row to them
+fn eliminate<P: ParameterSet>(mat: &mut [u64], row: usize) {
+    let (i, j) = (row / 64, row % 64);
+    let words = row_words::<P>();
+    let mut pivot_row = [0u64; MAX_SYS_N / 64];
+    pivot_row[..words].copy_from_slice(&mat[row * words..(row + 1) * words]);
+
+    let clear = |(k, mat_k): (usize, &mut [u64])| {
+        if k == row {
+            return;
+        }
+
+        let mut mask = mat_k[i] >> j;
+        mask &= 1;
+        mask = 0u64.wrapping_sub(mask);
+
+        for (itr_mat, &t) in mat_k[i..words].iter_mut().zip(&pivot_row[i..words]) {
+            *itr_mat ^= t & mask;
+        }
+    };
+
+    #[cfg(feature = "parallel")]
+    mat.par_chunks_exact_mut(words)
+        .enumerate()
+        .with_min_len(ROWS_PER_TASK)
+        .for_each(clear);
+    #[cfg(not(feature = "parallel"))]
+    mat.chunks_exact_mut(words).enumerate().for_each(clear);
 }
 
 /// Public key generation. Generate the public key `pk`,
 /// permutation `pi` and pivot element `pivots` based on the
-/// secret key `sk` and permutation `perm` provided.
+/// secret key `sk` and permutation `perm` provided. `mat` is scratch space.
+/// If `pk` is `None`, only `pi` and `pivots` are computed. The return value
+/// does not depend on it.
 /// `pk` has `max(1 << GFBITS, SYS_N)` elements which is
 /// 4096 for mceliece348864 and 8192 for mceliece8192128.
 /// `sk` has `2 * SYS_T` elements and perm `1 << GFBITS`.
-pub(crate) fn pk_gen(
-    pk: &mut [u8; PK_NROWS * PK_ROW_BYTES],
-    sk: &[u8; 2 * SYS_T],
-    perm: &[u32; 1 << GFBITS],
-    pi: &mut [i16; 1 << GFBITS],
-    #[cfg(any(
-        feature = "mceliece348864f",
-        feature = "mceliece460896f",
-        feature = "mceliece6688128f",
-        feature = "mceliece6960119f",
-        feature = "mceliece8192128f"
-    ))]
+/// `pivots` is only updated by the semi-systematic parameter sets (the `f` variants).
+#[allow(clippy::needless_range_loop)]
+pub(crate) fn pk_gen<P: ParameterSet>(
+    pk: Option<&mut [u8]>,
+    sk: &[u8],
+    perm: &[u32],
+    pi: &mut [i16],
     pivots: &mut u64,
+    mat: &mut PkMatrix<P>,
 ) -> i32 {
-    let mut buf = [0u64; 1 << GFBITS];
-    let mut mat;
-    #[cfg(feature = "alloc")]
-    {
-        use alloc::boxed::Box;
-        mat = Box::new([[0u8; SYS_N / 8]; PK_NROWS]);
-    }
-    #[cfg(not(feature = "alloc"))]
-    {
-        mat = [[0u8; SYS_N / 8]; PK_NROWS];
+    debug_assert!(pk.as_ref().is_none_or(|pk| pk.len() == P::PUBLIC_KEY_BYTES));
+    debug_assert_eq!(sk.len(), 2 * P::SYS_T);
+    debug_assert_eq!(perm.len(), 1 << P::GFBITS);
+    debug_assert_eq!(pi.len(), 1 << P::GFBITS);
+
+    // enter the thread pool once instead of in each parallel step of the row reduction
+    #[cfg(feature = "parallel")]
+    if rayon::current_thread_index().is_none() {
+        return rayon::scope(|_| pk_gen::<P>(pk, sk, perm, pi, pivots, mat));
     }
 
-    let mut g = [0u16; SYS_T + 1];
-    let mut l = [0u16; SYS_N];
-    let mut inv = [0u16; SYS_N];
+    let mut buf_arr = [0u64; 1 << MAX_GFBITS];
+    let buf = &mut buf_arr[..1 << P::GFBITS];
+    let mat = mat.as_mut();
+    let words = row_words::<P>();
 
-    g[SYS_T] = 1;
-    for (i, chunk) in sk.chunks(2).take(SYS_T).enumerate() {
-        g[i] = load_gf(sub!(chunk, 0, 2));
+    let mut g_buf = [0u16; MAX_SYS_T + 1];
+    let g = &mut g_buf[..=P::SYS_T];
+    let mut l_buf = [0u16; MAX_SYS_N];
+    let l = &mut l_buf[..P::SYS_N];
+
+    g[P::SYS_T] = 1;
+    for (i, chunk) in sk.chunks(2).take(P::SYS_T).enumerate() {
+        g[i] = load_gf::<P>(sub!(chunk, 0, 2));
     }
 
-    for i in 0..(1 << GFBITS) {
+    for i in 0..(1 << P::GFBITS) {
         buf[i] = perm[i] as u64;
         buf[i] <<= 31;
         buf[i] |= i as u64;
     }
 
-    uint64_sort(sub!(mut buf, 0, 1 << GFBITS, u64));
+    uint64_sort(buf);
 
-    for i in 1..(1 << GFBITS) {
+    for i in 1..(1 << P::GFBITS) {
         if buf[i - 1] >> 31 == buf[i] >> 31 {
             return -1;
         }
     }
 
-    for i in 0..(1 << GFBITS) {
-        pi[i] = buf[i] as i16 & GFMASK as i16;
+    for i in 0..(1 << P::GFBITS) {
+        pi[i] = buf[i] as i16 & P::GFMASK as i16;
     }
 
-    for i in 0..SYS_N {
-        l[i] = bitrev(pi[i] as u16);
+    for i in 0..P::SYS_N {
+        l[i] = bitrev::<P>(pi[i] as u16);
     }
 
-    root(&mut inv, &g, &l);
-
-    for itr_inv in inv.iter_mut() {
-        *itr_inv = gf_inv(*itr_inv);
-    }
+    fill_mat::<P>(mat, g, l);
 
-    for i in 0..SYS_T {
-        for j in (0..SYS_N).step_by(8) {
-            for k in 0..GFBITS {
-                let mut b = ((inv[j + 7] >> k) & 1) as u8;
-                b <<= 1;
-                b |= ((inv[j + 6] >> k) & 1) as u8;
-                b <<= 1;
-                b |= ((inv[j + 5] >> k) & 1) as u8;
-                b <<= 1;
-                b |= ((inv[j + 4] >> k) & 1) as u8;
-                b <<= 1;
-                b |= ((inv[j + 3] >> k) & 1) as u8;
-                b <<= 1;
-                b |= ((inv[j + 2] >> k) & 1) as u8;
-                b <<= 1;
-                b |= ((inv[j + 1] >> k) & 1) as u8;
-                b <<= 1;
-                b |= ((inv[j] >> k) & 1) as u8;
-
-                mat[i * GFBITS + k][j / 8] = b;
-            }
-        }
-        for j in 0..SYS_N {
-            inv[j] = gf_mul(inv[j], l[j]);
-        }
-    }
     // gaussian elimination
-    const ROWS: usize = PK_NROWS.div_ceil(8);
-
-    for i in 0..ROWS {
-        for j in 0..8 {
-            // NOTE: this loop is much very slow without optimization.
-            //       test_pk_gen_2 takes 126s, but 4s with opt-level=1.
-            let row = i * 8 + j;
-
-            if row >= PK_NROWS {
-                break;
-            }
-
-            #[cfg(any(
-                feature = "mceliece348864f",
-                feature = "mceliece460896f",
-                feature = "mceliece6688128f",
-                feature = "mceliece6960119f",
-                feature = "mceliece8192128f"
-            ))]
-            {
-                if row == PK_NROWS - 32 {
-                    if mov_columns(&mut mat, pi, pivots) != 0 {
-                        #[cfg(feature = "alloc")]
-                        {
-                            use zeroize::Zeroize;
-                            mat.zeroize();
-                        }
-                        return -1;
-                    }
-                }
-            }
+    for row in 0..P::PK_NROWS {
+        let (i, j) = (row / 64, row % 64);
 
-            for k in (row + 1)..PK_NROWS {
-                let mut mask = mat[row][i] ^ mat[k][i];
-                mask >>= j;
-                mask &= 1;
-                mask = 0u8.wrapping_sub(mask);
-
-                for c in 0..SYS_N / 8 {
-                    mat[row][c] ^= mat[k][c] & mask;
-                }
-            }
-
-            if ((mat[row][i] >> j) & 1) == 0 {
-                #[cfg(feature = "alloc")]
-                {
-                    use zeroize::Zeroize;
-                    mat.zeroize();
-                }
-                return -1;
-            }
-
-            for k in 0..PK_NROWS {
-                if k == row {
-                    continue;
-                }
-
-                let mut mask = mat[k][i] >> j;
-                mask &= 1;
-                mask = 0u8.wrapping_sub(mask);
-
-                for c in 0..(SYS_N / 8) {
-                    mat[k][c] ^= mat[row][c] & mask;
-                }
-            }
+        if P::SEMI_SYSTEMATIC && row == P::PK_NROWS - 32 && mov_columns::<P>(mat, pi, pivots) != 0 {
+            return -1;
         }
-    }
 
-    #[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-    let tail = PK_NROWS % 8;
-    #[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-    const INNER_PK_ACCESSES: usize = ((SYS_N / 8 - 1) - (PK_NROWS - 1) / 8) + 1;
+        add_to_pivot::<P>(mat, row);
 
-    for i in 0..PK_NROWS {
-        #[cfg(not(any(feature = "mceliece6960119", feature = "mceliece6960119f")))]
-        pk[i * PK_ROW_BYTES..(i + 1) * PK_ROW_BYTES]
-            .copy_from_slice(&mat[i][PK_NROWS / 8..PK_NROWS / 8 + PK_ROW_BYTES]);
-
-        #[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-        for (idx, j) in ((PK_NROWS - 1) / 8..SYS_N / 8 - 1).enumerate() {
-            pk[i * INNER_PK_ACCESSES + idx] = (mat[i][j] >> tail) | (mat[i][j + 1] << (8 - tail));
-        }
-        #[cfg(any(feature = "mceliece6960119", feature = "mceliece6960119f"))]
-        {
-            pk[(i + 1) * INNER_PK_ACCESSES - 1] = mat[i][SYS_N / 8 - 1] >> tail;
+        if ((mat[row * words + i] >> j) & 1) == 0 {
+            return -1;
         }
+
+        eliminate::<P>(mat, row);
     }
 
-    #[cfg(feature = "alloc")]
-    {
-        use zeroize::Zeroize;
-        mat.zeroize();
+    if let Some(pk) = pk {
+        for (pk_row, mat_row) in pk.chunks_mut(P::PK_ROW_BYTES).zip(mat.chunks_exact(words)) {
+            for (c, chunk) in pk_row.chunks_mut(8).enumerate() {
+                let t = load_bits(mat_row, P::PK_NROWS + 64 * c).to_le_bytes();
+                chunk.copy_from_slice(&t[..chunk.len()]);
+            }
+        }
     }
 
     0
@@ -374,27 +478,11 @@ pub(crate) fn pk_gen(
 
 #[cfg(test)]
 mod tests {
-    #[cfg(any(
-        feature = "mceliece348864f",
-        feature = "mceliece460896f",
-        feature = "mceliece6688128f",
-        feature = "mceliece6960119f",
-        feature = "mceliece8192128f"
-    ))]
     use super::*;
-    #[cfg(feature = "mceliece8192128f")]
-    use crate::api::CRYPTO_PUBLICKEYBYTES;
-    #[cfg(feature = "mceliece8192128f")]
+    use crate::params::Mceliece8192128f;
     use crate::test_utils::TestData;
 
     #[test]
-    #[cfg(any(
-        feature = "mceliece348864f",
-        feature = "mceliece460896f",
-        feature = "mceliece6688128f",
-        feature = "mceliece6960119f",
-        feature = "mceliece8192128f"
-    ))]
     fn test_ctz() {
         const EXPECTED: [i32; 180] = [
             64, 0, 1, 0, 2, 0, 1, 0, 3, 0, 1, 0, 2, 0, 1, 0, 4, 0, 1, 0, 2, 0, 1, 0, 3, 0, 1, 0, 2,
@@ -405,19 +493,12 @@ mod tests {
             0, 1, 0, 2, 0, 1, 0, 3, 0, 1, 0, 2, 0, 1, 0, 5, 0, 1, 0, 2, 0, 1, 0, 3, 0, 1, 0, 2, 0,
             1, 0, 4, 0, 1, 0,
         ];
-        for i in 0..180 {
-            assert_eq!(ctz(i as u64), EXPECTED[i]);
+        for (i, expected) in EXPECTED.iter().enumerate() {
+            assert_eq!(ctz(i as u64), *expected);
         }
     }
 
     #[test]
-    #[cfg(any(
-        feature = "mceliece348864f",
-        feature = "mceliece460896f",
-        feature = "mceliece6688128f",
-        feature = "mceliece6960119f",
-        feature = "mceliece8192128f"
-    ))]
     fn test_same_mask() {
         const EXPECTED: [u64; 25] = [
             0xFFFFFFFFFFFFFFFF,
@@ -454,18 +535,22 @@ mod tests {
     }
 
     #[test]
-    #[cfg(feature = "mceliece8192128f")]
     fn test_mov_columns() {
-        const COLS: usize = SYS_N / 8;
+        type P = Mceliece8192128f;
+        const COLS: usize = P::SYS_N / 8;
+        const PK_NROWS: usize = P::PK_NROWS;
+
+        let words = row_words::<P>();
 
         // input data
-        let mut mat = vec![[0u8; COLS]; PK_NROWS];
+        let mut mat = vec![0u64; PK_NROWS * words];
         let mat_data = TestData::new().u8vec("mceliece8192128f_mat_before");
         assert_eq!(mat_data.len(), PK_NROWS * COLS);
 
         for row in 0..PK_NROWS {
             for col in 0..COLS {
-                mat[row][col] = mat_data[row * COLS + col];
+                mat[row * words + col / 8] |=
+                    (mat_data[row * COLS + col] as u64) << (8 * (col % 8));
             }
         }
 
@@ -473,11 +558,7 @@ mod tests {
         let mut pivots = 0u64;
 
         // generated actual result
-        mov_columns(
-            sub!(mut mat.as_mut_slice(), 0, PK_NROWS, [u8; COLS]),
-            sub!(mut pi, 0, 1 << GFBITS, i16),
-            &mut pivots,
-        );
+        mov_columns::<P>(&mut mat, &mut pi[..1 << P::GFBITS], &mut pivots);
 
         // expected data
         let mut mat_expected = [[0u8; COLS]; PK_NROWS];
@@ -491,14 +572,26 @@ mod tests {
         let pivots_expected = 8053063679u64;
 
         // comparison
-        assert_eq!(mat, mat_expected);
+        for row in 0..PK_NROWS {
+            for col in 0..COLS {
+                assert_eq!(
+                    (mat[row * words + col / 8] >> (8 * (col % 8))) as u8,
+                    mat_expected[row][col]
+                );
+            }
+        }
         assert_eq!(pi, pi_expected);
         assert_eq!(pivots, pivots_expected);
     }
 
     #[test]
-    #[cfg(feature = "mceliece8192128f")]
     fn test_pk_gen_1() {
+        type P = Mceliece8192128f;
+        const GFBITS: usize = P::GFBITS;
+        const SYS_N: usize = P::SYS_N;
+        const SYS_T: usize = P::SYS_T;
+        const CRYPTO_PUBLICKEYBYTES: usize = P::PUBLIC_KEY_BYTES;
+
         let sk_data = TestData::new().u8vec("mceliece8192128f_pk_gen_sk_input");
         let perm_data = TestData::new().u32vec("mceliece8192128f_pk_gen_perm_input");
 
@@ -518,12 +611,13 @@ mod tests {
         sk.copy_from_slice(sk_data.as_slice());
         perm.copy_from_slice(perm_data.as_slice());
 
-        pk_gen(
-            sub!(mut pk, 0, CRYPTO_PUBLICKEYBYTES),
-            &mut sk,
-            &mut perm,
+        pk_gen::<P>(
+            Some(&mut pk),
+            &sk,
+            &perm,
             &mut pi,
             &mut pivots,
+            &mut PkMatrix::new(),
         );
 
         let pk_expected = TestData::new().u8vec("mceliece8192128f_pk_gen_pk_expected");
@@ -539,8 +633,13 @@ mod tests {
     }
 
     #[test]
-    #[cfg(feature = "mceliece8192128f")]
     fn test_pk_gen_2() {
+        type P = Mceliece8192128f;
+        const GFBITS: usize = P::GFBITS;
+        const SYS_N: usize = P::SYS_N;
+        const SYS_T: usize = P::SYS_T;
+        const CRYPTO_PUBLICKEYBYTES: usize = P::PUBLIC_KEY_BYTES;
+
         // NOTE expected pk_data of previous testcase becomes input for this one
         let pk_data = TestData::new().u8vec("mceliece8192128f_pk_gen_pk_expected");
         let sk_data = TestData::new().u8vec("mceliece8192128f_pk_gen_sk2_input");
@@ -567,12 +666,13 @@ mod tests {
         perm.copy_from_slice(perm_data.as_slice());
         pi.copy_from_slice(pi_data.as_slice());
 
-        pk_gen(
-            sub!(mut pk, 0, CRYPTO_PUBLICKEYBYTES),
-            &mut sk,
-            &mut perm,
+        pk_gen::<P>(
+            Some(&mut pk),
+            &sk,
+            &perm,
             &mut pi,
             &mut pivots,
+            &mut PkMatrix::new(),
         );
 
         let pk_expected = TestData::new().u8vec("mceliece8192128f_pk_gen_pk2_expected");
@@ -1,17 +1,22 @@
 //! This file is for evaluating a polynomial at one or more field elements
 
 use crate::{
+    benes::support_map,
+    fft::fft,
     gf::{gf_add, gf_mul, Gf},
-    params::{SYS_N, SYS_T},
+    params::{ParameterSet, MAX_GFBITS},
+    vec,
 };
 
 /// Evaluate polynomial `f` with argument `a`.
 /// Thus it returns `f(a)` in symbolic notation.
-pub(crate) fn eval(f: &[Gf; SYS_T + 1], a: Gf) -> Gf {
-    let mut r: Gf = f[SYS_T];
+pub(crate) fn eval<P: ParameterSet>(f: &[Gf], a: Gf) -> Gf {
+    debug_assert_eq!(f.len(), P::SYS_T + 1);
 
-    for i in (0..=SYS_T - 1).rev() {
-        r = gf_mul(r, a);
+    let mut r: Gf = f[P::SYS_T];
+
+    for i in (0..=P::SYS_T - 1).rev() {
+        r = gf_mul::<P>(r, a);
         r = gf_add(r, f[i]);
     }
     r
@@ -19,30 +24,55 @@ pub(crate) fn eval(f: &[Gf; SYS_T + 1], a: Gf) -> Gf {
 
 /// Given polynomial `f` and a list of field elements `l`,
 /// return the roots `out` satisfying `[ f(a) for a in L ]`
-pub(crate) fn root(out: &mut [Gf; SYS_N], f: &[Gf; SYS_T + 1], l: &[Gf; SYS_N]) {
-    for i in 0..SYS_N {
-        out[i] = eval(f, l[i]);
+///
+/// With the feature `vec`, the bitsliced [`crate::vec::root`] is used instead.
+pub(crate) fn root<P: ParameterSet>(out: &mut [Gf], f: &[Gf], l: &[Gf]) {
+    debug_assert_eq!(out.len(), l.len());
+
+    if cfg!(feature = "vec") {
+        return vec::root::<P>(out, f, l);
     }
+
+    for (itr_out, &a) in out.iter_mut().zip(l) {
+        *itr_out = eval::<P>(f, a);
+    }
+}
+
+/// Given polynomial `f` and the control bits `cond` of the support, return the evaluations
+/// `out` of `f` at the support elements. They are computed for the whole field by the
+/// additive FFT and then permuted into the support order by the Beneš network.
+pub(crate) fn root_support<P: ParameterSet>(out: &mut [Gf], f: &[Gf], cond: &[u8]) {
+    debug_assert_eq!(out.len(), P::SYS_N);
+    debug_assert_eq!(f.len(), P::SYS_T + 1);
+
+    let mut values_buf = [0 as Gf; 1 << MAX_GFBITS];
+    let values = &mut values_buf[..1 << P::GFBITS];
+    fft::<P>(values, f);
+
+    support_map::<P>(out, values, cond);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::api::CRYPTO_PRIMITIVE;
+    use crate::api::DefaultParameterSet;
     use crate::test_utils::TestData;
 
     #[test]
-    #[cfg(feature = "mceliece8192128f")]
     fn test_root_simple() {
+        type P = crate::params::Mceliece8192128f;
+        const SYS_N: usize = P::SYS_N;
+        const SYS_T: usize = P::SYS_T;
+
         let g = [1u16; SYS_T + 1];
         let mut l = [0u16; SYS_N];
         let mut inv = [0u16; SYS_N];
 
-        for i in 0..l.len() {
-            l[i] = i as u16;
+        for (i, itr_l) in l.iter_mut().enumerate() {
+            *itr_l = i as u16;
         }
 
-        root(&mut inv, &g, &l);
+        root::<P>(&mut inv, &g, &l);
 
         let expected = TestData::new().u16vec("mceliece8192128f_root_inv_expected");
         assert_eq!(expected, inv);
@@ -50,6 +80,10 @@ mod tests {
 
     #[test]
     fn test_root() {
+        type P = DefaultParameterSet;
+        const SYS_N: usize = P::SYS_N;
+        const SYS_T: usize = P::SYS_T;
+
         let mut out = [0u16; SYS_N];
         let mut f = [0u16; SYS_T + 1];
         let mut l = [0u16; SYS_N];
@@ -62,9 +96,9 @@ mod tests {
             *f = (i as Gf).wrapping_mul(3);
         }
 
-        root(&mut out, &f, &l);
+        root::<P>(&mut out, &f, &l);
 
-        let mut name = format!("{}_root_out_expected", CRYPTO_PRIMITIVE);
+        let mut name = format!("{}_root_out_expected", P::NAME);
         // NOTE the f-variants equals the non-f variants. We only stored the non-f variants
         name = name.replace("f_root_out", "_root_out");
         let expected = TestData::new().u16vec(&name);
@@ -0,0 +1,319 @@
+//! Serde support for keys, ciphertexts and shared secrets
+//!
+//! Binary formats get the raw bytes, human-readable formats a lowercase hex string. The hex
+//! string is written straight to the serializer and decoded straight into the key buffer, and
+//! byte sequences are filled in element by element, so the megabyte-sized public keys are
+//! never copied into an intermediate buffer. Deserialization checks the length.
+//!
+//! [`SecretKey`] and [`SharedSecret`] only implement [`Deserialize`]. To serialize them, they
+//! have to be wrapped in [`ExposeSecret`].
+
+use alloc::boxed::Box;
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, Deserialize, Deserializer, SeqAccess, Unexpected, Visitor};
+use serde::ser::{Serialize, Serializer};
+
+use crate::{
+    ByteArray, Ciphertext, KeyBufferMut, ParameterSet, PublicKey, SecretKey, SharedSecret,
+    CRYPTO_BYTES,
+};
+
+/// Opt-in wrapper to serialize a [`SecretKey`] or a [`SharedSecret`], owned or borrowed.
+///
+/// ```
+/// # #[cfg(feature = "serde")] {
+/// use classic_mceliece_rust::{ExposeSecret, SecretKey};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Config {
+///     secret_key: ExposeSecret<SecretKey<'static>>,
+/// }
+/// # }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+#[derive(Debug)]
+pub struct ExposeSecret<T>(pub T);
+
+/// Lowercase hex, written to the formatter in chunks
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+        let mut chunk = [0u8; 128];
+        for bytes in self.0.chunks(chunk.len() / 2) {
+            for (digits, &byte) in chunk.chunks_exact_mut(2).zip(bytes) {
+                digits[0] = DIGITS[usize::from(byte >> 4)];
+                digits[1] = DIGITS[usize::from(byte & 0xF)];
+            }
+            let digits = &chunk[..2 * bytes.len()];
+            f.write_str(core::str::from_utf8(digits).expect("hex digits are ASCII"))?;
+        }
+        Ok(())
+    }
+}
+
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.collect_str(&Hex(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+/// Clears the partially deserialized `buf` before it is dropped
+fn discard<A: ByteArray>(buf: Box<A>) {
+    #[cfg(feature = "zeroize")]
+    {
+        let mut buf = buf;
+        zeroize::Zeroize::zeroize((*buf).as_mut());
+    }
+    #[cfg(not(feature = "zeroize"))]
+    drop(buf);
+}
+
+struct BytesVisitor<A>(PhantomData<A>);
+
+impl<'de, A: ByteArray> Visitor<'de> for BytesVisitor<A> {
+    type Value = Box<A>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} bytes or {} hex digits", A::LEN, 2 * A::LEN)
+    }
+
+    fn visit_bytes<E: de::Error>(self, bytes: &[u8]) -> Result<Box<A>, E> {
+        if bytes.len() != A::LEN {
+            return Err(E::invalid_length(bytes.len(), &self));
+        }
+        let mut buf = A::zeroed_boxed();
+        (*buf).as_mut().copy_from_slice(bytes);
+        Ok(buf)
+    }
+
+    fn visit_str<E: de::Error>(self, hex: &str) -> Result<Box<A>, E> {
+        if hex.len() != 2 * A::LEN {
+            return Err(E::invalid_length(hex.len(), &self));
+        }
+        let mut buf = A::zeroed_boxed();
+        if hex::decode_to_slice(hex, (*buf).as_mut()).is_err() {
+            discard(buf);
+            return Err(E::invalid_value(Unexpected::Other("non-hex string"), &self));
+        }
+        Ok(buf)
+    }
+
+    fn visit_seq<S: SeqAccess<'de>>(self, mut seq: S) -> Result<Box<A>, S::Error> {
+        let mut buf = A::zeroed_boxed();
+        for i in 0..A::LEN {
+            match seq.next_element()? {
+                Some(byte) => (*buf).as_mut()[i] = byte,
+                None => {
+                    discard(buf);
+                    return Err(de::Error::invalid_length(i, &self));
+                }
+            }
+        }
+        if seq.next_element::<de::IgnoredAny>()?.is_some() {
+            discard(buf);
+            return Err(de::Error::invalid_length(A::LEN + 1, &self));
+        }
+        Ok(buf)
+    }
+}
+
+fn deserialize_bytes<'de, A: ByteArray, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Box<A>, D::Error> {
+    let visitor = BytesVisitor(PhantomData);
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(visitor)
+    } else {
+        deserializer.deserialize_bytes(visitor)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<P: ParameterSet> Serialize for PublicKey<'_, P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.as_ref(), serializer)
+    }
+}
+
+/// Checks the length and structure of the key, see [`PublicKey::try_from_bytes`].
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'de, P: ParameterSet> Deserialize<'de> for PublicKey<'static, P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let public_key = PublicKey::from(deserialize_bytes::<P::PublicKeyArray, D>(deserializer)?);
+        public_key.validate().map_err(de::Error::custom)?;
+        Ok(public_key)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<P: ParameterSet> Serialize for Ciphertext<P> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.as_ref(), serializer)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'de, P: ParameterSet> Deserialize<'de> for Ciphertext<P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let buf = deserialize_bytes::<P::CiphertextArray, D>(deserializer)?;
+        Ok(Ciphertext(*buf))
+    }
+}
+
+/// Only checks the length of the key, see [`SecretKey::validate`] for the structure.
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'de, P: ParameterSet> Deserialize<'de> for SecretKey<'static, P> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let buf = deserialize_bytes::<P::SecretKeyArray, D>(deserializer)?;
+        Ok(SecretKey::from(buf))
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'de> Deserialize<'de> for SharedSecret<'static> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let buf = deserialize_bytes::<[u8; CRYPTO_BYTES], D>(deserializer)?;
+        Ok(SharedSecret(KeyBufferMut::Owned(buf)))
+    }
+}
+
+impl<P: ParameterSet> Serialize for ExposeSecret<SecretKey<'_, P>> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.0.as_ref(), serializer)
+    }
+}
+
+impl<P: ParameterSet> Serialize for ExposeSecret<&SecretKey<'_, P>> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.0.as_ref(), serializer)
+    }
+}
+
+impl Serialize for ExposeSecret<SharedSecret<'_>> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.0.as_ref(), serializer)
+    }
+}
+
+impl Serialize for ExposeSecret<&SharedSecret<'_>> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bytes(self.0.as_ref(), serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for ExposeSecret<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(ExposeSecret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generic, Mceliece348864, Mceliece6960119};
+    use serde_test::{
+        assert_de_tokens_error, assert_ser_tokens, assert_tokens, Compact, Configure, Readable,
+        Token,
+    };
+    use std::string::ToString;
+
+    #[test]
+    fn test_serde_tokens() {
+        const BYTES: [u8; CRYPTO_BYTES] = {
+            let mut bytes = [7u8; CRYPTO_BYTES];
+            bytes[0] = 0xAB;
+            bytes
+        };
+        let hex = "ab07070707070707070707070707070707070707070707070707070707070707";
+        let mut ss_buf = BYTES;
+        let shared_secret = SharedSecret(KeyBufferMut::Borrowed(&mut ss_buf));
+
+        assert_ser_tokens(&ExposeSecret(&shared_secret).readable(), &[Token::Str(hex)]);
+        assert_ser_tokens(
+            &ExposeSecret(&shared_secret).compact(),
+            &[Token::Bytes(&BYTES)],
+        );
+
+        let decoded: SharedSecret = serde_json::from_str(&std::format!("\"{hex}\"")).unwrap();
+        assert_eq!(decoded.as_ref(), BYTES);
+
+        let ct = Ciphertext::<Mceliece348864>::from([1u8; Mceliece348864::CIPHERTEXT_BYTES]);
+        assert_tokens(&CiphertextEq(ct).compact(), &[Token::Bytes(&[1u8; 96])]);
+
+        assert_de_tokens_error::<Compact<CiphertextEq>>(
+            &[Token::Bytes(&[1u8; 95])],
+            "invalid length 95, expected 96 bytes or 192 hex digits",
+        );
+        assert_de_tokens_error::<Readable<CiphertextEq>>(
+            &[Token::Str("zz".repeat(96).leak())],
+            "invalid value: non-hex string, expected 96 bytes or 192 hex digits",
+        );
+        assert_de_tokens_error::<Compact<CiphertextEq>>(
+            &[
+                Token::Seq { len: Some(2) },
+                Token::U8(1),
+                Token::U8(2),
+                Token::SeqEnd,
+            ],
+            "invalid length 2, expected 96 bytes or 192 hex digits",
+        );
+    }
+
+    /// Comparable ciphertext for `serde_test`
+    #[derive(Debug)]
+    struct CiphertextEq(Ciphertext<Mceliece348864>);
+
+    impl PartialEq for CiphertextEq {
+        fn eq(&self, other: &Self) -> bool {
+            self.0.as_ref() == other.0.as_ref()
+        }
+    }
+
+    impl Serialize for CiphertextEq {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            self.0.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for CiphertextEq {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            Ciphertext::deserialize(deserializer).map(CiphertextEq)
+        }
+    }
+
+    #[test]
+    fn test_serde_keys() {
+        std::thread::Builder::new()
+            .stack_size(4 * 1024 * 1024)
+            .spawn(|| {
+                type P = Mceliece6960119;
+                let (public_key, secret_key) = generic::keypair_from_seed_boxed::<P>(&[5u8; 32]);
+
+                let json = serde_json::to_string(&public_key).unwrap();
+                assert_eq!(json.len(), 2 * P::PUBLIC_KEY_BYTES + 2);
+                let decoded: PublicKey<P> = serde_json::from_str(&json).unwrap();
+                assert_eq!(decoded.as_ref(), public_key.as_ref());
+
+                let json = serde_json::to_string(&ExposeSecret(&secret_key)).unwrap();
+                let decoded: SecretKey<P> = serde_json::from_str(&json).unwrap();
+                assert_eq!(decoded.as_ref(), secret_key.as_ref());
+
+                // the padding bits of the public key are checked
+                let mut bytes = public_key.as_ref().to_vec();
+                bytes[P::PK_ROW_BYTES - 1] |= 0x80;
+                let json = serde_json::to_string(&Hex(&bytes).to_string()).unwrap();
+                let error = serde_json::from_str::<PublicKey<P>>(&json).unwrap_err();
+                assert!(error.to_string().contains("padding"), "{error}");
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
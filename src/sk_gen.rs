@@ -1,29 +1,33 @@
 //! Generation of secret key
 
 use crate::gf::{gf_inv, gf_iszero, gf_mul, gf_mul_inplace, Gf};
-use crate::params::SYS_T;
+use crate::params::{ParameterSet, MAX_SYS_T};
 
 /// Take element `f` in `GF((2^m)^t)` and return minimal polynomial `out` of `f`
 /// Returns 0 for success and -1 for failure
-pub(crate) fn genpoly_gen(out: &mut [Gf; SYS_T], f: &[Gf; SYS_T]) -> isize {
-    let mut mat = [[0u16; SYS_T]; SYS_T + 1];
+pub(crate) fn genpoly_gen<P: ParameterSet>(out: &mut [Gf], f: &[Gf]) -> isize {
+    debug_assert_eq!(out.len(), P::SYS_T);
+    debug_assert_eq!(f.len(), P::SYS_T);
+
+    let mut mat_buf = [[0u16; MAX_SYS_T]; MAX_SYS_T + 1];
+    let mat = &mut mat_buf[..=P::SYS_T];
     mat[0][0] = 1;
 
-    mat[0][1..SYS_T].fill(0);
+    mat[0][1..P::SYS_T].fill(0);
 
-    mat[1] = *f;
+    mat[1][..P::SYS_T].copy_from_slice(f);
 
-    for j in 2..=SYS_T {
+    for j in 2..=P::SYS_T {
         let (left, right) = mat.split_at_mut(j);
-        gf_mul_inplace(&mut right[0], &left[j - 1], f);
+        gf_mul_inplace::<P>(&mut right[0][..P::SYS_T], &left[j - 1][..P::SYS_T], f);
     }
 
-    for j in 0..SYS_T {
-        for k in (j + 1)..SYS_T {
+    for j in 0..P::SYS_T {
+        for k in (j + 1)..P::SYS_T {
             let mask = gf_iszero(mat[j][j]);
 
             let mut c = j;
-            while c < SYS_T + 1 {
+            while c < P::SYS_T + 1 {
                 mat[c][j] ^= mat[c][k] & mask;
                 c += 1;
             }
@@ -33,37 +37,38 @@ pub(crate) fn genpoly_gen(out: &mut [Gf; SYS_T], f: &[Gf; SYS_T]) -> isize {
             return -1;
         }
 
-        let inv = gf_inv(mat[j][j]);
+        let inv = gf_inv::<P>(mat[j][j]);
 
         for itr_mat in mat.iter_mut() {
-            itr_mat[j] = gf_mul(itr_mat[j], inv);
+            itr_mat[j] = gf_mul::<P>(itr_mat[j], inv);
         }
 
-        for k in 0..SYS_T {
+        for k in 0..P::SYS_T {
             if k != j {
                 let t = mat[j][k];
 
                 for itr_mat in mat.iter_mut() {
-                    itr_mat[k] ^= gf_mul(itr_mat[j], t);
+                    itr_mat[k] ^= gf_mul::<P>(itr_mat[j], t);
                 }
             }
         }
     }
 
-    out[0..SYS_T].copy_from_slice(&mat[SYS_T][0..SYS_T]);
+    out.copy_from_slice(&mat[P::SYS_T][0..P::SYS_T]);
 
     0
 }
 
 #[cfg(test)]
-#[cfg(all(feature = "mceliece8192128f", test))]
 mod tests {
     use super::*;
     use crate::macros::sub;
+    use crate::params::Mceliece8192128f;
     use crate::test_utils::TestData;
 
     #[test]
     fn test_genpoly_gen() {
+        const SYS_T: usize = <Mceliece8192128f as ParameterSet>::SYS_T;
         assert_eq!(SYS_T, 128);
 
         let input_src = TestData::new().u16vec("mceliece8192128f_sk_gen_genpoly_1st_round_input");
@@ -73,7 +78,7 @@ mod tests {
 
         let mut output = [0u16; SYS_T];
 
-        genpoly_gen(&mut output, first_round_input);
+        genpoly_gen::<Mceliece8192128f>(&mut output, first_round_input);
 
         assert_eq!(&output, first_round_output.as_slice());
     }
@@ -1,27 +1,133 @@
 //! Syndrome computation
 
-use crate::gf::{gf_add, gf_inv, gf_mul, Gf};
-use crate::params::{SYS_N, SYS_T};
-use crate::root::eval;
-
-/// Given Goppa polynomial `f`, support `l`, and received word `r`
-/// compute `out`, the syndrome of length 2t
-pub(crate) fn synd(
-    out: &mut [Gf; SYS_T * 2],
-    f: &[Gf; SYS_T + 1],
-    l: &[Gf; SYS_N],
-    r: &[u8; SYS_N / 8],
-) {
-    out[0..2 * SYS_T].fill(0);
-
-    for i in 0..SYS_N {
-        let c: Gf = (r[i / 8] >> (i % 8)) as u16 & 1;
-        let e: Gf = eval(f, l[i]);
-        let mut e_inv: Gf = gf_inv(gf_mul(e, e));
-
-        for itr_out in out.iter_mut() {
-            *itr_out = gf_add(*itr_out, gf_mul(e_inv, c));
-            e_inv = gf_mul(e_inv, l[i]);
+use crate::benes::apply_benes;
+use crate::fft::{fft, fft_tr};
+use crate::gf::{gf_inv, gf_mul, Gf};
+use crate::params::{ParameterSet, MAX_GFBITS};
+use crate::util::bitrev;
+use crate::vec;
+
+/// Given Goppa polynomial `f`, compute `out[a] = 1 / f(a)^2` for all field elements `a`,
+/// the weights of the received bits in [`synd`]
+///
+/// With the feature `vec`, the bitsliced [`crate::vec::synd_weights`] is used instead.
+pub(crate) fn synd_weights<P: ParameterSet>(out: &mut [Gf], f: &[Gf]) {
+    debug_assert_eq!(out.len(), 1 << P::GFBITS);
+    debug_assert_eq!(f.len(), P::SYS_T + 1);
+
+    if cfg!(feature = "vec") {
+        return vec::synd_weights::<P>(out, f);
+    }
+
+    fft::<P>(out, f);
+
+    for itr_out in out.iter_mut() {
+        *itr_out = gf_inv::<P>(gf_mul::<P>(*itr_out, *itr_out));
+    }
+}
+
+/// Given the weights `w` of the Goppa polynomial (see [`synd_weights`]), the control bits
+/// `cond` of the support, and received word `r` compute `out`, the syndrome of length 2t
+///
+/// The syndrome `Σ r_i · l_i^j / g(l_i)^2` is the transposed additive FFT of the received bits,
+/// which the inverse Beneš network moves from the support order to the field order.
+pub(crate) fn synd<P: ParameterSet>(out: &mut [Gf], w: &[Gf], cond: &[u8], r: &[u8]) {
+    debug_assert_eq!(out.len(), 2 * P::SYS_T);
+    debug_assert_eq!(w.len(), 1 << P::GFBITS);
+    debug_assert_eq!(cond.len(), P::COND_BYTES);
+    debug_assert_eq!(r.len(), P::SYS_N / 8);
+
+    let mut bits_buf = [0u8; (1 << MAX_GFBITS) / 8];
+    let bits = &mut bits_buf[..(1 << P::GFBITS) / 8];
+    bits[..r.len()].copy_from_slice(r);
+
+    // the bit of the field element `a` is now at position `bitrev(a)`
+    apply_benes::<P>(bits, cond, 1);
+
+    let mut v_buf = [0 as Gf; 1 << MAX_GFBITS];
+    let v = &mut v_buf[..1 << P::GFBITS];
+    for (a, itr_v) in v.iter_mut().enumerate() {
+        let j = bitrev::<P>(a as Gf) as usize;
+        let c: Gf = (bits[j / 8] >> (j % 8)) as u16 & 1;
+        *itr_v = w[a] & c.wrapping_neg();
+    }
+
+    fft_tr::<P>(out, v);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::benes::support_gen;
+    use crate::params::{Mceliece348864, Mceliece6960119, MAX_COND_BYTES, MAX_SYS_N, MAX_SYS_T};
+    use crate::root::{eval, root_support};
+
+    fn check_synd<P: ParameterSet>() {
+        let mut state: u64 = 7;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        let mut g = [0 as Gf; MAX_SYS_T + 1];
+        let g = &mut g[..=P::SYS_T];
+        for itr_g in g.iter_mut() {
+            *itr_g = next() as Gf & P::GFMASK as Gf;
+        }
+        g[P::SYS_T] = 1;
+        let mut cond = [0u8; MAX_COND_BYTES];
+        let cond = &mut cond[..P::COND_BYTES];
+        for itr_cond in cond.iter_mut() {
+            *itr_cond = next() as u8;
         }
+        let mut r = [0u8; MAX_SYS_N / 8];
+        let r = &mut r[..P::SYS_N / 8];
+        for itr_r in r.iter_mut() {
+            *itr_r = next() as u8;
+        }
+
+        let mut l = [0 as Gf; MAX_SYS_N];
+        let l = &mut l[..P::SYS_N];
+        support_gen::<P>(l, cond);
+
+        let mut images = [0 as Gf; MAX_SYS_N];
+        root_support::<P>(&mut images[..P::SYS_N], g, cond);
+        for i in 0..P::SYS_N {
+            assert_eq!(images[i], eval::<P>(g, l[i]));
+        }
+
+        let mut w = [0 as Gf; 1 << MAX_GFBITS];
+        let w = &mut w[..1 << P::GFBITS];
+        synd_weights::<P>(w, g);
+        let mut s = [0 as Gf; 2 * MAX_SYS_T];
+        synd::<P>(&mut s[..2 * P::SYS_T], w, cond, r);
+
+        let mut expected = [0 as Gf; 2 * MAX_SYS_T];
+        for i in 0..P::SYS_N {
+            if (r[i / 8] >> (i % 8)) & 1 == 1 {
+                let e = eval::<P>(g, l[i]);
+                let mut e_inv = gf_inv::<P>(gf_mul::<P>(e, e));
+                for itr_expected in expected[..2 * P::SYS_T].iter_mut() {
+                    *itr_expected ^= e_inv;
+                    e_inv = gf_mul::<P>(e_inv, l[i]);
+                }
+            }
+        }
+        assert_eq!(s, expected);
+    }
+
+    #[test]
+    fn test_synd() {
+        std::thread::Builder::new()
+            .stack_size(4 * 1024 * 1024)
+            .spawn(|| {
+                check_synd::<Mceliece348864>();
+                check_synd::<Mceliece6960119>();
+            })
+            .unwrap()
+            .join()
+            .unwrap();
     }
 }
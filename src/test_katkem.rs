@@ -11,7 +11,7 @@ use std::io::BufRead;
 use std::io::BufReader;
 use std::io::Write;
 
-use crate::nist_aes_rng::AesState;
+use crate::nist_aes_rng::NistDrbg;
 use crate::test_utils::TestData;
 use crate::{decapsulate, encapsulate, keypair, keypair_boxed};
 use crate::{
@@ -202,11 +202,9 @@ pub(crate) fn create_request_file(filepath: &str) -> R {
     for (i, e) in entropy_input.iter_mut().enumerate() {
         *e = i as u8;
     }
-    let mut rng = AesState::new();
-    rng.randombytes_init(entropy_input);
+    let mut rng = NistDrbg::new(entropy_input);
 
-    let mut rng_kem = AesState::new();
-    rng_kem.randombytes_init(entropy_input);
+    let mut rng_kem = NistDrbg::new(entropy_input);
 
     // create KATNUM testcase seeds
     for t in 0..KATNUM {
@@ -233,10 +231,8 @@ pub(crate) fn create_response_file(filepath: &str) -> R {
     for (i, e) in entropy_input.iter_mut().enumerate() {
         *e = i as u8;
     }
-    let mut rng = AesState::new();
-    let mut rng_kem = AesState::new();
-    rng.randombytes_init(entropy_input);
-    rng_kem.randombytes_init(entropy_input);
+    let mut rng = NistDrbg::new(entropy_input);
+    let mut rng_kem = NistDrbg::new(entropy_input);
 
     // create KATNUM testcase seeds
     for t in 0..KATNUM {
@@ -244,8 +240,7 @@ pub(crate) fn create_response_file(filepath: &str) -> R {
         tc.count = t;
         rng.fill_bytes(&mut tc.seed);
 
-        let mut tc_rng = AesState::new();
-        tc_rng.randombytes_init(tc.seed);
+        let mut tc_rng = NistDrbg::new(tc.seed);
 
         let mut pk_buf = Box::new([0u8; CRYPTO_PUBLICKEYBYTES]);
         let mut sk_buf = [0u8; CRYPTO_SECRETKEYBYTES];
@@ -266,7 +261,7 @@ pub(crate) fn create_response_file(filepath: &str) -> R {
         let (ct_kem, ss_kem) = ClassicMcEliece.try_encap(&mut rng_kem, &pk_kem).unwrap();
         let ss2_kem = sk_kem.try_decap(&ct_kem).unwrap();
 
-        (&mut tc).pk_kem = *pk_kem.as_array();
+        tc.pk_kem = *pk_kem.as_array();
         tc.sk_kem = *sk_kem.as_array();
         assert_eq!(ss_kem.as_bytes(), ss2_kem.as_bytes());
         tc.ss_kem.copy_from_slice(ss_kem.as_bytes());
@@ -284,8 +279,8 @@ pub(crate) fn verify(filepath: &str) -> R {
 
     let fd = fs::File::open(filepath)?;
     let mut reader = BufReader::new(fd);
-    let mut rng = AesState::new();
-    let mut rng_kem = AesState::new();
+    let mut rng = NistDrbg::new([0u8; 48]);
+    let mut rng_kem = NistDrbg::new([0u8; 48]);
 
     // first record in a response file is empty (e.g. “# ntruhps2048509\n”)
     // hence, skip it
@@ -297,8 +292,8 @@ pub(crate) fn verify(filepath: &str) -> R {
         let mut expected = Box::new(Testcase::new());
         expected.read_from_file(&mut reader)?;
 
-        rng.randombytes_init(expected.seed);
-        rng_kem.randombytes_init(expected.seed_kem);
+        rng.randombytes_init(expected.seed, None);
+        rng_kem.randombytes_init(expected.seed_kem, None);
 
         let mut actual = Box::new(Testcase::with_seed(t, &expected.seed, &expected.seed_kem));
 
@@ -458,9 +453,11 @@ fn zeroize() {
 }
 
 #[test]
-#[cfg(feature = "mceliece8192128f")]
 fn crypto_alloc_api_keypair() {
-    use crate::{keypair_boxed, CRYPTO_PUBLICKEYBYTES, CRYPTO_SECRETKEYBYTES};
+    use crate::generic::keypair_boxed;
+    use crate::params::{Mceliece8192128f, ParameterSet};
+
+    type P = Mceliece8192128f;
 
     let entropy_input = <[u8; 48]>::try_from(
         TestData::new()
@@ -470,15 +467,14 @@ fn crypto_alloc_api_keypair() {
     .unwrap();
 
     let compare_sk = TestData::new().u8vec("mceliece8192128f_operations_sk_expected");
-    assert_eq!(compare_sk.len(), CRYPTO_SECRETKEYBYTES);
+    assert_eq!(compare_sk.len(), P::SECRET_KEY_BYTES);
 
     let compare_pk = TestData::new().u8vec("mceliece8192128f_operations_pk_expected");
-    assert_eq!(compare_pk.len(), CRYPTO_PUBLICKEYBYTES);
+    assert_eq!(compare_pk.len(), P::PUBLIC_KEY_BYTES);
 
-    let mut rng_state = crate::nist_aes_rng::AesState::new();
-    rng_state.randombytes_init(entropy_input);
+    let mut rng_state = crate::nist_aes_rng::NistDrbg::new(entropy_input);
 
-    let (pk, sk) = keypair_boxed(&mut rng_state);
+    let (pk, sk) = keypair_boxed::<P, _>(&mut rng_state);
 
     assert_eq!(compare_sk.as_slice(), sk.0.as_ref());
     assert_eq!(compare_pk.as_slice(), pk.0.as_ref());
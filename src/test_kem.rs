@@ -1,6 +1,6 @@
 #![cfg(all(test, feature = "kem"))]
 
-use crate::nist_aes_rng::AesState;
+use crate::nist_aes_rng::NistDrbg;
 use crate::{Ciphertext, CRYPTO_CIPHERTEXTBYTES};
 use kem::generic_array::GenericArray;
 use kem::{Decapsulator, EncappedKey, Encapsulator};
@@ -9,7 +9,7 @@ use kem::{Decapsulator, EncappedKey, Encapsulator};
 fn crypto_kem_api() -> Result<(), kem::Error> {
     use crate::ClassicMcEliece;
 
-    let mut rng_state = AesState::new();
+    let mut rng_state = NistDrbg::new([0u8; 48]);
 
     let (pk, sk) = crate::keypair_boxed(&mut rng_state);
     let (ciphertext, shared_secret) = ClassicMcEliece.try_encap(&mut rng_state, &pk)?;
@@ -1,7 +1,6 @@
 //! Matrix transpose implementation
 
 /// Compute transposition of `input` and store it in `output`
-#[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
 pub(crate) fn transpose(output: &mut [u64; 64], input: [u64; 64]) {
     let masks: [[u64; 2]; 6] = [
         [0x5555555555555555, 0xAAAAAAAAAAAAAAAA],
@@ -22,7 +21,7 @@ pub(crate) fn transpose(output: &mut [u64; 64], input: [u64; 64]) {
                 let x = (output[j] & masks[d][0]) | ((output[j + s] & masks[d][0]) << s);
                 let y = ((output[j] & masks[d][1]) >> s) | (output[j + s] & masks[d][1]);
 
-                output[j + 0] = x;
+                output[j] = x;
                 output[j + s] = y;
             }
         }
@@ -37,7 +36,6 @@ pub(crate) fn transpose(output: &mut [u64; 64], input: [u64; 64]) {
 /// input argument == output argument. Because we cannot create a
 /// shared and mutable reference simultaneously, we can only generate
 /// one argument.
-#[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
 pub(crate) fn transpose_64x64_inplace(arg: &mut [u64; 64]) {
     let masks = [
         [0x5555555555555555u64, 0xAAAAAAAAAAAAAAAAu64],
@@ -356,19 +354,13 @@ mod tests {
         };
 
         for testcase in testcases {
-            #[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
-            {
-                let mut test_output: [u64; 64] = [0; 64];
-                transpose(&mut test_output, testcase.input);
-                assert_eq!(test_output, testcase.output);
-            }
+            let mut test_output: [u64; 64] = [0; 64];
+            transpose(&mut test_output, testcase.input);
+            assert_eq!(test_output, testcase.output);
 
-            #[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-            {
-                let mut data = testcase.input;
-                transpose_64x64_inplace(&mut data);
-                assert_eq!(data, testcase.output);
-            }
+            let mut data = testcase.input;
+            transpose_64x64_inplace(&mut data);
+            assert_eq!(data, testcase.output);
         }
     }
 }
@@ -23,19 +23,20 @@ const fn uint64_minmax(mut a: u64, mut b: u64) -> (u64, u64) {
 
 /// Sort a sequence of integers using a sorting network to achieve constant time.
 /// To our understanding, this implements [djbsort](https://sorting.cr.yp.to/).
-pub(crate) fn uint64_sort<const N: usize>(x: &mut [u64; N]) {
-    if N < 2 {
+pub(crate) fn uint64_sort(x: &mut [u64]) {
+    let n = x.len();
+    if n < 2 {
         return;
     }
     let mut top = 1;
 
-    while top < N.wrapping_sub(top) {
+    while top < n.wrapping_sub(top) {
         top += top;
     }
 
     let mut p = top;
     while p > 0 {
-        for i in 0..(N - p) {
+        for i in 0..(n - p) {
             if (i & p) == 0 {
                 let (tmp_xi, tmp_xip) = uint64_minmax(x[i], x[i + p]);
                 x[i] = tmp_xi;
@@ -44,7 +45,7 @@ pub(crate) fn uint64_sort<const N: usize>(x: &mut [u64; N]) {
         }
         let mut q = top;
         while q > p {
-            for i in 0..(N - q) {
+            for i in 0..(n - q) {
                 if (i & p) == 0 {
                     let mut a = x[i + p];
                     let mut r = q;
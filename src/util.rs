@@ -1,6 +1,6 @@
 //! This file is for loading/storing data in a little-endian fashion and a `bitrev` function
 
-use crate::{gf::Gf, params::GFMASK};
+use crate::{gf::Gf, params::ParameterSet};
 
 /// Store Gf element `a` in array `dest`
 #[inline(always)]
@@ -10,56 +10,73 @@ pub(crate) fn store_gf(dest: &mut [u8; 2], gf: Gf) {
 
 /// Interpret 2 bytes from `src` as integer and return it as Gf element
 #[inline(always)]
-pub(crate) fn load_gf(src: &[u8; 2]) -> Gf {
-    u16::from_le_bytes(*src) & (GFMASK as u16)
+pub(crate) fn load_gf<P: ParameterSet>(src: &[u8; 2]) -> Gf {
+    u16::from_le_bytes(*src) & (P::GFMASK as u16)
 }
 
 /// Reverse the bits of Gf element `a`. The LSB becomes the MSB.
 /// The 2nd LSB becomes the 2nd MSB. etc …
-pub(crate) fn bitrev(mut a: Gf) -> Gf {
+pub(crate) fn bitrev<P: ParameterSet>(mut a: Gf) -> Gf {
     a = ((a & 0x00FF) << 8) | ((a & 0xFF00) >> 8);
     a = ((a & 0x0F0F) << 4) | ((a & 0xF0F0) >> 4);
     a = ((a & 0x3333) << 2) | ((a & 0xCCCC) >> 2);
     a = ((a & 0x5555) << 1) | ((a & 0xAAAA) >> 1);
 
-    #[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-    {
-        a >> 4
-    }
-    #[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
-    {
-        a >> 3
-    }
+    a >> (16 - P::GFBITS)
 }
 
-/// Ugly hack to allocate a `Box<[u8; _]>` directly on the heap without first
+/// Ugly hack to allocate a `Box<[T; _]>` directly on the heap without first
 /// putting the array on the stack
 #[cfg(feature = "alloc")]
 #[inline(always)]
-pub fn alloc_boxed_array<const SIZE: usize>() -> alloc::boxed::Box<[u8; SIZE]> {
-    alloc::boxed::Box::<[u8; SIZE]>::try_from(alloc::vec![0u8; SIZE].into_boxed_slice()).unwrap()
+pub fn alloc_boxed_array<T: Copy + Default + core::fmt::Debug, const SIZE: usize>(
+) -> alloc::boxed::Box<[T; SIZE]> {
+    alloc::boxed::Box::<[T; SIZE]>::try_from(alloc::vec![T::default(); SIZE].into_boxed_slice())
+        .unwrap()
+}
+
+/// Like [`alloc_boxed_array`], but returns `None` instead of aborting if the allocation fails
+#[cfg(feature = "alloc")]
+pub fn try_alloc_boxed_array<T: Copy + Default, const SIZE: usize>(
+) -> Option<alloc::boxed::Box<[T; SIZE]>> {
+    let mut buf = alloc::vec::Vec::new();
+    buf.try_reserve_exact(SIZE).ok()?;
+    buf.resize(SIZE, T::default());
+    alloc::boxed::Box::<[T; SIZE]>::try_from(buf.into_boxed_slice()).ok()
+}
+
+/// Returns a vector of `len` default values, or `None` if the allocation fails
+#[cfg(feature = "parallel")]
+pub(crate) fn try_alloc_vec<T: Copy + Default>(len: usize) -> Option<alloc::vec::Vec<T>> {
+    let mut buf = alloc::vec::Vec::new();
+    buf.try_reserve_exact(len).ok()?;
+    buf.resize(len, T::default());
+    Some(buf)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::params::{Mceliece348864, Mceliece460896};
 
     #[test]
     fn test_load_gf() {
-        assert_eq!(load_gf(&[0xAB, 0x42]), 0x02AB);
+        assert_eq!(load_gf::<Mceliece460896>(&[0xAB, 0x42]), 0x02AB);
+        assert_eq!(load_gf::<Mceliece460896>(&[0xAB, 0xF2]), 0x12AB);
+        assert_eq!(load_gf::<Mceliece348864>(&[0xAB, 0xF2]), 0x02AB);
     }
 
     #[test]
-    #[cfg(any(feature = "mceliece348864", feature = "mceliece348864f"))]
-    fn test_bitrev() {
-        assert_eq!(bitrev(0b1011_0111_0111_1011), 0b0000_1101_1110_1110);
-        assert_eq!(bitrev(0b0110_1010_0101_1011), 0b0000_1101_1010_0101);
+    fn test_bitrev_gf12() {
+        type P = Mceliece348864;
+        assert_eq!(bitrev::<P>(0b1011_0111_0111_1011), 0b0000_1101_1110_1110);
+        assert_eq!(bitrev::<P>(0b0110_1010_0101_1011), 0b0000_1101_1010_0101);
     }
 
     #[test]
-    #[cfg(not(any(feature = "mceliece348864", feature = "mceliece348864f")))]
-    fn test_bitrev() {
-        assert_eq!(bitrev(0b1011_0111_0111_1011), 0b0001_1011_1101_1101);
-        assert_eq!(bitrev(0b0110_1010_0101_1011), 0b0001_1011_0100_1010);
+    fn test_bitrev_gf13() {
+        type P = Mceliece460896;
+        assert_eq!(bitrev::<P>(0b1011_0111_0111_1011), 0b0001_1011_1101_1101);
+        assert_eq!(bitrev::<P>(0b0110_1010_0101_1011), 0b0001_1011_0100_1010);
     }
 }
@@ -0,0 +1,293 @@
+//! This file is for checking the structure of public and secret keys
+
+use crate::{
+    benes::support_gen,
+    controlbits::controlbitsfrompermutation,
+    error::{PublicKeyError, SecretKeyError},
+    gf::{gf_inv, gf_iszero, gf_mul, Gf},
+    macros::sub,
+    operations::check_pk_padding,
+    params::{ParameterSet, MAX_COND_BYTES, MAX_GFBITS, MAX_SYS_N, MAX_SYS_T},
+    root::root_support,
+    util::bitrev,
+};
+
+/// Reduce the polynomial `a` of degree below `2 * SYS_T - 1` modulo the monic polynomial
+/// `g` of degree `SYS_T`. The result is left in the lowest `SYS_T` coefficients of `a`.
+fn poly_reduce<P: ParameterSet>(a: &mut [Gf], g: &[Gf]) {
+    debug_assert_eq!(a.len(), 2 * P::SYS_T - 1);
+    debug_assert_eq!(g.len(), P::SYS_T + 1);
+
+    for i in (P::SYS_T..2 * P::SYS_T - 1).rev() {
+        let c = a[i];
+        a[i] = 0;
+        for j in 0..P::SYS_T {
+            a[i - P::SYS_T + j] ^= gf_mul::<P>(c, g[j]);
+        }
+    }
+}
+
+/// Square the polynomial `a` of degree below `SYS_T` modulo `g`.
+fn poly_sqmod<P: ParameterSet>(a: &mut [Gf], g: &[Gf]) {
+    debug_assert_eq!(a.len(), P::SYS_T);
+
+    let mut prod_buf = [0 as Gf; 2 * MAX_SYS_T - 1];
+    let prod = &mut prod_buf[..2 * P::SYS_T - 1];
+
+    // in characteristic 2, squaring is linear: (sum a_i x^i)^2 = sum a_i^2 x^(2i)
+    for (i, &a_i) in a.iter().enumerate() {
+        prod[2 * i] = gf_mul::<P>(a_i, a_i);
+    }
+
+    poly_reduce::<P>(prod, g);
+    a.copy_from_slice(&prod[..P::SYS_T]);
+
+    #[cfg(feature = "zeroize")]
+    {
+        use zeroize::Zeroize;
+        prod_buf.zeroize();
+    }
+}
+
+/// Degree of the polynomial `a`, or `None` for the zero polynomial.
+fn poly_degree(a: &[Gf]) -> Option<usize> {
+    a.iter().rposition(|&c| c != 0)
+}
+
+/// Whether the greatest common divisor of `a` and `b` is a constant.
+/// Both polynomials are overwritten.
+///
+/// This is the Euclidean algorithm, its running time depends on the degrees of the
+/// remainders and thus on `a` and `b`.
+fn poly_coprime<'a, P: ParameterSet>(mut a: &'a mut [Gf], mut b: &'a mut [Gf]) -> bool {
+    while let Some(db) = poly_degree(b) {
+        // a = a mod b
+        let lead_inv = gf_inv::<P>(b[db]);
+        while let Some(da) = poly_degree(a).filter(|&da| da >= db) {
+            let c = gf_mul::<P>(a[da], lead_inv);
+            for j in 0..=db {
+                a[da - db + j] ^= gf_mul::<P>(c, b[j]);
+            }
+        }
+        core::mem::swap(&mut a, &mut b);
+    }
+
+    poly_degree(a) == Some(0)
+}
+
+/// Rabin's irreducibility test for the monic polynomial `g` of degree `t = SYS_T` over
+/// `GF(q)`, `q = 2^GFBITS`: `g` is irreducible if and only if `x^(q^t) = x mod g` and
+/// `gcd(x^(q^(t/d)) - x, g) = 1` for every prime divisor `d` of `t`.
+///
+/// This is not constant-time.
+fn is_irreducible<P: ParameterSet>(g: &[Gf]) -> bool {
+    debug_assert_eq!(g.len(), P::SYS_T + 1);
+
+    let t = P::SYS_T;
+
+    // h = x^(q^k) mod g, starting at k = 0
+    let mut h_buf = [0 as Gf; MAX_SYS_T];
+    let h = &mut h_buf[..t];
+    h[1] = 1;
+
+    let mut a_buf = [0 as Gf; MAX_SYS_T + 1];
+    let mut b_buf = [0 as Gf; MAX_SYS_T + 1];
+    let mut irreducible = true;
+
+    for k in 1..=t {
+        for _ in 0..P::GFBITS {
+            poly_sqmod::<P>(h, g);
+        }
+
+        let maximal_divisor = (2..=t).any(|d| t.is_multiple_of(d) && is_prime(d) && t / d == k);
+        if k == t || maximal_divisor {
+            let a = &mut a_buf[..=t];
+            a.fill(0);
+            a[..t].copy_from_slice(h);
+            a[1] ^= 1;
+
+            if k == t {
+                irreducible = poly_degree(a).is_none();
+            } else {
+                let b = &mut b_buf[..=t];
+                b.copy_from_slice(g);
+                irreducible = poly_coprime::<P>(a, b);
+            }
+
+            if !irreducible {
+                break;
+            }
+        }
+    }
+
+    // the powers of x modulo g are as secret as g
+    #[cfg(feature = "zeroize")]
+    {
+        use zeroize::Zeroize;
+        h_buf.zeroize();
+        a_buf.zeroize();
+        b_buf.zeroize();
+    }
+
+    irreducible
+}
+
+fn is_prime(n: usize) -> bool {
+    n >= 2
+        && (2..n)
+            .take_while(|d| d * d <= n)
+            .all(|d| !n.is_multiple_of(d))
+}
+
+/// Check the structure of the public key `pk`. Only the padding bits of the matrix rows
+/// have a fixed value; any other matrix is a possible public key.
+pub(crate) fn check_pk<P: ParameterSet>(pk: &[u8]) -> Result<(), PublicKeyError> {
+    debug_assert_eq!(pk.len(), P::PUBLIC_KEY_BYTES);
+
+    if P::PK_NROWS % 8 != 0 && check_pk_padding::<P>(pk) != 0 {
+        return Err(PublicKeyError::Padding);
+    }
+
+    Ok(())
+}
+
+/// Check the structure of the secret key `sk` (all sections after the seed are checked,
+/// the random string `s` has no structure). See [`SecretKeyError`] for the checks done.
+pub(crate) fn check_sk<P: ParameterSet>(sk: &[u8]) -> Result<(), SecretKeyError> {
+    debug_assert_eq!(sk.len(), P::SECRET_KEY_BYTES);
+
+    // pivots: 32 of the 64 columns for the semi-systematic form, all ones otherwise
+    let pivots = u64::from_le_bytes(*sub!(sk, 32, 8));
+    let pivots_ok = if P::SEMI_SYSTEMATIC {
+        pivots.count_ones() == 32
+    } else {
+        pivots == 0xFFFFFFFF
+    };
+    if !pivots_ok {
+        return Err(SecretKeyError::Pivots);
+    }
+
+    let mut g_buf = [0 as Gf; MAX_SYS_T + 1];
+    let mut field_buf = [0 as Gf; 1 << MAX_GFBITS];
+    let mut pi_buf = [0i16; 1 << MAX_GFBITS];
+    let mut expected_cond_buf = [0u8; MAX_COND_BYTES];
+    let mut images_buf = [0 as Gf; MAX_SYS_N];
+
+    let result = check_goppa_code::<P>(
+        sk,
+        &mut g_buf,
+        &mut field_buf,
+        &mut pi_buf,
+        &mut expected_cond_buf,
+        &mut images_buf,
+    );
+
+    // the buffers hold the Goppa polynomial and the support, also if a check failed midway
+    #[cfg(feature = "zeroize")]
+    {
+        use zeroize::Zeroize;
+        g_buf.zeroize();
+        field_buf.zeroize();
+        pi_buf.zeroize();
+        expected_cond_buf.zeroize();
+        images_buf.zeroize();
+    }
+
+    result
+}
+
+/// Check the Goppa polynomial and the control bits of the secret key `sk`.
+/// The remaining arguments are scratch space.
+fn check_goppa_code<P: ParameterSet>(
+    sk: &[u8],
+    g_buf: &mut [Gf; MAX_SYS_T + 1],
+    field_buf: &mut [Gf; 1 << MAX_GFBITS],
+    pi_buf: &mut [i16; 1 << MAX_GFBITS],
+    expected_cond_buf: &mut [u8; MAX_COND_BYTES],
+    images_buf: &mut [Gf; MAX_SYS_N],
+) -> Result<(), SecretKeyError> {
+    // Goppa polynomial: reduced coefficients, monic of degree t and irreducible
+    let irr = &sk[40..40 + P::IRR_BYTES];
+    let g = &mut g_buf[..=P::SYS_T];
+    for (i, chunk) in irr.chunks(2).enumerate() {
+        let coefficient = u16::from_le_bytes(*sub!(chunk, 0, 2));
+        if coefficient as usize & !P::GFMASK != 0 {
+            return Err(SecretKeyError::GoppaPolynomial);
+        }
+        g[i] = coefficient;
+    }
+    g[P::SYS_T] = 1;
+
+    if !is_irreducible::<P>(g) {
+        return Err(SecretKeyError::GoppaPolynomial);
+    }
+
+    // control bits: they must be the canonical control bits of the permutation they
+    // describe (any bit string describes some permutation), and the support must not
+    // contain roots of g
+    let cond = &sk[40 + P::IRR_BYTES..40 + P::IRR_BYTES + P::COND_BYTES];
+
+    let field = &mut field_buf[..1 << P::GFBITS];
+    support_gen::<P>(field, cond);
+
+    let pi = &mut pi_buf[..1 << P::GFBITS];
+    for (pi_i, &a) in pi.iter_mut().zip(field.iter()) {
+        *pi_i = bitrev::<P>(a) as i16;
+    }
+
+    let expected_cond = &mut expected_cond_buf[..P::COND_BYTES];
+    controlbitsfrompermutation(expected_cond, pi, P::GFBITS, 1 << P::GFBITS);
+    if cond != expected_cond {
+        return Err(SecretKeyError::ControlBits);
+    }
+
+    let images = &mut images_buf[..P::SYS_N];
+    root_support::<P>(images, g, cond);
+
+    if images.iter().any(|&image| gf_iszero(image) != 0) {
+        return Err(SecretKeyError::ControlBits);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::{Mceliece348864, Mceliece6960119};
+
+    #[test]
+    fn test_is_prime() {
+        let primes: std::vec::Vec<usize> = (0..20).filter(|&n| is_prime(n)).collect();
+        assert_eq!(primes, [2, 3, 5, 7, 11, 13, 17, 19]);
+    }
+
+    #[test]
+    fn test_poly_coprime() {
+        type P = Mceliece348864;
+
+        // (x + 1)(x + 2) and (x + 2)(x + 3) share the factor x + 2
+        let mut a = [2, 3, 1];
+        let mut b = [6, 1, 1];
+        assert!(!poly_coprime::<P>(&mut a, &mut b));
+
+        // (x + 1)(x + 2) and x + 3 are coprime
+        let mut a = [2, 3, 1];
+        let mut b = [3, 1, 0];
+        assert!(poly_coprime::<P>(&mut a, &mut b));
+    }
+
+    #[test]
+    fn test_is_irreducible() {
+        type P = Mceliece6960119;
+
+        // x^t has the root 0
+        let mut g = [0 as Gf; P::SYS_T + 1];
+        g[P::SYS_T] = 1;
+        assert!(!is_irreducible::<P>(&g));
+
+        // x^t + x^(t-1) has the roots 0 and 1
+        g[P::SYS_T - 1] = 1;
+        assert!(!is_irreducible::<P>(&g));
+    }
+}
@@ -0,0 +1,492 @@
+//! Runtime selection of Classic McEliece parameter sets
+
+use core::fmt;
+
+#[cfg(feature = "alloc")]
+use crate::any::{AnyPublicKey, AnySecretKey};
+use crate::api::CRYPTO_BYTES;
+use crate::error::Error;
+use crate::pk_gen::PkMatrix;
+use crate::{generic, operations, validate};
+use rand::{CryptoRng, RngCore};
+
+/// Calls `$body` with `$p` bound to the marker type of parameter set `$variant`.
+macro_rules! with_parameter_set {
+    ($variant:expr, $p:ident => $body:expr) => {{
+        use $crate::params::*;
+        match $variant {
+            $crate::Variant::Mceliece348864 => {
+                type $p = Mceliece348864;
+                $body
+            }
+            $crate::Variant::Mceliece348864f => {
+                type $p = Mceliece348864f;
+                $body
+            }
+            $crate::Variant::Mceliece460896 => {
+                type $p = Mceliece460896;
+                $body
+            }
+            $crate::Variant::Mceliece460896f => {
+                type $p = Mceliece460896f;
+                $body
+            }
+            $crate::Variant::Mceliece6688128 => {
+                type $p = Mceliece6688128;
+                $body
+            }
+            $crate::Variant::Mceliece6688128f => {
+                type $p = Mceliece6688128f;
+                $body
+            }
+            $crate::Variant::Mceliece6960119 => {
+                type $p = Mceliece6960119;
+                $body
+            }
+            $crate::Variant::Mceliece6960119f => {
+                type $p = Mceliece6960119f;
+                $body
+            }
+            $crate::Variant::Mceliece8192128 => {
+                type $p = Mceliece8192128;
+                $body
+            }
+            $crate::Variant::Mceliece8192128f => {
+                type $p = Mceliece8192128f;
+                $body
+            }
+        }
+    }};
+}
+
+pub(crate) use with_parameter_set;
+
+/// The 10 parameter sets of Classic McEliece (NIST round 4).
+///
+/// Use it to select a parameter set at runtime, e.g. after reading it from the wire.
+/// Each variant corresponds to one of the marker types implementing [`ParameterSet`](crate::ParameterSet).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Variant {
+    Mceliece348864,
+    Mceliece348864f,
+    Mceliece460896,
+    Mceliece460896f,
+    Mceliece6688128,
+    Mceliece6688128f,
+    Mceliece6960119,
+    Mceliece6960119f,
+    Mceliece8192128,
+    Mceliece8192128f,
+}
+
+impl Variant {
+    /// All parameter sets in the order of the specification
+    pub const ALL: [Variant; 10] = [
+        Variant::Mceliece348864,
+        Variant::Mceliece348864f,
+        Variant::Mceliece460896,
+        Variant::Mceliece460896f,
+        Variant::Mceliece6688128,
+        Variant::Mceliece6688128f,
+        Variant::Mceliece6960119,
+        Variant::Mceliece6960119f,
+        Variant::Mceliece8192128,
+        Variant::Mceliece8192128f,
+    ];
+
+    /// Returns the designated identifier, e.g. `"mceliece6960119f"`
+    pub fn name(self) -> &'static str {
+        with_parameter_set!(self, P => P::NAME)
+    }
+
+    /// Looks up the parameter set by its designated identifier, e.g. `"mceliece6960119f"`
+    pub fn from_name(name: &str) -> Option<Variant> {
+        Variant::ALL
+            .into_iter()
+            .find(|variant| variant.name() == name)
+    }
+
+    /// The number of bytes required to store the public key
+    pub fn public_key_bytes(self) -> usize {
+        with_parameter_set!(self, P => P::PUBLIC_KEY_BYTES)
+    }
+
+    /// The number of bytes required to store the secret key
+    pub fn secret_key_bytes(self) -> usize {
+        with_parameter_set!(self, P => P::SECRET_KEY_BYTES)
+    }
+
+    /// The number of bytes required to store the ciphertext resulting from the encryption
+    pub fn ciphertext_bytes(self) -> usize {
+        with_parameter_set!(self, P => P::CIPHERTEXT_BYTES)
+    }
+
+    /// KEM Keypair generation for this parameter set.
+    ///
+    /// Writes the public key to `public_key_buf` and the secret key to `secret_key_buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffers do not have [`Variant::public_key_bytes`] and
+    /// [`Variant::secret_key_bytes`] bytes respectively, or if `rng` fails.
+    pub fn keypair<R: CryptoRng + RngCore>(
+        self,
+        public_key_buf: &mut [u8],
+        secret_key_buf: &mut [u8],
+        rng: &mut R,
+    ) {
+        assert_eq!(public_key_buf.len(), self.public_key_bytes());
+        assert_eq!(secret_key_buf.len(), self.secret_key_bytes());
+
+        with_parameter_set!(self, P => {
+            generic::expect_rng(operations::crypto_kem_keypair::<P, R>(
+                public_key_buf,
+                secret_key_buf,
+                rng,
+                &mut PkMatrix::new(),
+            ));
+        });
+    }
+
+    /// Fallible KEM Keypair generation for this parameter set.
+    ///
+    /// Like [`Variant::keypair`], but returns [`Error::Rng`] if `rng` fails. Both buffers
+    /// are then set to all 0's, so no partially generated key material is left behind.
+    /// Returns [`Error::Allocation`] without touching the buffers if the scratch space of
+    /// key generation can not be allocated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffers do not have [`Variant::public_key_bytes`] and
+    /// [`Variant::secret_key_bytes`] bytes respectively.
+    pub fn try_keypair<R: CryptoRng + RngCore>(
+        self,
+        public_key_buf: &mut [u8],
+        secret_key_buf: &mut [u8],
+        rng: &mut R,
+    ) -> Result<(), Error> {
+        assert_eq!(public_key_buf.len(), self.public_key_bytes());
+        assert_eq!(secret_key_buf.len(), self.secret_key_bytes());
+
+        with_parameter_set!(self, P => {
+            operations::crypto_kem_keypair::<P, R>(
+                public_key_buf,
+                secret_key_buf,
+                rng,
+                &mut PkMatrix::try_new()?,
+            )?;
+        });
+        Ok(())
+    }
+
+    /// Deterministic KEM Keypair generation for this parameter set.
+    ///
+    /// Like [`Variant::keypair`], but derives the keys from the 32 bytes `seed`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffers do not have [`Variant::public_key_bytes`] and
+    /// [`Variant::secret_key_bytes`] bytes respectively.
+    pub fn keypair_from_seed(
+        self,
+        public_key_buf: &mut [u8],
+        secret_key_buf: &mut [u8],
+        seed: &[u8; 32],
+    ) {
+        assert_eq!(public_key_buf.len(), self.public_key_bytes());
+        assert_eq!(secret_key_buf.len(), self.secret_key_bytes());
+
+        with_parameter_set!(self, P => {
+            operations::crypto_kem_keypair_from_seed::<P>(
+                Some(public_key_buf),
+                secret_key_buf,
+                seed,
+                &mut PkMatrix::new(),
+            )
+        })
+    }
+
+    /// KEM Encapsulation for this parameter set.
+    ///
+    /// Given a public key `public_key`, write the ciphertext to `ciphertext_buf` and the
+    /// shared secret to `shared_secret_buf`. If `public_key` is malformed, see
+    /// [`PublicKey::validate`](crate::PublicKey::validate), the syndrome part of the
+    /// ciphertext and the shared secret are all zero. Use [`Variant::try_encapsulate`] to
+    /// get an error instead.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `public_key` or `ciphertext_buf` does not have [`Variant::public_key_bytes`]
+    /// and [`Variant::ciphertext_bytes`] bytes respectively, or if `rng` fails.
+    pub fn encapsulate<R: CryptoRng + RngCore>(
+        self,
+        public_key: &[u8],
+        ciphertext_buf: &mut [u8],
+        shared_secret_buf: &mut [u8; CRYPTO_BYTES],
+        rng: &mut R,
+    ) {
+        assert_eq!(public_key.len(), self.public_key_bytes());
+        assert_eq!(ciphertext_buf.len(), self.ciphertext_bytes());
+
+        with_parameter_set!(self, P => {
+            generic::expect_rng(operations::crypto_kem_enc::<P, R>(
+                ciphertext_buf,
+                shared_secret_buf,
+                public_key,
+                rng,
+            ));
+        })
+    }
+
+    /// Fallible KEM Encapsulation for this parameter set.
+    ///
+    /// Like [`Variant::encapsulate`], but returns an error without writing to the buffers
+    /// if `public_key` is malformed, see [`PublicKey::validate`](crate::PublicKey::validate).
+    /// If `rng` fails, returns [`Error::Rng`] and sets both buffers to all 0's.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `public_key` or `ciphertext_buf` does not have [`Variant::public_key_bytes`]
+    /// and [`Variant::ciphertext_bytes`] bytes respectively.
+    pub fn try_encapsulate<R: CryptoRng + RngCore>(
+        self,
+        public_key: &[u8],
+        ciphertext_buf: &mut [u8],
+        shared_secret_buf: &mut [u8; CRYPTO_BYTES],
+        rng: &mut R,
+    ) -> Result<(), Error> {
+        assert_eq!(public_key.len(), self.public_key_bytes());
+        assert_eq!(ciphertext_buf.len(), self.ciphertext_bytes());
+
+        with_parameter_set!(self, P => {
+            validate::check_pk::<P>(public_key)?;
+            operations::crypto_kem_enc::<P, R>(ciphertext_buf, shared_secret_buf, public_key, rng)?;
+        });
+        Ok(())
+    }
+
+    /// Derandomized KEM Encapsulation for this parameter set.
+    ///
+    /// Like [`Variant::try_encapsulate`], but derives the error vector from the 32 bytes
+    /// `coins`, see [`generic::encapsulate_derand`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `public_key` or `ciphertext_buf` does not have [`Variant::public_key_bytes`]
+    /// and [`Variant::ciphertext_bytes`] bytes respectively.
+    pub fn encapsulate_derand(
+        self,
+        public_key: &[u8],
+        coins: &[u8; 32],
+        ciphertext_buf: &mut [u8],
+        shared_secret_buf: &mut [u8; CRYPTO_BYTES],
+    ) -> Result<(), Error> {
+        let mut rng = operations::coins_rng(coins);
+        self.try_encapsulate(public_key, ciphertext_buf, shared_secret_buf, &mut rng)
+    }
+
+    /// KEM Decapsulation for this parameter set.
+    ///
+    /// Given a secret key `secret_key` and a ciphertext `ciphertext`, write the shared
+    /// secret negotiated by both parties to `shared_secret_buf`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ciphertext` or `secret_key` does not have [`Variant::ciphertext_bytes`]
+    /// and [`Variant::secret_key_bytes`] bytes respectively.
+    pub fn decapsulate(
+        self,
+        ciphertext: &[u8],
+        secret_key: &[u8],
+        shared_secret_buf: &mut [u8; CRYPTO_BYTES],
+    ) {
+        assert_eq!(ciphertext.len(), self.ciphertext_bytes());
+        assert_eq!(secret_key.len(), self.secret_key_bytes());
+
+        with_parameter_set!(self, P => {
+            operations::crypto_kem_dec::<P>(shared_secret_buf, ciphertext, secret_key);
+        })
+    }
+
+    /// Fallible KEM Decapsulation for this parameter set.
+    ///
+    /// Like [`Variant::decapsulate`], but returns [`Error::InvalidCiphertext`] if the padding
+    /// bits of `ciphertext` are not zero (mceliece6960119 only), see
+    /// [`try_decapsulate`](crate::try_decapsulate). A ciphertext which does not decode still
+    /// results in a pseudo-random shared secret (implicit rejection), not in an error.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ciphertext` or `secret_key` does not have [`Variant::ciphertext_bytes`]
+    /// and [`Variant::secret_key_bytes`] bytes respectively.
+    pub fn try_decapsulate(
+        self,
+        ciphertext: &[u8],
+        secret_key: &[u8],
+        shared_secret_buf: &mut [u8; CRYPTO_BYTES],
+    ) -> Result<(), Error> {
+        assert_eq!(ciphertext.len(), self.ciphertext_bytes());
+        assert_eq!(secret_key.len(), self.secret_key_bytes());
+
+        let padding_ok = with_parameter_set!(self, P => {
+            operations::crypto_kem_dec::<P>(shared_secret_buf, ciphertext, secret_key)
+        });
+
+        // the padding bits are public, so branching on them leaks nothing
+        if padding_ok != 0 {
+            return Err(Error::InvalidCiphertext);
+        }
+
+        Ok(())
+    }
+
+    /// KEM Keypair generation for this parameter set, with the keys stored on the heap.
+    ///
+    /// See [`keypair_boxed`](crate::keypair_boxed).
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn keypair_boxed<R: CryptoRng + RngCore>(
+        self,
+        rng: &mut R,
+    ) -> (AnyPublicKey<'static>, AnySecretKey<'static>) {
+        with_parameter_set!(self, P => {
+            let (public_key, secret_key) = crate::generic::keypair_boxed::<P, R>(rng);
+            (public_key.into(), secret_key.into())
+        })
+    }
+
+    /// Fallible KEM Keypair generation for this parameter set, with the keys stored on the
+    /// heap.
+    ///
+    /// See [`try_keypair_boxed`](crate::try_keypair_boxed).
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn try_keypair_boxed<R: CryptoRng + RngCore>(
+        self,
+        rng: &mut R,
+    ) -> Result<(AnyPublicKey<'static>, AnySecretKey<'static>), Error> {
+        with_parameter_set!(self, P => {
+            let (public_key, secret_key) = crate::generic::try_keypair_boxed::<P, R>(rng)?;
+            Ok((public_key.into(), secret_key.into()))
+        })
+    }
+
+    /// Deterministic KEM Keypair generation for this parameter set, with the keys stored on
+    /// the heap.
+    ///
+    /// See [`keypair_from_seed_boxed`](crate::keypair_from_seed_boxed).
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn keypair_from_seed_boxed(
+        self,
+        seed: &[u8; 32],
+    ) -> (AnyPublicKey<'static>, AnySecretKey<'static>) {
+        with_parameter_set!(self, P => {
+            let (public_key, secret_key) = crate::generic::keypair_from_seed_boxed::<P>(seed);
+            (public_key.into(), secret_key.into())
+        })
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::{
+        DefaultParameterSet, CRYPTO_CIPHERTEXTBYTES, CRYPTO_PRIMITIVE, CRYPTO_PUBLICKEYBYTES,
+        CRYPTO_SECRETKEYBYTES,
+    };
+    use crate::params::{Mceliece6960119, ParameterSet};
+
+    #[test]
+    fn test_names_roundtrip() {
+        for variant in Variant::ALL {
+            assert_eq!(Variant::from_name(variant.name()), Some(variant));
+            assert_eq!(format!("{}", variant), variant.name());
+        }
+        assert_eq!(Variant::from_name("mceliece1234"), None);
+    }
+
+    #[test]
+    fn test_default_parameter_set() {
+        let variant = DefaultParameterSet::VARIANT;
+        assert_eq!(variant.name(), CRYPTO_PRIMITIVE);
+        assert_eq!(variant.public_key_bytes(), CRYPTO_PUBLICKEYBYTES);
+        assert_eq!(variant.secret_key_bytes(), CRYPTO_SECRETKEYBYTES);
+        assert_eq!(variant.ciphertext_bytes(), CRYPTO_CIPHERTEXTBYTES);
+    }
+
+    #[test]
+    fn test_sizes() {
+        let sizes = Variant::ALL.map(|variant| {
+            (
+                variant.public_key_bytes(),
+                variant.secret_key_bytes(),
+                variant.ciphertext_bytes(),
+            )
+        });
+        assert_eq!(
+            sizes,
+            [
+                (261120, 6492, 96),
+                (261120, 6492, 96),
+                (524160, 13608, 156),
+                (524160, 13608, 156),
+                (1044992, 13932, 208),
+                (1044992, 13932, 208),
+                (1047319, 13948, 194),
+                (1047319, 13948, 194),
+                (1357824, 14120, 208),
+                (1357824, 14120, 208),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_try_encapsulate_decapsulate() {
+        fn run() {
+            let variant = Variant::Mceliece6960119;
+            let mut rng = rand::thread_rng();
+
+            let mut pk = vec![0u8; variant.public_key_bytes()];
+            let mut sk = vec![0u8; variant.secret_key_bytes()];
+            variant.keypair_from_seed(&mut pk, &mut sk, &[7u8; 32]);
+
+            let mut ct = vec![0u8; variant.ciphertext_bytes()];
+            let mut ss_bob = [0u8; CRYPTO_BYTES];
+            let mut ss_alice = [0u8; CRYPTO_BYTES];
+            variant
+                .try_encapsulate(&pk, &mut ct, &mut ss_bob, &mut rng)
+                .unwrap();
+            variant.try_decapsulate(&ct, &sk, &mut ss_alice).unwrap();
+            assert_eq!(ss_bob, ss_alice);
+
+            // the last bits of the ciphertext are padding
+            ct[variant.ciphertext_bytes() - 1] |= 0x80;
+            let result = variant.try_decapsulate(&ct, &sk, &mut ss_alice);
+            assert_eq!(result, Err(Error::InvalidCiphertext));
+
+            // the last bit of the first row of the public key is padding
+            pk[Mceliece6960119::PK_ROW_BYTES - 1] |= 0x80;
+            let result = variant.try_encapsulate(&pk, &mut ct, &mut ss_bob, &mut rng);
+            assert_eq!(
+                result,
+                Err(Error::InvalidPublicKey(crate::PublicKeyError::Padding))
+            );
+            variant.encapsulate(&pk, &mut ct, &mut ss_bob, &mut rng);
+            assert_eq!(ss_bob, [0u8; CRYPTO_BYTES]);
+        }
+
+        std::thread::Builder::new()
+            .stack_size(4 * 1024 * 1024)
+            .spawn(run)
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
@@ -0,0 +1,337 @@
+//! Bitsliced field arithmetic for the decoder (the backend of feature `vec`)
+//!
+//! Like the `vec` implementation of Classic McEliece, this module processes 64 field elements
+//! at once: bit `j` of element `i` is bit `i` of word `j` of a [`Bitsliced`] value. A field
+//! multiplication then takes `m²` AND and XOR operations on `u64` words for all 64 elements,
+//! without any table lookups or branches on secret data.
+//!
+//! [`butterflies`], [`butterflies_tr`], [`synd_weights`] and [`root`] compute exactly the same
+//! outputs as their counterparts in [`crate::fft`], [`crate::synd`] and [`crate::root`], which
+//! call them if the feature `vec` is enabled.
+
+use crate::fft::fft;
+use crate::gf::Gf;
+use crate::params::{ParameterSet, MAX_GFBITS, MAX_SYS_T};
+use crate::transpose;
+
+/// 64 field elements in bitsliced representation. Only the first `P::GFBITS` words are used.
+type Bitsliced = [u64; MAX_GFBITS];
+
+/// Multiplies the 64 pairs of field elements in `a` and `b`
+fn vec_mul<P: ParameterSet>(a: &Bitsliced, b: &Bitsliced) -> Bitsliced {
+    let mut buf = [0u64; 2 * MAX_GFBITS - 1];
+
+    for i in 0..P::GFBITS {
+        for j in 0..P::GFBITS {
+            buf[i + j] ^= a[i] & b[j];
+        }
+    }
+
+    // reduction modulo the field polynomial x^12 + x^3 + 1 or x^13 + x^4 + x^3 + x + 1
+    for i in (P::GFBITS..2 * P::GFBITS - 1).rev() {
+        if P::GFBITS == 12 {
+            buf[i - 12 + 3] ^= buf[i];
+            buf[i - 12] ^= buf[i];
+        } else {
+            buf[i - 13 + 4] ^= buf[i];
+            buf[i - 13 + 3] ^= buf[i];
+            buf[i - 13 + 1] ^= buf[i];
+            buf[i - 13] ^= buf[i];
+        }
+    }
+
+    let mut out = [0u64; MAX_GFBITS];
+    out[..P::GFBITS].copy_from_slice(&buf[..P::GFBITS]);
+    out
+}
+
+/// Computes `a^(2^m - 2)`, which is the inverse of each non-zero element and 0 for 0,
+/// like [`gf_inv`](crate::gf::gf_inv)
+fn vec_inv<P: ParameterSet>(a: &Bitsliced) -> Bitsliced {
+    // a^(2^k - 1) for k = 1, ..., m - 1
+    let mut out = *a;
+    for _ in 2..P::GFBITS {
+        out = vec_mul::<P>(&vec_mul::<P>(&out, &out), a);
+    }
+    vec_mul::<P>(&out, &out)
+}
+
+/// Returns 64 copies of the field element `a`
+fn broadcast(a: Gf) -> Bitsliced {
+    let mut out = [0u64; MAX_GFBITS];
+    for (j, word) in out.iter_mut().enumerate() {
+        *word = 0u64.wrapping_sub(((a >> j) & 1) as u64);
+    }
+    out
+}
+
+/// Converts up to 64 field elements into bitsliced representation. Missing elements are 0.
+fn bitslice<P: ParameterSet>(elements: &[Gf]) -> Bitsliced {
+    debug_assert!(elements.len() <= 64);
+
+    let mut buf = [0u64; 64];
+    for (word, &element) in buf.iter_mut().zip(elements) {
+        *word = element as u64;
+    }
+    transpose::transpose_64x64_inplace(&mut buf);
+
+    let mut out = [0u64; MAX_GFBITS];
+    out[..P::GFBITS].copy_from_slice(&buf[..P::GFBITS]);
+    out
+}
+
+/// Converts the first `out.len()` elements of `a` back into the usual representation
+fn unbitslice<P: ParameterSet>(out: &mut [Gf], a: &Bitsliced) {
+    debug_assert!(out.len() <= 64);
+
+    let mut buf = [0u64; 64];
+    buf[..P::GFBITS].copy_from_slice(&a[..P::GFBITS]);
+    transpose::transpose_64x64_inplace(&mut buf);
+
+    for (element, &word) in out.iter_mut().zip(buf.iter()) {
+        *element = word as Gf;
+    }
+}
+
+/// Evaluates the polynomial with the bitsliced coefficients `f` at the 64 elements of `a`
+fn eval<P: ParameterSet>(f: &[Bitsliced], a: &Bitsliced) -> Bitsliced {
+    debug_assert_eq!(f.len(), P::SYS_T + 1);
+
+    let mut r = f[P::SYS_T];
+    for coefficient in f[..P::SYS_T].iter().rev() {
+        r = vec_mul::<P>(&r, a);
+        for (word, c) in r.iter_mut().zip(coefficient) {
+            *word ^= c;
+        }
+    }
+    r
+}
+
+/// Converts the `1 << GFBITS` elements of `values` into bitsliced representation
+fn bitslice_all<P: ParameterSet>(out: &mut [Bitsliced], values: &[Gf]) {
+    for (itr_out, chunk) in out.iter_mut().zip(values.chunks(64)) {
+        *itr_out = bitslice::<P>(chunk);
+    }
+}
+
+/// Converts bitsliced values back into the `1 << GFBITS` elements of `out`
+fn unbitslice_all<P: ParameterSet>(out: &mut [Gf], values: &[Bitsliced]) {
+    for (chunk, itr_values) in out.chunks_mut(64).zip(values) {
+        unbitslice::<P>(chunk, itr_values);
+    }
+}
+
+/// Applies `op(x0, x1, a)` to the pairs of elements at distance `h` in blocks of `2 h`,
+/// where `a` holds the element `span[k]` for the `k`-th pair of a block
+fn butterfly_level<P: ParameterSet>(
+    values: &mut [Bitsliced],
+    span: &[Gf],
+    op: fn(&mut Bitsliced, &mut Bitsliced, &Bitsliced),
+) {
+    let h = span.len();
+
+    if h >= 64 {
+        let words = h / 64;
+        let mut a_buf = [[0u64; MAX_GFBITS]; (1 << MAX_GFBITS) / 128];
+        let a = &mut a_buf[..words];
+        for (itr_a, chunk) in a.iter_mut().zip(span.chunks(64)) {
+            *itr_a = bitslice::<P>(chunk);
+        }
+
+        for block in values.chunks_mut(2 * words) {
+            let (v0, v1) = block.split_at_mut(words);
+            for ((x0, x1), itr_a) in v0.iter_mut().zip(v1.iter_mut()).zip(a.iter()) {
+                op(x0, x1, itr_a);
+            }
+        }
+    } else {
+        // both elements of a pair are in the same word: the lanes `i` with bit `h` of `i`
+        // cleared hold `x0`, the others `x1`
+        let mut lanes = [0 as Gf; 64];
+        let mut mask = 0u64;
+        for (i, lane) in lanes.iter_mut().enumerate() {
+            if i & h == 0 {
+                *lane = span[i % h];
+                mask |= 1 << i;
+            }
+        }
+        let a = bitslice::<P>(&lanes);
+
+        for word in values.iter_mut() {
+            let mut x0 = [0u64; MAX_GFBITS];
+            let mut x1 = [0u64; MAX_GFBITS];
+            for j in 0..P::GFBITS {
+                x0[j] = word[j] & mask;
+                x1[j] = (word[j] >> h) & mask;
+            }
+            op(&mut x0, &mut x1, &a);
+            for j in 0..P::GFBITS {
+                word[j] = (x0[j] & mask) | ((x1[j] & mask) << h);
+            }
+        }
+    }
+}
+
+/// Bitsliced version of the butterflies of [`crate::fft::fft`]. `span(d, a)` fills `a` with
+/// the multipliers of level `d`.
+pub(crate) fn butterflies<P: ParameterSet>(
+    values: &mut [Gf],
+    depth: usize,
+    span: impl Fn(usize, &mut [Gf]),
+) {
+    let mut buf = [[0u64; MAX_GFBITS]; (1 << MAX_GFBITS) / 64];
+    let bitsliced = &mut buf[..(1 << P::GFBITS) / 64];
+    bitslice_all::<P>(bitsliced, values);
+
+    let mut a_buf = [0 as Gf; 1 << (MAX_GFBITS - 1)];
+    for d in (0..depth).rev() {
+        let a = &mut a_buf[..1 << (P::GFBITS - d - 1)];
+        span(d, a);
+        butterfly_level::<P>(bitsliced, a, |x0, x1, a| {
+            let product = vec_mul::<P>(a, x1);
+            for j in 0..P::GFBITS {
+                x0[j] ^= product[j];
+                x1[j] ^= x0[j];
+            }
+        });
+    }
+
+    unbitslice_all::<P>(values, bitsliced);
+}
+
+/// Bitsliced version of the butterflies of [`crate::fft::fft_tr`]
+pub(crate) fn butterflies_tr<P: ParameterSet>(
+    values: &mut [Gf],
+    depth: usize,
+    span: impl Fn(usize, &mut [Gf]),
+) {
+    let mut buf = [[0u64; MAX_GFBITS]; (1 << MAX_GFBITS) / 64];
+    let bitsliced = &mut buf[..(1 << P::GFBITS) / 64];
+    bitslice_all::<P>(bitsliced, values);
+
+    let mut a_buf = [0 as Gf; 1 << (MAX_GFBITS - 1)];
+    for d in 0..depth {
+        let a = &mut a_buf[..1 << (P::GFBITS - d - 1)];
+        span(d, a);
+        butterfly_level::<P>(bitsliced, a, |x0, x1, a| {
+            for j in 0..P::GFBITS {
+                x0[j] ^= x1[j];
+            }
+            let product = vec_mul::<P>(a, x0);
+            for j in 0..P::GFBITS {
+                x1[j] ^= product[j];
+            }
+        });
+    }
+
+    unbitslice_all::<P>(values, bitsliced);
+}
+
+/// Bitsliced version of [`crate::synd::synd_weights`]: given Goppa polynomial `f`, compute
+/// `out[a] = 1 / f(a)^2` for all field elements `a`
+pub(crate) fn synd_weights<P: ParameterSet>(out: &mut [Gf], f: &[Gf]) {
+    debug_assert_eq!(out.len(), 1 << P::GFBITS);
+    debug_assert_eq!(f.len(), P::SYS_T + 1);
+
+    fft::<P>(out, f);
+
+    for chunk in out.chunks_mut(64) {
+        let e = bitslice::<P>(chunk);
+        unbitslice::<P>(chunk, &vec_inv::<P>(&vec_mul::<P>(&e, &e)));
+    }
+}
+
+/// Bitsliced version of [`crate::root::root`]: given polynomial `f` and a list of field
+/// elements `l`, return `out`, the evaluations `[ f(a) for a in L ]`
+pub(crate) fn root<P: ParameterSet>(out: &mut [Gf], f: &[Gf], l: &[Gf]) {
+    debug_assert_eq!(out.len(), l.len());
+    debug_assert_eq!(f.len(), P::SYS_T + 1);
+
+    let mut f_buf = [[0u64; MAX_GFBITS]; MAX_SYS_T + 1];
+    let f_bitsliced = &mut f_buf[..=P::SYS_T];
+    for (bitsliced, &coefficient) in f_bitsliced.iter_mut().zip(f) {
+        *bitsliced = broadcast(coefficient);
+    }
+
+    for (images, support) in out.chunks_mut(64).zip(l.chunks(64)) {
+        let a = bitslice::<P>(support);
+        unbitslice::<P>(images, &eval::<P>(f_bitsliced, &a));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gf::{gf_inv, gf_mul};
+    use crate::params::{Mceliece348864, Mceliece6960119, MAX_SYS_N};
+    use crate::root::eval as eval_ref;
+
+    /// Pseudo-random field elements from a xorshift generator
+    fn elements<P: ParameterSet>(out: &mut [Gf], mut state: u64) {
+        for element in out.iter_mut() {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            *element = state as Gf & P::GFMASK as Gf;
+        }
+    }
+
+    fn check_field<P: ParameterSet>() {
+        let mut a = [0; 64];
+        let mut b = [0; 64];
+        elements::<P>(&mut a, 1);
+        elements::<P>(&mut b, 2);
+        a[0] = 0;
+
+        let product = vec_mul::<P>(&bitslice::<P>(&a), &bitslice::<P>(&b));
+        let inverse = vec_inv::<P>(&bitslice::<P>(&a));
+        let mut products = [0; 64];
+        let mut inverses = [0; 64];
+        unbitslice::<P>(&mut products, &product);
+        unbitslice::<P>(&mut inverses, &inverse);
+        for i in 0..64 {
+            assert_eq!(products[i], gf_mul::<P>(a[i], b[i]));
+            assert_eq!(inverses[i], gf_inv::<P>(a[i]));
+        }
+    }
+
+    fn check_decoder<P: ParameterSet>() {
+        let mut f = [0; MAX_SYS_T + 1];
+        let f = &mut f[..=P::SYS_T];
+        let mut l = [0; MAX_SYS_N];
+        let l = &mut l[..P::SYS_N];
+        elements::<P>(f, 3);
+        elements::<P>(l, 4);
+        f[P::SYS_T] = 1;
+        // a root of f in the support
+        l[5] = 0;
+        f[0] = 0;
+
+        let mut images = [0; MAX_SYS_N];
+        root::<P>(&mut images[..P::SYS_N], f, l);
+        for i in 0..P::SYS_N {
+            assert_eq!(images[i], eval_ref::<P>(f, l[i]));
+        }
+        assert_eq!(images[5], 0);
+    }
+
+    #[test]
+    fn test_vec_field_arithmetic() {
+        check_field::<Mceliece348864>();
+        check_field::<Mceliece6960119>();
+    }
+
+    #[test]
+    fn test_vec_decoder() {
+        std::thread::Builder::new()
+            .stack_size(4 * 1024 * 1024)
+            .spawn(|| {
+                check_decoder::<Mceliece348864>();
+                // SYS_N = 6960 is not a multiple of 64
+                check_decoder::<Mceliece6960119>();
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+}
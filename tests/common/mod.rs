@@ -0,0 +1,14 @@
+//! Helpers shared by the integration tests
+
+use std::thread;
+
+/// Runs `f` on a thread with a stack large enough for the key buffers of the large
+/// parameter sets, and propagates its panics.
+pub fn run_with_stack(f: fn()) {
+    thread::Builder::new()
+        .stack_size(4 * 1024 * 1024)
+        .spawn(f)
+        .unwrap()
+        .join()
+        .unwrap();
+}
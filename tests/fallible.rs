@@ -0,0 +1,152 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use classic_mceliece_rust::generic::{
+    keypair_boxed, keypair_from_seed_boxed, try_decapsulate_boxed, try_encapsulate,
+    try_encapsulate_boxed, try_keypair, try_keypair_boxed,
+};
+use classic_mceliece_rust::{
+    Ciphertext, Error, Mceliece348864, Mceliece6960119, ParameterSet, CRYPTO_BYTES,
+};
+use common::run_with_stack;
+use rand::{CryptoRng, RngCore};
+use std::num::NonZeroU32;
+
+/// A random number generator which always fails, like a hardware generator without entropy
+struct FailingRng;
+
+impl RngCore for FailingRng {
+    fn next_u32(&mut self) -> u32 {
+        panic!("FailingRng can not generate numbers")
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        panic!("FailingRng can not generate numbers")
+    }
+
+    fn fill_bytes(&mut self, _dest: &mut [u8]) {
+        panic!("FailingRng can not generate numbers")
+    }
+
+    fn try_fill_bytes(&mut self, _dest: &mut [u8]) -> Result<(), rand::Error> {
+        Err(NonZeroU32::new(rand::Error::CUSTOM_START).unwrap().into())
+    }
+}
+
+impl CryptoRng for FailingRng {}
+
+#[test]
+fn rng_failure_is_reported() {
+    run_with_stack(|| {
+        type P = Mceliece348864;
+
+        let mut pk_buf = Box::new([0u8; P::PUBLIC_KEY_BYTES]);
+        let mut sk_buf = [0u8; P::SECRET_KEY_BYTES];
+        let result = try_keypair::<P, _>(&mut pk_buf, &mut sk_buf, &mut FailingRng);
+        assert_eq!(result.err(), Some(Error::Rng));
+        assert_eq!(
+            try_keypair_boxed::<P, _>(&mut FailingRng).err(),
+            Some(Error::Rng)
+        );
+
+        let (public_key, _) = keypair_from_seed_boxed::<P>(&[1u8; 32]);
+        let mut ss_buf = [0u8; CRYPTO_BYTES];
+        let result = try_encapsulate(&public_key, &mut ss_buf, &mut FailingRng);
+        assert_eq!(result.err(), Some(Error::Rng));
+    });
+}
+
+#[test]
+#[should_panic]
+fn infallible_keypair_panics_on_rng_failure() {
+    let _ = keypair_boxed::<Mceliece348864, _>(&mut FailingRng);
+}
+
+#[test]
+fn try_decapsulate_rejects_ciphertext_padding() {
+    run_with_stack(|| {
+        type P = Mceliece6960119;
+        let mut rng = rand::thread_rng();
+
+        let (public_key, secret_key) = try_keypair_boxed::<P, _>(&mut rng).unwrap();
+        let (ciphertext, shared_secret_bob) = try_encapsulate_boxed(&public_key, &mut rng).unwrap();
+        let shared_secret_alice = try_decapsulate_boxed(&ciphertext, &secret_key).unwrap();
+        assert_eq!(shared_secret_bob.as_array(), shared_secret_alice.as_array());
+
+        // the last bits of the ciphertext are padding
+        let mut ct_buf = *ciphertext.as_array();
+        ct_buf[P::CIPHERTEXT_BYTES - 1] |= 0x80;
+        let result = try_decapsulate_boxed(&Ciphertext::<P>::from(ct_buf), &secret_key);
+        assert_eq!(result.err(), Some(Error::InvalidCiphertext));
+
+        // implicit rejection: a ciphertext which does not decode is no error
+        let mut ct_buf = *ciphertext.as_array();
+        ct_buf[0] ^= 1;
+        let shared_secret_rejected =
+            try_decapsulate_boxed(&Ciphertext::<P>::from(ct_buf), &secret_key).unwrap();
+        assert_ne!(
+            shared_secret_bob.as_array(),
+            shared_secret_rejected.as_array()
+        );
+    });
+}
+
+/// A random number generator which provides `remaining` bytes and then fails
+struct ExhaustedRng {
+    remaining: usize,
+}
+
+impl RngCore for ExhaustedRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut buf = [0u8; 4];
+        self.try_fill_bytes(&mut buf).unwrap();
+        u32::from_le_bytes(buf)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut buf = [0u8; 8];
+        self.try_fill_bytes(&mut buf).unwrap();
+        u64::from_le_bytes(buf)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).unwrap()
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        if dest.len() > self.remaining {
+            self.remaining = 0;
+            return Err(NonZeroU32::new(rand::Error::CUSTOM_START).unwrap().into());
+        }
+        self.remaining -= dest.len();
+        dest.fill(0x5A);
+        Ok(())
+    }
+}
+
+impl CryptoRng for ExhaustedRng {}
+
+#[test]
+fn rng_failure_leaves_no_key_material() {
+    run_with_stack(|| {
+        type P = Mceliece348864;
+
+        let mut pk_buf = Box::new([0xAAu8; P::PUBLIC_KEY_BYTES]);
+        let mut sk_buf = [0xAAu8; P::SECRET_KEY_BYTES];
+        let mut rng = ExhaustedRng { remaining: 16 };
+        let result = try_keypair::<P, _>(&mut pk_buf, &mut sk_buf, &mut rng);
+        assert_eq!(result.err(), Some(Error::Rng));
+        assert!(pk_buf.iter().all(|&b| b == 0));
+        assert!(sk_buf.iter().all(|&b| b == 0));
+
+        // the constant output of ExhaustedRng has repeated indices, so the error vector
+        // is resampled until the generator is exhausted
+        let (public_key, _) = keypair_from_seed_boxed::<P>(&[1u8; 32]);
+        let mut ss_buf = [0xAAu8; CRYPTO_BYTES];
+        let mut rng = ExhaustedRng { remaining: 4096 };
+        let result = try_encapsulate(&public_key, &mut ss_buf, &mut rng);
+        assert_eq!(result.err(), Some(Error::Rng));
+        assert_eq!(ss_buf, [0u8; CRYPTO_BYTES]);
+    });
+}
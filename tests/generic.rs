@@ -0,0 +1,273 @@
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use classic_mceliece_rust::generic::{
+    decapsulate, decapsulate_boxed, encapsulate, encapsulate_boxed, keypair, keypair_boxed,
+};
+use classic_mceliece_rust::{
+    Mceliece348864, Mceliece348864f, Mceliece460896f, ParameterSet, CRYPTO_BYTES,
+};
+use common::run_with_stack;
+
+fn run_boxed<P: ParameterSet>() {
+    let mut rng = rand::thread_rng();
+
+    let (public_key, secret_key) = keypair_boxed::<P, _>(&mut rng);
+    assert_eq!(public_key.as_ref().len(), P::PUBLIC_KEY_BYTES);
+    assert_eq!(secret_key.as_ref().len(), P::SECRET_KEY_BYTES);
+
+    let (ciphertext, shared_secret_bob) = encapsulate_boxed(&public_key, &mut rng);
+    assert_eq!(ciphertext.as_ref().len(), P::CIPHERTEXT_BYTES);
+
+    let shared_secret_alice = decapsulate_boxed(&ciphertext, &secret_key);
+    assert_eq!(shared_secret_bob.as_array(), shared_secret_alice.as_array());
+}
+
+#[test]
+fn several_parameter_sets_in_one_binary() {
+    run_with_stack(|| {
+        run_boxed::<Mceliece348864f>();
+        run_boxed::<Mceliece460896f>();
+    });
+}
+
+#[test]
+fn borrowed_buffers() {
+    fn run() {
+        let mut rng = rand::thread_rng();
+
+        let mut pk_buf = Box::new([0u8; Mceliece348864::PUBLIC_KEY_BYTES]);
+        let mut sk_buf = [0u8; Mceliece348864::SECRET_KEY_BYTES];
+        let (public_key, secret_key) =
+            keypair::<Mceliece348864, _>(&mut pk_buf, &mut sk_buf, &mut rng);
+
+        let mut ss_bob = [0u8; CRYPTO_BYTES];
+        let (ciphertext, shared_secret_bob) = encapsulate(&public_key, &mut ss_bob, &mut rng);
+        let mut ss_alice = [0u8; CRYPTO_BYTES];
+        let shared_secret_alice = decapsulate(&ciphertext, &secret_key, &mut ss_alice);
+        assert_eq!(shared_secret_bob.as_array(), shared_secret_alice.as_array());
+    }
+
+    run_with_stack(run);
+}
+
+#[test]
+fn keypair_from_seed_is_deterministic() {
+    use classic_mceliece_rust::generic::keypair_from_seed_boxed;
+
+    run_with_stack(|| {
+        let seed = [42u8; 32];
+        let (pk1, sk1) = keypair_from_seed_boxed::<Mceliece348864>(&seed);
+        let (pk2, sk2) = keypair_from_seed_boxed::<Mceliece348864>(&seed);
+        assert_eq!(pk1.as_array(), pk2.as_array());
+        assert_eq!(sk1.as_array(), sk2.as_array());
+
+        let (pk3, _) = keypair_from_seed_boxed::<Mceliece348864>(&[43u8; 32]);
+        assert_ne!(pk1.as_array(), pk3.as_array());
+    });
+}
+
+#[test]
+fn secret_key_from_seed_roundtrip() {
+    use classic_mceliece_rust::{SecretKey, SecretSeed};
+
+    run_with_stack(|| {
+        let mut rng = rand::thread_rng();
+        let (_, secret_key) = keypair_boxed::<Mceliece348864f, _>(&mut rng);
+
+        let seed = SecretSeed::from(*secret_key.seed().as_array());
+        let restored = SecretKey::<Mceliece348864f>::from_seed_boxed(&seed);
+        assert_eq!(secret_key.as_array(), restored.as_array());
+
+        let mut sk_buf = [0u8; Mceliece348864f::SECRET_KEY_BYTES];
+        let restored = SecretKey::<Mceliece348864f>::from_seed(&seed, &mut sk_buf);
+        assert_eq!(secret_key.as_array(), restored.as_array());
+    });
+}
+
+#[test]
+fn public_key_from_secret_key() {
+    use classic_mceliece_rust::Mceliece6960119f;
+
+    fn run<P: ParameterSet>() {
+        let mut rng = rand::thread_rng();
+        let (public_key, secret_key) = keypair_boxed::<P, _>(&mut rng);
+        let derived = secret_key.public_key().unwrap();
+        assert_eq!(public_key.as_array(), derived.as_array());
+    }
+
+    run_with_stack(|| {
+        run::<Mceliece348864>();
+        run::<Mceliece6960119f>();
+    });
+}
+
+#[test]
+fn secret_key_validation() {
+    use classic_mceliece_rust::generic::keypair_from_seed_boxed;
+    use classic_mceliece_rust::{ByteArray, Error, Mceliece6960119, SecretKey, SecretKeyError};
+
+    fn corrupted<P: ParameterSet>(
+        secret_key: &SecretKey<'_, P>,
+        corrupt: impl FnOnce(&mut [u8]),
+    ) -> Result<(), Error> {
+        let mut sk_buf = P::SecretKeyArray::zeroed_boxed();
+        (*sk_buf).as_mut().copy_from_slice(secret_key.as_ref());
+        corrupt((*sk_buf).as_mut());
+        SecretKey::<P>::from(sk_buf).validate()
+    }
+
+    fn run<P: ParameterSet>() {
+        let irr_bytes = 2 * P::SYS_T;
+        let (public_key, secret_key) = keypair_from_seed_boxed::<P>(&[7u8; 32]);
+        assert_eq!(secret_key.validate(), Ok(()));
+        assert_eq!(secret_key.validate_with_public_key(&public_key), Ok(()));
+
+        let invalid = |error| Err(Error::InvalidSecretKey(error));
+        assert_eq!(
+            corrupted(&secret_key, |sk| sk[32] ^= 1),
+            invalid(SecretKeyError::Pivots)
+        );
+        assert_eq!(
+            corrupted(&secret_key, |sk| sk[41] |= 0x80),
+            invalid(SecretKeyError::GoppaPolynomial)
+        );
+        // a zero constant coefficient gives the root 0
+        assert_eq!(
+            corrupted(&secret_key, |sk| sk[40..42].fill(0)),
+            invalid(SecretKeyError::GoppaPolynomial)
+        );
+        assert_eq!(
+            corrupted(&secret_key, |sk| sk[40 + irr_bytes] ^= 1),
+            invalid(SecretKeyError::ControlBits)
+        );
+
+        let (other_public_key, _) = keypair_from_seed_boxed::<P>(&[8u8; 32]);
+        assert_eq!(
+            secret_key.validate_with_public_key(&other_public_key),
+            invalid(SecretKeyError::PublicKeyMismatch)
+        );
+    }
+
+    run_with_stack(|| {
+        run::<Mceliece348864>();
+        run::<Mceliece6960119>();
+    });
+}
+
+#[test]
+fn public_key_validation() {
+    use classic_mceliece_rust::generic::{keypair_from_seed_boxed, try_encapsulate_boxed};
+    use classic_mceliece_rust::{ByteArray, Error, Mceliece6960119, PublicKey, PublicKeyError};
+
+    run_with_stack(|| {
+        type P = Mceliece6960119;
+        let mut rng = rand::thread_rng();
+
+        let (public_key, _) = keypair_from_seed_boxed::<P>(&[7u8; 32]);
+        assert_eq!(public_key.validate(), Ok(()));
+
+        let mut bytes = public_key.as_ref().to_vec();
+        assert!(PublicKey::<P>::try_from_bytes(&bytes).is_ok());
+        assert_eq!(
+            PublicKey::<P>::try_from_bytes(&bytes[1..]).unwrap_err(),
+            Error::InvalidLength {
+                variant: P::VARIANT,
+                expected: P::PUBLIC_KEY_BYTES,
+                actual: P::PUBLIC_KEY_BYTES - 1,
+            }
+        );
+
+        // the last bit of the first row is padding
+        bytes[P::PK_ROW_BYTES - 1] |= 0x80;
+        let invalid = Err(Error::InvalidPublicKey(PublicKeyError::Padding));
+        assert_eq!(PublicKey::<P>::try_from_bytes(&bytes).map(|_| ()), invalid);
+
+        let mut pk_buf = <P as ParameterSet>::PublicKeyArray::zeroed_boxed();
+        pk_buf.copy_from_slice(&bytes);
+        let public_key = PublicKey::<P>::from(pk_buf);
+        assert_eq!(public_key.validate(), invalid);
+        assert_eq!(
+            try_encapsulate_boxed(&public_key, &mut rng).map(|_| ()),
+            invalid
+        );
+    });
+}
+
+#[test]
+fn encapsulate_derand_is_deterministic() {
+    use classic_mceliece_rust::generic::{encapsulate_derand_boxed, keypair_from_seed_boxed};
+
+    run_with_stack(|| {
+        let (public_key, secret_key) = keypair_from_seed_boxed::<Mceliece348864>(&[0u8; 32]);
+
+        let (ct1, ss1) = encapsulate_derand_boxed(&public_key, &[1u8; 32]).unwrap();
+        let (ct2, ss2) = encapsulate_derand_boxed(&public_key, &[1u8; 32]).unwrap();
+        assert_eq!(ct1.as_array(), ct2.as_array());
+        assert_eq!(ss1.as_array(), ss2.as_array());
+        // the derivation of the error vector from the coins is part of the API
+        assert_eq!(
+            hex::encode(ss1.as_array()),
+            "df3b8f8ad66d0a9d2d370cf9d02f22f4944f4cf247907301b13c1a82d2e3521c"
+        );
+
+        let (ct3, ss3) = encapsulate_derand_boxed(&public_key, &[2u8; 32]).unwrap();
+        assert_ne!(ct1.as_array(), ct3.as_array());
+        assert_ne!(ss1.as_array(), ss3.as_array());
+
+        let shared_secret_alice = decapsulate_boxed(&ct1, &secret_key);
+        assert_eq!(ss1.as_array(), shared_secret_alice.as_array());
+    });
+}
+
+#[test]
+fn decapsulation_key_matches_secret_key() {
+    use classic_mceliece_rust::{ByteArray, Ciphertext, DecapsulationKey, Error, Mceliece6960119};
+
+    fn run<P: ParameterSet>()
+    where
+        Ciphertext<P>: From<P::CiphertextArray>,
+    {
+        let mut rng = rand::thread_rng();
+        let (public_key, secret_key) = keypair_boxed::<P, _>(&mut rng);
+        let decapsulation_key = Box::new(DecapsulationKey::new(&secret_key));
+
+        for _ in 0..3 {
+            let (ciphertext, shared_secret_bob) = encapsulate_boxed(&public_key, &mut rng);
+            let shared_secret_alice = decapsulation_key.decapsulate_boxed(&ciphertext);
+            assert_eq!(shared_secret_bob.as_array(), shared_secret_alice.as_array());
+
+            // implicit rejection derives the same shared secret as the secret key
+            let mut ct_buf = P::CiphertextArray::zeroed();
+            ct_buf.as_mut().copy_from_slice(ciphertext.as_ref());
+            ct_buf.as_mut()[0] ^= 1;
+            let ciphertext = Ciphertext::<P>::from(ct_buf);
+            let mut ss_buf = [0u8; CRYPTO_BYTES];
+            let rejected = decapsulation_key.decapsulate(&ciphertext, &mut ss_buf);
+            assert_ne!(shared_secret_bob.as_array(), rejected.as_array());
+            assert_eq!(
+                decapsulate_boxed(&ciphertext, &secret_key).as_array(),
+                rejected.as_array()
+            );
+        }
+    }
+
+    run_with_stack(|| {
+        run::<Mceliece348864f>();
+        run::<Mceliece6960119>();
+
+        type P = Mceliece6960119;
+        let mut rng = rand::thread_rng();
+        let (public_key, secret_key) = keypair_boxed::<P, _>(&mut rng);
+        let decapsulation_key = Box::new(DecapsulationKey::new(&secret_key));
+        let (ciphertext, _) = encapsulate_boxed(&public_key, &mut rng);
+        assert!(decapsulation_key.try_decapsulate_boxed(&ciphertext).is_ok());
+
+        // the last bits of the ciphertext are padding
+        let mut ct_buf = *ciphertext.as_array();
+        ct_buf[P::CIPHERTEXT_BYTES - 1] |= 0x80;
+        let result = decapsulation_key.try_decapsulate_boxed(&Ciphertext::<P>::from(ct_buf));
+        assert_eq!(result.err(), Some(Error::InvalidCiphertext));
+    });
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "kat")]
+
+use classic_mceliece_rust::kat;
+use classic_mceliece_rust::Variant;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::{env, fs, thread};
+
+fn run_kat(path: &Path) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_classic-mceliece-kat"))
+        .arg(path)
+        .output()
+        .unwrap()
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!(
+        "classic-mceliece-kat-{}-{name}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn kat_runner_reports_each_count() {
+    let variant = Variant::Mceliece348864;
+    let mut testcases = thread::Builder::new()
+        .stack_size(4 * 1024 * 1024)
+        .spawn(move || kat::respond(variant, &kat::request(2)))
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let dir = temp_dir("counts");
+    fs::write(dir.join("other.rsp"), "# kem/unknown\n\ncount = 0\n").unwrap();
+    fs::write(dir.join("ignored.txt"), "not a response file").unwrap();
+    fs::write(dir.join("good.rsp"), kat::write_rsp(variant, &testcases)).unwrap();
+
+    let output = run_kat(&dir);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(output.status.success(), "{stdout}");
+    assert!(stdout.contains("mceliece348864 count = 0: pass"));
+    assert!(stdout.contains("mceliece348864 count = 1: pass"));
+    assert!(stdout.contains("other.rsp: skipped, unknown variant"));
+    assert!(stdout.contains("1 files, 2 passed, 0 failed"));
+
+    testcases[1].ss[0] ^= 1;
+    let bad = dir.join("bad.rsp");
+    fs::write(&bad, kat::write_rsp(variant, &testcases)).unwrap();
+
+    let output = run_kat(&bad);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(output.status.code(), Some(1), "{stdout}");
+    assert!(stdout.contains("mceliece348864 count = 0: pass"));
+    assert!(stdout.contains("mceliece348864 count = 1: FAIL"));
+    assert!(stdout.contains("1 files, 1 passed, 1 failed"));
+
+    fs::remove_dir_all(dir).unwrap();
+}